@@ -12,7 +12,7 @@ use tachibana::framework::{
 };
 use tachibana::prelude::*;
 
-fn main() {
+fn main() -> Result<(), game::Error> {
     Builder::new()
         .app_name(CString::new("Tachibana").unwrap())
         .window_title("Tachibana")
@@ -27,14 +27,14 @@ fn main() {
                     .boxed(),
                     Rect::new(
                         LayoutSize::min(100.0, 100.0).expand_height_by(3.0),
-                        Paint::new_color4f(0.7, 0.1, 0.2, 0.3).anti_alias(),
+                        Paint::new_color4f(0.7, 0.1, 0.2, 0.3),
                         false,
                     )
                     .boxed(),
                     Transform::new(
                         Rect::new(
                             LayoutSize::min(50.0, 100.0),
-                            Paint::new_color4f(0.0, 0.0, 1.0, 1.0).anti_alias(),
+                            Paint::new_color4f(0.0, 0.0, 1.0, 1.0),
                             false,
                         ),
                         Matrix::translate((20.0, 20.0)),
@@ -44,7 +44,6 @@ fn main() {
                         LayoutDimension::min(100.0),
                         Paint::new_color4f(0.0, 1.0, 0.0, 1.0)
                             .with_stroke_width(12.0)
-                            .anti_alias()
                             .stroke(),
                         false,
                     )
@@ -1,12 +1,30 @@
+pub mod accessibility;
+pub mod assets;
+pub mod audio_device;
+pub mod audio_filter;
+pub mod audio_sync;
+pub mod beat;
+pub mod gst_plugins;
+pub mod lerp;
 pub mod music;
+pub mod music_queue;
+pub mod observable;
+pub mod shortcuts;
+pub mod snapping;
+pub mod sound_fx;
+pub mod spring;
+pub mod task;
+pub mod theme;
+pub mod tween;
 pub mod widgets;
 
 use std::collections::VecDeque;
 
 use crate::prelude::*;
-use game::{Error, Game, InputEvent, State};
-use skia::{Canvas, Color4f, Data, Image, Paint, Point, Size};
-use widgets::{LayoutSize, Widget, Wrap};
+use game::{Error, FocusDirection, Game, InputEvent, Keycode, Mod, State};
+use shortcuts::Shortcuts;
+use skia::{scalar, Canvas, Color4f, Data, Image, ImageFilter, Matrix, Paint, Point, Rect, Size};
+use widgets::{Font, FontStyle, Insets, LayoutSize, Widget, Wrap};
 
 const CURSOR: &[u8] = include_bytes!("resources/cursor.png");
 const CURSORTRAIL: &[u8] = include_bytes!("resources/cursortrail.png");
@@ -15,6 +33,9 @@ pub struct Framework<T: Widget> {
     root: Wrap<T>,
     layout_size: LayoutSize,
     size: Size,
+    window_size: Size,
+    /// The safe area the root was last laid out in
+    safe_area: Insets,
 
     cursor: Image,
     cursor_trail: Image,
@@ -22,6 +43,10 @@ pub struct Framework<T: Widget> {
 
     cursor_scale: f32,
     cursor_fade_time: f32,
+    cursor_extrapolation: bool,
+
+    pub shortcuts: Shortcuts,
+    overlay: Option<Box<dyn FnMut(&mut Canvas) + Send>>,
 }
 
 impl<T: Widget> Framework<T> {
@@ -30,6 +55,8 @@ impl<T: Widget> Framework<T> {
             root: root.into(),
             layout_size: LayoutSize::ZERO,
             size: Size::new_empty(),
+            window_size: Size::new_empty(),
+            safe_area: Insets::uniform(0.0),
 
             cursor: Image::from_encoded(
                 // SAFETY: `Data::new_bytes(ptr)` requires that `ptr` outlives
@@ -47,8 +74,147 @@ impl<T: Widget> Framework<T> {
             cursor_history: VecDeque::new(),
             cursor_scale: 0.5,
             cursor_fade_time: 0.1,
+            cursor_extrapolation: false,
+
+            shortcuts: Shortcuts::new(),
+            overlay: None,
         }
     }
+
+    /// Draw the cursor where the mouse is estimated to be at the time of
+    /// drawing, rather than at the last received mouse position.
+    pub fn with_cursor_extrapolation(mut self, enabled: bool) -> Self {
+        self.cursor_extrapolation = enabled;
+        self
+    }
+
+    pub fn with_shortcuts(mut self, shortcuts: Shortcuts) -> Self {
+        self.shortcuts = shortcuts;
+        self
+    }
+
+    /// Draw on top of the widgets, but below the cursor, without adding a
+    /// widget to the tree. Handy for a frame rate counter or a debug HUD.
+    pub fn with_overlay(mut self, f: impl FnMut(&mut Canvas) + Send + 'static) -> Self {
+        self.overlay = Some(Box::new(f));
+        self
+    }
+}
+
+impl<T: Widget> Framework<T> {
+    /// Lay the root out in the window, minus the safe area
+    fn layout(&mut self) {
+        let s = self.safe_area;
+        let available = Size::new(
+            (self.window_size.width - s.left - s.right).max(0.0),
+            (self.window_size.height - s.top - s.bottom).max(0.0),
+        );
+        self.size = Size::new(
+            self.layout_size.width.min.max(available.width),
+            self.layout_size.height.min.max(available.height),
+        );
+        self.root.set_size(self.size);
+    }
+
+    fn root_matrix(&self) -> Matrix {
+        Matrix::translate((self.safe_area.left, self.safe_area.top))
+    }
+
+    /// Draw [`DebugOptions::grid`](game::DebugOptions::grid) and
+    /// [`DebugOptions::rulers`](game::DebugOptions::rulers)
+    fn draw_grid(canvas: &mut Canvas, size: Size, grid: Option<scalar>, rulers: bool) {
+        if let Some(spacing) = grid.filter(|s| *s >= 1.0) {
+            let line = Paint::new_color4f(0.0, 1.0, 1.0, 0.25);
+            let mut x = spacing;
+            while x < size.width {
+                canvas.draw_line((x, 0.0), (x, size.height), &line);
+                x += spacing;
+            }
+            let mut y = spacing;
+            while y < size.height {
+                canvas.draw_line((0.0, y), (size.width, y), &line);
+                y += spacing;
+            }
+        }
+        if !rulers {
+            return;
+        }
+        let thickness = 16.0;
+        let background = Paint::new_color4f(0.0, 0.0, 0.0, 0.7);
+        canvas.draw_rect(Rect::from_wh(size.width, thickness), &background);
+        canvas.draw_rect(Rect::from_wh(thickness, size.height), &background);
+        let ink = Paint::new_color4f(0.0, 1.0, 1.0, 0.9);
+        let mut font = Font::Default.resolve(&FontStyle::Regular);
+        font.set_size(9.0);
+        // A tick every 10 pixels, a longer one every 50 and a label every
+        // 100
+        let tick = |i: i32| match i % 10 {
+            0 => thickness,
+            5 => thickness * 0.5,
+            _ => thickness * 0.25,
+        };
+        for i in 1..=(size.width / 10.0) as i32 {
+            let x = i as scalar * 10.0;
+            canvas.draw_line((x, thickness - tick(i)), (x, thickness), &ink);
+            if i % 10 == 0 {
+                canvas.draw_str(format!("{}", i * 10), (x + 2.0, 9.0), &font, &ink);
+            }
+        }
+        for i in 1..=(size.height / 10.0) as i32 {
+            let y = i as scalar * 10.0;
+            canvas.draw_line((thickness - tick(i), y), (thickness, y), &ink);
+            if i % 10 == 0 {
+                canvas.save();
+                canvas.rotate(-90.0, Some(Point::new(9.0, y - 2.0)));
+                canvas.draw_str(format!("{}", i * 10), (9.0, y - 2.0), &font, &ink);
+                canvas.restore();
+            }
+        }
+    }
+
+    /// Move keyboard focus between [`Focusable`](widgets::Focusable)
+    /// widgets with keys the widgets themselves didn't use, returning
+    /// whether the key was used. Tab and the arrow keys only count when
+    /// something is focusable, and Escape only when it clears a focus, so
+    /// that the game can still use them.
+    fn navigate(key: Keycode) -> bool {
+        State::with_mut(|x| match key {
+            Keycode::Tab | Keycode::Up | Keycode::Down | Keycode::Left | Keycode::Right
+                if !x.focus.has_widgets() =>
+            {
+                false
+            }
+            Keycode::Tab => {
+                let shift = x
+                    .input_state
+                    .modifiers
+                    .intersects(Mod::LSHIFTMOD | Mod::RSHIFTMOD);
+                x.focus.next(shift);
+                true
+            }
+            Keycode::Up => {
+                x.focus.towards(FocusDirection::Up);
+                true
+            }
+            Keycode::Down => {
+                x.focus.towards(FocusDirection::Down);
+                true
+            }
+            Keycode::Left => {
+                x.focus.towards(FocusDirection::Left);
+                true
+            }
+            Keycode::Right => {
+                x.focus.towards(FocusDirection::Right);
+                true
+            }
+            Keycode::Escape if x.focus.focused().is_some() => {
+                x.focus.set(None, false);
+                true
+            }
+            _ => false,
+        })
+    }
 }
 
 impl<T: Widget> Game for Framework<T> {
@@ -66,15 +232,52 @@ impl<T: Widget> Game for Framework<T> {
 
     fn draw(&mut self, canvas: &mut Canvas) {
         let (size, changed) = self.root.size();
-        if size != self.layout_size || changed {
+        let safe_area = State::safe_area();
+        if size != self.layout_size || changed || safe_area != self.safe_area {
             self.layout_size = size;
-            self.root.set_size(self.size);
+            self.safe_area = safe_area;
+            self.layout();
         }
+        canvas.save();
+        canvas.concat(&self.root_matrix());
         self.root.draw(canvas);
+        canvas.restore();
+    }
+
+    fn post_process(&mut self) -> Option<ImageFilter> {
+        State::frame_filter()
+    }
+
+    fn draw_overlay(&mut self, canvas: &mut Canvas) {
+        let debug = State::debug();
+        if debug.grid.is_some() || debug.rulers {
+            let size = State::with(|x| x.input_state.canvas_size());
+            Self::draw_grid(canvas, size, debug.grid, debug.rulers);
+        }
+        if let Some(overlay) = &mut self.overlay {
+            overlay(canvas);
+        }
+        if let Some(options) = debug.pointer_trail {
+            let now = State::elapsed();
+            // Taken out of the state while drawing, as the paint looks up
+            // the theme
+            let mut trail = State::with_mut(|x| std::mem::take(&mut x.pointer_trail));
+            if trail.draw(canvas, now, &options) {
+                State::request_redraw();
+            }
+            State::with_mut(|x| x.pointer_trail = trail);
+        }
+        if State::cursor().is_some() {
+            return;
+        }
 
         let scale = self.cursor_scale;
         let scale_inv = 1.0 / scale;
-        let mouse_pos = State::mouse_position() * scale_inv;
+        let mouse_pos = if self.cursor_extrapolation {
+            State::extrapolated_mouse_position()
+        } else {
+            State::mouse_position()
+        } * scale_inv;
         let cursor_mid = self.cursor.dimensions().center();
         let trail_mid: Point = self.cursor_trail.dimensions().center();
         canvas.save();
@@ -96,24 +299,34 @@ impl<T: Widget> Game for Framework<T> {
     }
 
     fn set_size(&mut self, window_size: Size) {
-        self.size = Size::new(
-            self.layout_size.width.min.max(window_size.width),
-            self.layout_size.height.min.max(window_size.height),
-        );
-        self.root.set_size(self.size);
+        self.window_size = window_size;
+        self.safe_area = State::safe_area();
+        self.layout();
     }
 
-    fn input(&mut self, event: InputEvent) {
-        self.root.input(&event);
-        if let InputEvent::MouseMove(pos) = event {
-            self.cursor_history
-                .push_back((pos, State::elapsed().as_secs_f32()))
+    fn input(&mut self, event: InputEvent) -> bool {
+        if let InputEvent::KeyDown(key, _) = event {
+            if self.shortcuts.trigger(key) {
+                return true;
+            }
+        }
+        let mut handled = event
+            .reverse_map_position(self.root_matrix())
+            .map_or(false, |event| self.root.input(&event));
+        if let InputEvent::KeyDown(key, _) = event {
+            if !handled {
+                handled = Self::navigate(key);
+            }
+        }
+        if let InputEvent::MouseMove(pos, time) = event {
+            self.cursor_history.push_back((pos, time.as_secs_f32()))
         }
+        handled
     }
 
     fn close(&mut self) {}
 
     fn crash(&mut self, err: Error) {
-        println!("Tachibana has crashed!\nMore info: {:?}", err);
+        println!("Tachibana has crashed: {}\nMore info: {:?}", err, err);
     }
 }
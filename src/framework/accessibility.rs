@@ -0,0 +1,191 @@
+//! Semantic information about widgets for assistive technology.
+//!
+//! Widgets are annotated with a [`Role`] and a label through
+//! [`Wrap::with_accessibility`](super::widgets::Wrap::with_accessibility).
+//! Every frame, the annotated widgets that are drawn are collected into a
+//! tree, which mirrors the nesting of the annotated widgets and leaves out
+//! everything else. The last complete tree is available through
+//! [`State::accessibility_tree`](crate::game::State::accessibility_tree).
+
+use std::fmt::Write;
+
+use crate::game::ID;
+use crate::skia::Rect;
+
+/// What kind of control or content a widget is
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Role {
+    Button,
+    CheckBox,
+    Dialog,
+    Group,
+    Heading,
+    Image,
+    Link,
+    List,
+    ListItem,
+    ProgressBar,
+    ScrollView,
+    Slider,
+    Text,
+    TextInput,
+    /// Anything not covered by the other roles
+    Other(String),
+}
+
+impl Role {
+    pub fn name(&self) -> &str {
+        match self {
+            Role::Button => "button",
+            Role::CheckBox => "checkbox",
+            Role::Dialog => "dialog",
+            Role::Group => "group",
+            Role::Heading => "heading",
+            Role::Image => "image",
+            Role::Link => "link",
+            Role::List => "list",
+            Role::ListItem => "listitem",
+            Role::ProgressBar => "progressbar",
+            Role::ScrollView => "scrollview",
+            Role::Slider => "slider",
+            Role::Text => "text",
+            Role::TextInput => "textinput",
+            Role::Other(name) => name,
+        }
+    }
+}
+
+/// The accessibility annotation of a widget
+#[derive(Debug, Clone, PartialEq)]
+pub struct Accessibility {
+    pub role: Role,
+    /// What the widget is called, like "Play" for a play button
+    pub label: String,
+}
+
+impl Accessibility {
+    pub fn new(role: Role, label: impl Into<String>) -> Self {
+        Self {
+            role,
+            label: label.into(),
+        }
+    }
+}
+
+/// An annotated widget in the accessibility tree
+#[derive(Debug, Clone, PartialEq)]
+pub struct AccessNode {
+    pub id: ID,
+    pub role: Role,
+    pub label: String,
+    /// Where the widget was drawn, in canvas coordinates
+    pub bounds: Rect,
+    pub children: Vec<AccessNode>,
+}
+
+impl AccessNode {
+    /// Calls `f` on this node and all of its descendants, parents first
+    pub fn walk(&self, f: &mut impl FnMut(&AccessNode)) {
+        f(self);
+        for child in &self.children {
+            child.walk(f);
+        }
+    }
+
+    fn write_json(&self, out: &mut String) {
+        let b = &self.bounds;
+        out.push_str("{\"role\":");
+        write_json_str(out, self.role.name());
+        out.push_str(",\"label\":");
+        write_json_str(out, &self.label);
+        let _ = write!(
+            out,
+            ",\"bounds\":[{},{},{},{}],\"children\":[",
+            b.left, b.top, b.right, b.bottom
+        );
+        for (i, child) in self.children.iter().enumerate() {
+            if i > 0 {
+                out.push(',');
+            }
+            child.write_json(out);
+        }
+        out.push_str("]}");
+    }
+}
+
+/// Serialize a tree to JSON, as an array of its root nodes. Node IDs are
+/// left out since they differ between runs.
+pub fn to_json(nodes: &[AccessNode]) -> String {
+    let mut out = String::from("[");
+    for (i, node) in nodes.iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        node.write_json(&mut out);
+    }
+    out.push(']');
+    out
+}
+
+fn write_json_str(out: &mut String, s: &str) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+/// Builds the accessibility tree as widgets are drawn
+#[derive(Default)]
+pub(crate) struct AccessRecorder {
+    open: Vec<AccessNode>,
+    roots: Vec<AccessNode>,
+    last: Vec<AccessNode>,
+}
+
+impl AccessRecorder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start a node for a widget that is about to draw its children
+    pub fn open(&mut self, id: ID, accessibility: &Accessibility, bounds: Rect) {
+        self.open.push(AccessNode {
+            id,
+            role: accessibility.role.clone(),
+            label: accessibility.label.clone(),
+            bounds,
+            children: Vec::new(),
+        });
+    }
+
+    /// Finish the node started last, once its widget is done drawing
+    pub fn close(&mut self) {
+        if let Some(node) = self.open.pop() {
+            match self.open.last_mut() {
+                Some(parent) => parent.children.push(node),
+                None => self.roots.push(node),
+            }
+        }
+    }
+
+    pub fn next_frame(&mut self) {
+        self.open.clear();
+        self.last = std::mem::take(&mut self.roots);
+    }
+
+    /// The tree of the last complete frame
+    pub fn tree(&self) -> &[AccessNode] {
+        &self.last
+    }
+}
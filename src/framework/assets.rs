@@ -0,0 +1,162 @@
+use core::fmt::{Display, Formatter, Result as FmtResult};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::error::Error as StdError;
+use std::fs;
+use std::io::Error as IoError;
+use std::path::{Path, PathBuf};
+
+use crate::skia::{Data, Image, Typeface};
+
+#[derive(Debug)]
+pub enum AssetError {
+    Io(IoError),
+    /// The file was read, but could not be decoded
+    Decode(PathBuf),
+}
+
+impl Display for AssetError {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match self {
+            AssetError::Io(e) => e.fmt(f),
+            AssetError::Decode(path) => write!(f, "Failed to decode {}", path.display()),
+        }
+    }
+}
+
+impl StdError for AssetError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match self {
+            AssetError::Io(e) => Some(e),
+            AssetError::Decode(_) => None,
+        }
+    }
+}
+
+impl From<IoError> for AssetError {
+    fn from(e: IoError) -> Self {
+        AssetError::Io(e)
+    }
+}
+
+struct CachedImage {
+    image: Image,
+    bytes: usize,
+    last_used: u64,
+}
+
+/// Decoded images and loaded typefaces, shared by path for the whole game
+/// thread.
+///
+/// The returned handles are reference counted by Skia, so every widget
+/// loading the same file shares a single copy. Images are evicted least
+/// recently used first once their decoded size exceeds the budget; evicted
+/// images stay alive for as long as someone still holds them. Typefaces are
+/// small and are kept until [`clear`](Self::clear) is called.
+pub struct AssetCache {
+    images: HashMap<PathBuf, CachedImage>,
+    typefaces: HashMap<PathBuf, Typeface>,
+    budget: usize,
+    used: usize,
+    clock: u64,
+}
+
+impl AssetCache {
+    pub const DEFAULT_BUDGET: usize = 256 * 1024 * 1024;
+
+    thread_local!(static CACHE: RefCell<AssetCache> = RefCell::new(AssetCache::new()));
+
+    fn new() -> Self {
+        Self {
+            images: HashMap::new(),
+            typefaces: HashMap::new(),
+            budget: Self::DEFAULT_BUDGET,
+            used: 0,
+            clock: 0,
+        }
+    }
+
+    fn with<R>(f: impl FnOnce(&mut AssetCache) -> R) -> R {
+        Self::CACHE.with(|x| f(&mut x.borrow_mut()))
+    }
+
+    /// Load an image, decoding it only if it isn't cached yet
+    pub fn image(path: impl AsRef<Path>) -> Result<Image, AssetError> {
+        let path = path.as_ref();
+        Self::with(|cache| {
+            cache.clock += 1;
+            if let Some(cached) = cache.images.get_mut(path) {
+                cached.last_used = cache.clock;
+                return Ok(cached.image.clone());
+            }
+            let data = Data::new_copy(&fs::read(path)?);
+            let image =
+                Image::from_encoded(data).ok_or_else(|| AssetError::Decode(path.to_owned()))?;
+            let bytes = image.width() as usize * image.height() as usize * 4;
+            cache.used += bytes;
+            cache.images.insert(
+                path.to_owned(),
+                CachedImage {
+                    image: image.clone(),
+                    bytes,
+                    last_used: cache.clock,
+                },
+            );
+            cache.evict();
+            Ok(image)
+        })
+    }
+
+    /// Load a typeface from a font file, reading it only if it isn't cached yet
+    pub fn typeface(path: impl AsRef<Path>) -> Result<Typeface, AssetError> {
+        let path = path.as_ref();
+        Self::with(|cache| {
+            if let Some(typeface) = cache.typefaces.get(path) {
+                return Ok(typeface.clone());
+            }
+            let data = Data::new_copy(&fs::read(path)?);
+            let typeface = Typeface::from_data(data, None)
+                .ok_or_else(|| AssetError::Decode(path.to_owned()))?;
+            cache.typefaces.insert(path.to_owned(), typeface.clone());
+            Ok(typeface)
+        })
+    }
+
+    /// Set the most decoded image data to keep around, in bytes
+    pub fn set_budget(budget: usize) {
+        Self::with(|cache| {
+            cache.budget = budget;
+            cache.evict();
+        });
+    }
+
+    /// The size of all cached images, in bytes
+    pub fn used() -> usize {
+        Self::with(|cache| cache.used)
+    }
+
+    /// Forget everything cached
+    pub fn clear() {
+        Self::with(|cache| {
+            cache.images.clear();
+            cache.typefaces.clear();
+            cache.used = 0;
+        });
+    }
+
+    fn evict(&mut self) {
+        // The most recently used image is never evicted, even if it alone is
+        // over budget, since it was most likely just loaded to be used.
+        while self.used > self.budget && self.images.len() > 1 {
+            let oldest = self
+                .images
+                .iter()
+                .min_by_key(|(_, x)| x.last_used)
+                .map(|(path, _)| path.clone())
+                .unwrap();
+            if let Some(x) = self.images.remove(&oldest) {
+                self.used -= x.bytes;
+            }
+        }
+    }
+}
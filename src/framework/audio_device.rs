@@ -0,0 +1,145 @@
+use std::cell::{Cell, RefCell};
+use std::time::Duration;
+
+use gstreamer::{glib::BoolError, prelude::*, Bin, Device, DeviceMonitor, Element};
+
+thread_local! {
+    static DEFAULT: RefCell<Option<AudioDevice>> = RefCell::new(None);
+    static DEFAULT_BUFFER: Cell<AudioBuffer> = Cell::new(AudioBuffer::default());
+}
+
+/// How much audio an output keeps queued up ahead of what is heard.
+///
+/// A longer buffer gives the decoder more slack before the output runs dry,
+/// which is heard as crackling or dropouts on a busy or slow system, but
+/// everything, like a sound effect on a hit or a change of volume, reaches
+/// the speakers that much later. Shorter chunks react faster as well, at the
+/// cost of waking up more often to write them. The defaults are GStreamer's,
+/// 200ms split into 10ms chunks; rhythm games may want to go lower, while
+/// music players can afford a lot more.
+///
+/// Only outputs built on GStreamer's audio sink base class, which covers
+/// PulseAudio, ALSA, WASAPI and the other usual ones, take these settings;
+/// others ignore them.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct AudioBuffer {
+    /// The total length of audio queued up in the output
+    pub buffer_time: Duration,
+    /// The length of each chunk written to the output, at most `buffer_time`
+    pub latency_time: Duration,
+}
+
+impl AudioBuffer {
+    pub fn new(buffer_time: Duration, latency_time: Duration) -> Self {
+        Self {
+            buffer_time,
+            latency_time,
+        }
+    }
+
+    /// The buffer new [`Music`](super::music::Music) and
+    /// [`SoundFx`](super::sound_fx::SoundFx) are made with
+    pub fn default_buffer() -> AudioBuffer {
+        DEFAULT_BUFFER.with(|x| x.get())
+    }
+
+    pub fn set_default_buffer(buffer: AudioBuffer) {
+        DEFAULT_BUFFER.with(|x| x.set(buffer));
+    }
+
+    /// Set the buffer on a sink if it has the properties for it
+    fn apply(&self, sink: &Element) {
+        let latency_time = self.latency_time.min(self.buffer_time);
+        let properties = [
+            ("buffer-time", self.buffer_time),
+            ("latency-time", latency_time),
+        ];
+        for &(name, time) in properties.iter() {
+            if sink.find_property(name).is_some() {
+                if let Err(e) = sink.set_property(name, &(time.as_micros() as i64)) {
+                    eprintln!("Failed to set {} on the audio sink: {}", name, e);
+                }
+            }
+        }
+    }
+}
+
+impl Default for AudioBuffer {
+    fn default() -> Self {
+        Self::new(Duration::from_millis(200), Duration::from_millis(10))
+    }
+}
+
+/// An audio output, like a pair of headphones or the speakers.
+#[derive(Debug, Clone)]
+pub struct AudioDevice {
+    name: String,
+    device: Device,
+}
+
+impl AudioDevice {
+    /// List the audio outputs currently available on the system
+    pub fn all() -> Vec<AudioDevice> {
+        let monitor = DeviceMonitor::new();
+        monitor.add_filter(Some("Audio/Sink"), None);
+        if monitor.start().is_err() {
+            return Vec::new();
+        }
+        let devices = monitor
+            .get_devices()
+            .into_iter()
+            .map(|device| AudioDevice {
+                name: device.get_display_name().to_string(),
+                device,
+            })
+            .collect();
+        monitor.stop();
+        devices
+    }
+
+    /// A human readable name for the device
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The device new [`Music`](super::music::Music) and
+    /// [`SoundFx`](super::sound_fx::SoundFx) play on. `None` means the
+    /// system's default output, which is also what is used until this is
+    /// set.
+    pub fn default_output() -> Option<AudioDevice> {
+        DEFAULT.with(|x| x.borrow().clone())
+    }
+
+    pub fn set_default_output(device: Option<AudioDevice>) {
+        DEFAULT.with(|x| *x.borrow_mut() = device);
+    }
+
+    pub(crate) fn create_sink(&self) -> Result<Element, BoolError> {
+        self.device.create_element(None)
+    }
+
+    /// Make a sink for `device`, or for the system's default output if it's
+    /// `None`, with `buffer` applied.
+    pub(crate) fn sink_for(
+        device: Option<&AudioDevice>,
+        buffer: AudioBuffer,
+    ) -> Result<Element, BoolError> {
+        let sink = match device {
+            Some(device) => device.create_sink()?,
+            None => gstreamer::ElementFactory::make("autoaudiosink", None)?,
+        };
+        buffer.apply(&sink);
+        // The automatic sink only picks the actual output once it starts, so
+        // the buffer has to be set on that once it is added.
+        if let Some(bin) = sink.downcast_ref::<Bin>() {
+            bin.connect_element_added(move |_, element| buffer.apply(element));
+        }
+        Ok(sink)
+    }
+}
+
+impl PartialEq for AudioDevice {
+    fn eq(&self, other: &Self) -> bool {
+        self.device == other.device
+    }
+}
@@ -0,0 +1,111 @@
+use gstreamer::{glib, prelude::*, ChildProxy, Element};
+
+/// Which filters a [`Music`](super::music::Music) plays through, picked when
+/// it is created. Their settings can be changed while playing.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct FilterChain {
+    /// The number of equalizer bands, or 0 for no equalizer. The bands are
+    /// spread evenly over the audible range on a logarithmic scale.
+    pub bands: usize,
+    /// Initial cutoff of a low-pass filter, in Hz
+    pub low_pass: Option<f32>,
+    /// Initial cutoff of a high-pass filter, in Hz
+    pub high_pass: Option<f32>,
+}
+
+impl FilterChain {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_equalizer(mut self, bands: usize) -> Self {
+        self.bands = bands;
+        self
+    }
+
+    pub fn with_low_pass(mut self, cutoff: f32) -> Self {
+        self.low_pass = Some(cutoff);
+        self
+    }
+
+    pub fn with_high_pass(mut self, cutoff: f32) -> Self {
+        self.high_pass = Some(cutoff);
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bands == 0 && self.low_pass.is_none() && self.high_pass.is_none()
+    }
+
+    /// The GStreamer elements the chain is built from
+    pub fn elements(&self) -> Vec<&'static str> {
+        let mut elements = vec!["audioconvert", "audiopanorama"];
+        if self.bands > 0 {
+            elements.push("equalizer-nbands");
+        }
+        if self.low_pass.is_some() || self.high_pass.is_some() {
+            elements.push("audiocheblimit");
+        }
+        elements
+    }
+
+    fn description(&self) -> String {
+        let mut description = String::from("audioconvert");
+        if self.bands > 0 {
+            description += &format!(" ! equalizer-nbands name=eq num-bands={}", self.bands);
+        }
+        if let Some(cutoff) = self.high_pass {
+            description += &format!(
+                " ! audiocheblimit name=highpass mode=high-pass poles=4 cutoff={}",
+                cutoff
+            );
+        }
+        if let Some(cutoff) = self.low_pass {
+            description += &format!(
+                " ! audiocheblimit name=lowpass mode=low-pass poles=4 cutoff={}",
+                cutoff
+            );
+        }
+        description + " ! audiopanorama name=pan ! audioconvert"
+    }
+}
+
+/// The elements of a built [`FilterChain`]
+pub(crate) struct Filters {
+    pub bin: Element,
+    pub equalizer: Option<Element>,
+    pub low_pass: Option<Element>,
+    pub high_pass: Option<Element>,
+    pub panorama: Option<Element>,
+}
+
+impl Filters {
+    pub fn build(chain: &FilterChain) -> Result<Self, glib::Error> {
+        let bin = gstreamer::parse_bin_from_description(&chain.description(), true)?;
+        let get = |name| bin.get_by_name(name);
+        Ok(Self {
+            equalizer: get("eq"),
+            low_pass: get("lowpass"),
+            high_pass: get("highpass"),
+            panorama: get("pan"),
+            bin: bin.upcast(),
+        })
+    }
+
+    /// The number of equalizer bands
+    pub fn band_count(&self) -> usize {
+        self.equalizer
+            .as_ref()
+            .and_then(|eq| eq.dynamic_cast_ref::<ChildProxy>())
+            .map_or(0, |eq| eq.get_children_count() as usize)
+    }
+
+    /// The equalizer band at `index`, which has `freq`, `bandwidth` and
+    /// `gain` properties
+    pub fn band(&self, index: usize) -> Option<glib::Object> {
+        self.equalizer
+            .as_ref()?
+            .dynamic_cast_ref::<ChildProxy>()?
+            .get_child_by_index(index as u32)
+    }
+}
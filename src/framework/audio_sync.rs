@@ -0,0 +1,115 @@
+//! Keeping visuals in sync with audio that reaches the speakers late.
+//!
+//! Sound passes through buffers in GStreamer, the OS and the device before it
+//! is heard, so the position a [`Music`](super::music::Music) reports is a
+//! little ahead of what is audible. The audio offset is that latency, and is
+//! subtracted by [`Music::visual_position`](super::music::Music::visual_position).
+
+use std::cell::Cell;
+use std::time::Duration;
+
+use crate::game::State;
+use serde::{Deserialize, Serialize};
+
+thread_local! {
+    static OFFSET: Cell<Duration> = Cell::new(Duration::ZERO);
+}
+
+/// The current audio offset, zero unless set
+pub fn audio_offset() -> Duration {
+    OFFSET.with(|x| x.get())
+}
+
+pub fn set_audio_offset(offset: Duration) {
+    OFFSET.with(|x| x.set(offset));
+}
+
+/// The audio sync settings in a form that can be stored with
+/// [`settings`](crate::utils::settings), usually as part of the app's own
+/// settings struct.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AudioSyncSettings {
+    pub audio_offset_ms: u64,
+}
+
+impl AudioSyncSettings {
+    /// The settings as currently in effect
+    pub fn current() -> Self {
+        Self {
+            audio_offset_ms: audio_offset().as_millis() as u64,
+        }
+    }
+
+    pub fn apply(&self) {
+        set_audio_offset(Duration::from_millis(self.audio_offset_ms));
+    }
+}
+
+/// Measures the audio offset by having the user tap along to a steady click.
+///
+/// Each update, check [`click_due`](Self::click_due) and play a short sound
+/// when it returns true, then call [`tap`](Self::tap) whenever the user
+/// presses a key. The median delay between clicks and taps is the offset.
+#[derive(Debug, Clone)]
+pub struct Calibration {
+    interval: Duration,
+    next_click: Duration,
+    clicks: Vec<Duration>,
+    delays: Vec<Duration>,
+}
+
+impl Calibration {
+    /// Click every `interval`, starting right away
+    pub fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+            next_click: State::elapsed(),
+            clicks: Vec::new(),
+            delays: Vec::new(),
+        }
+    }
+
+    /// Whether it's time to play the next click
+    pub fn click_due(&mut self) -> bool {
+        let now = State::elapsed();
+        if now < self.next_click {
+            return false;
+        }
+        self.clicks.push(now);
+        self.next_click = now + self.interval;
+        true
+    }
+
+    /// Record that the user tapped along with a click
+    pub fn tap(&mut self) {
+        let now = State::elapsed();
+        // Taps are matched to the click they are closest to after, as long as
+        // that is within half an interval, so a stray tap doesn't count.
+        if let Some(&click) = self.clicks.iter().rev().find(|&&c| c <= now) {
+            let delay = now - click;
+            if delay < self.interval / 2 {
+                self.delays.push(delay);
+            }
+        }
+    }
+
+    /// The number of taps that counted so far
+    pub fn taps(&self) -> usize {
+        self.delays.len()
+    }
+
+    /// The measured offset, once there has been at least one tap
+    pub fn offset(&self) -> Option<Duration> {
+        let mut delays = self.delays.clone();
+        delays.sort();
+        delays.get(delays.len() / 2).copied()
+    }
+
+    /// Use the measured offset from now on, returning it
+    pub fn apply(&self) -> Option<Duration> {
+        let offset = self.offset()?;
+        set_audio_offset(offset);
+        Some(offset)
+    }
+}
@@ -0,0 +1,250 @@
+use core::fmt::{Display, Formatter, Result as FmtResult};
+use std::error::Error as StdError;
+use std::io::Error as IoError;
+use std::path::Path;
+use std::time::Duration;
+
+use crate::game::State;
+use gstreamer::{
+    glib::{self, BoolError},
+    prelude::*,
+    ClockTime, MessageView, State as GstState, StateChangeError,
+};
+
+/// How often the loudness is sampled while analyzing
+const INTERVAL: Duration = Duration::from_millis(10);
+/// Beats closer together than this are counted as one, which caps the
+/// tempo at 240 BPM
+const MIN_GAP: Duration = Duration::from_millis(250);
+/// How many samples on each side are averaged to decide whether a rise in
+/// loudness stands out
+const WINDOW: usize = 50;
+/// How far a rise has to stand out above the average rise to be a beat
+const THRESHOLD: f32 = 1.5;
+
+/// The beats of a track, found ahead of time by looking for sudden rises in
+/// loudness.
+///
+/// Analysis decodes the whole file as fast as possible, which takes a while
+/// for long tracks, so it's best done in the background through
+/// [`State::spawn_task`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct BeatMap {
+    /// Positions of the beats in the track, in order
+    pub beats: Vec<Duration>,
+}
+
+impl BeatMap {
+    pub fn analyze<P: AsRef<Path>>(path: P) -> Result<Self, BeatError> {
+        let path = path.as_ref().canonicalize()?;
+        let uri = "file:///".to_owned() + path.to_str().ok_or(BeatError::InvalidPath)?;
+        let pipeline = gstreamer::parse_launch(&format!(
+            "uridecodebin uri=\"{}\" ! audioconvert ! level interval={} post-messages=true ! fakesink sync=false",
+            uri,
+            INTERVAL.as_nanos()
+        ))?;
+        let result = Self::collect_levels(&pipeline);
+        let _ = pipeline.set_state(GstState::Null);
+        Ok(Self {
+            beats: Self::detect(&result?),
+        })
+    }
+
+    /// Run the pipeline to the end, returning the loudness at each point in
+    /// time
+    fn collect_levels(pipeline: &gstreamer::Element) -> Result<Vec<(Duration, f32)>, BeatError> {
+        let bus = pipeline.get_bus().ok_or(BeatError::Decode(None))?;
+        pipeline.set_state(GstState::Playing)?;
+        let mut levels = Vec::new();
+        for msg in bus.iter_timed(ClockTime::none()) {
+            match msg.view() {
+                MessageView::Element(element) => {
+                    let s = match element.get_structure() {
+                        Some(s) if s.get_name() == "level" => s,
+                        _ => continue,
+                    };
+                    let time = match s.get_some::<u64>("stream-time") {
+                        Ok(time) => Duration::from_nanos(time),
+                        Err(_) => continue,
+                    };
+                    // RMS is given in decibels for each channel, which are
+                    // averaged as amplitudes.
+                    let rms = match s.get::<glib::ValueArray>("rms") {
+                        Ok(Some(rms)) => rms,
+                        _ => continue,
+                    };
+                    let channels: Vec<f32> = rms
+                        .iter()
+                        .filter_map(|v| v.get_some::<f64>().ok())
+                        .map(|db| 10f64.powf(db / 20.0) as f32)
+                        .collect();
+                    if !channels.is_empty() {
+                        let level = channels.iter().sum::<f32>() / channels.len() as f32;
+                        levels.push((time, level));
+                    }
+                }
+                MessageView::Eos(..) => break,
+                MessageView::Error(err) => return Err(BeatError::Decode(err.get_debug())),
+                _ => {}
+            }
+        }
+        Ok(levels)
+    }
+
+    fn detect(levels: &[(Duration, f32)]) -> Vec<Duration> {
+        let flux: Vec<f32> = levels
+            .windows(2)
+            .map(|w| (w[1].1 - w[0].1).max(0.0))
+            .collect();
+        let mut beats: Vec<Duration> = Vec::new();
+        for (i, &f) in flux.iter().enumerate() {
+            let window = &flux[i.saturating_sub(WINDOW)..(i + WINDOW + 1).min(flux.len())];
+            let average = window.iter().sum::<f32>() / window.len() as f32;
+            let is_peak = (i == 0 || flux[i - 1] <= f) && flux.get(i + 1).map_or(true, |&n| n < f);
+            if !is_peak || f <= average * THRESHOLD || f <= f32::EPSILON {
+                continue;
+            }
+            let time = levels[i + 1].0;
+            if beats.last().map_or(true, |&last| time - last >= MIN_GAP) {
+                beats.push(time);
+            }
+        }
+        beats
+    }
+
+    /// The tempo in beats per minute, from the typical gap between beats
+    pub fn bpm(&self) -> Option<f32> {
+        let mut gaps: Vec<Duration> = self.beats.windows(2).map(|w| w[1] - w[0]).collect();
+        if gaps.is_empty() {
+            return None;
+        }
+        gaps.sort();
+        Some(60.0 / gaps[gaps.len() / 2].as_secs_f32())
+    }
+}
+
+/// A beat that a [`BeatTracker`] has passed
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Beat {
+    /// Which beat of the track this is, from 0
+    pub index: usize,
+    /// Where in the track the beat is
+    pub position: Duration,
+    /// When the beat played, in terms of [`State::elapsed`]
+    pub time: Duration,
+}
+
+/// Follows a track's playback position through its [`BeatMap`], producing
+/// the beats that have been passed since the last update.
+#[derive(Debug, Clone)]
+pub struct BeatTracker {
+    pub map: BeatMap,
+    next: usize,
+    last_position: Duration,
+}
+
+impl BeatTracker {
+    pub fn new(map: BeatMap) -> Self {
+        Self {
+            map,
+            next: 0,
+            last_position: Duration::ZERO,
+        }
+    }
+
+    /// Give the current playback position, usually
+    /// [`Music::visual_position`](super::music::Music::visual_position), and
+    /// get the beats passed since the last call. Seeking backwards starts
+    /// over from the new position without producing beats.
+    pub fn update(&mut self, position: Duration) -> Vec<Beat> {
+        let beats = &self.map.beats;
+        if position < self.last_position {
+            self.next = match beats.binary_search(&position) {
+                Ok(i) | Err(i) => i,
+            };
+            self.last_position = position;
+            return Vec::new();
+        }
+        self.last_position = position;
+
+        let now = State::elapsed();
+        let mut passed = Vec::new();
+        while let Some(&beat) = beats.get(self.next) {
+            if beat > position {
+                break;
+            }
+            passed.push(Beat {
+                index: self.next,
+                position: beat,
+                time: now.checked_sub(position - beat).unwrap_or_default(),
+            });
+            self.next += 1;
+        }
+        passed
+    }
+
+    /// How far along the current beat is, from 0 right on a beat to almost 1
+    /// just before the next one, for pulsing visuals
+    pub fn phase(&self, position: Duration) -> f32 {
+        let beats = &self.map.beats;
+        let i = match beats.binary_search(&position) {
+            Ok(_) => return 0.0,
+            Err(i) => i,
+        };
+        match (i.checked_sub(1).and_then(|i| beats.get(i)), beats.get(i)) {
+            (Some(&from), Some(&to)) => (position - from).as_secs_f32() / (to - from).as_secs_f32(),
+            _ => 0.0,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum BeatError {
+    GStreamerError(glib::Error),
+    GStreamerBoolError(BoolError),
+    GStreamerStateChangeError(StateChangeError),
+    IoError(IoError),
+    InvalidPath,
+    /// The file could not be decoded, with GStreamer's debug info if any
+    Decode(Option<String>),
+}
+
+impl Display for BeatError {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match self {
+            BeatError::GStreamerError(e) => e.fmt(f),
+            BeatError::GStreamerBoolError(e) => e.fmt(f),
+            BeatError::GStreamerStateChangeError(e) => e.fmt(f),
+            BeatError::IoError(e) => e.fmt(f),
+            BeatError::InvalidPath => write!(f, "The path is not valid UTF-8"),
+            BeatError::Decode(Some(debug)) => write!(f, "Failed to decode: {}", debug),
+            BeatError::Decode(None) => write!(f, "Failed to decode"),
+        }
+    }
+}
+
+impl StdError for BeatError {}
+
+impl From<glib::Error> for BeatError {
+    fn from(err: glib::Error) -> Self {
+        Self::GStreamerError(err)
+    }
+}
+
+impl From<BoolError> for BeatError {
+    fn from(err: BoolError) -> Self {
+        Self::GStreamerBoolError(err)
+    }
+}
+
+impl From<StateChangeError> for BeatError {
+    fn from(err: StateChangeError) -> Self {
+        Self::GStreamerStateChangeError(err)
+    }
+}
+
+impl From<IoError> for BeatError {
+    fn from(err: IoError) -> Self {
+        Self::IoError(err)
+    }
+}
@@ -0,0 +1,69 @@
+use core::fmt::{Display, Formatter, Result as FmtResult};
+use std::error::Error as StdError;
+
+use gstreamer::{Element, ElementFactory};
+
+/// A GStreamer element that couldn't be created because the plugin that
+/// provides it isn't installed, which is what most audio failures on a
+/// fresh system come down to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MissingElement {
+    pub element: String,
+    /// The plugin providing the element and the package it's part of, if
+    /// it is one this crate uses
+    pub plugin: Option<(&'static str, &'static str)>,
+}
+
+impl MissingElement {
+    pub fn new(element: &str) -> Self {
+        Self {
+            element: element.to_owned(),
+            plugin: Self::plugin_of(element),
+        }
+    }
+
+    /// The elements this crate creates, and where they come from
+    fn plugin_of(element: &str) -> Option<(&'static str, &'static str)> {
+        Some(match element {
+            "playbin" => ("playback", "gst-plugins-base"),
+            "audioconvert" => ("audioconvert", "gst-plugins-base"),
+            "autoaudiosink" => ("autodetect", "gst-plugins-good"),
+            "audiopanorama" | "audiocheblimit" => ("audiofx", "gst-plugins-good"),
+            "equalizer-nbands" => ("equalizer", "gst-plugins-good"),
+            _ => return None,
+        })
+    }
+}
+
+impl Display for MissingElement {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        write!(f, "The GStreamer element '{}' is missing", self.element)?;
+        match self.plugin {
+            Some((plugin, package)) => write!(
+                f,
+                ". It comes with the '{}' plugin from {}, which is probably not installed",
+                plugin, package
+            ),
+            None => write!(
+                f,
+                ", probably because the plugin providing it is not installed"
+            ),
+        }
+    }
+}
+
+impl StdError for MissingElement {}
+
+/// Create an element, telling which plugin is missing if it can't be
+pub(crate) fn make_element(element: &str) -> Result<Element, MissingElement> {
+    ElementFactory::make(element, None).map_err(|_| MissingElement::new(element))
+}
+
+/// Check that all the elements exist before building a pipeline from them,
+/// since a failed parse only says that something is wrong
+pub(crate) fn require(elements: &[&str]) -> Result<(), MissingElement> {
+    match elements.iter().find(|e| ElementFactory::find(e).is_none()) {
+        Some(missing) => Err(MissingElement::new(missing)),
+        None => Ok(()),
+    }
+}
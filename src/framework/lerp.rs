@@ -0,0 +1,124 @@
+use crate::skia::{scalar, Color, Color4f, Matrix, Point, Rect, Size};
+
+/// Linear interpolation, for anything that can be animated with a
+/// [`Tween`](super::tween::Tween).
+///
+/// `t` goes from 0 for `self` to 1 for `other`. Easing curves can overshoot,
+/// so implementations should also give sensible values a little outside of
+/// that range.
+pub trait Lerp {
+    fn lerp(&self, other: &Self, t: f32) -> Self;
+}
+
+impl Lerp for f32 {
+    fn lerp(&self, other: &Self, t: f32) -> Self {
+        self + (other - self) * t
+    }
+}
+
+impl Lerp for f64 {
+    fn lerp(&self, other: &Self, t: f32) -> Self {
+        self + (other - self) * t as f64
+    }
+}
+
+/// Also covers `Vector`, which is the same type
+impl Lerp for Point {
+    fn lerp(&self, other: &Self, t: f32) -> Self {
+        Point::new(self.x.lerp(&other.x, t), self.y.lerp(&other.y, t))
+    }
+}
+
+impl Lerp for Size {
+    fn lerp(&self, other: &Self, t: f32) -> Self {
+        Size::new(
+            self.width.lerp(&other.width, t),
+            self.height.lerp(&other.height, t),
+        )
+    }
+}
+
+impl Lerp for Rect {
+    fn lerp(&self, other: &Self, t: f32) -> Self {
+        Rect::new(
+            self.left.lerp(&other.left, t),
+            self.top.lerp(&other.top, t),
+            self.right.lerp(&other.right, t),
+            self.bottom.lerp(&other.bottom, t),
+        )
+    }
+}
+
+/// Interpolated with premultiplied alpha, so that fading to a transparent
+/// color doesn't pass through that color's RGB, which for transparent black
+/// would darken on the way
+impl Lerp for Color4f {
+    fn lerp(&self, other: &Self, t: f32) -> Self {
+        let a = self.a.lerp(&other.a, t).max(0.0).min(1.0);
+        if a <= 0.0 {
+            return Color4f::new(0.0, 0.0, 0.0, 0.0);
+        }
+        let channel = |from: scalar, to: scalar| {
+            let premultiplied = (from * self.a).lerp(&(to * other.a), t);
+            (premultiplied / a).max(0.0).min(1.0)
+        };
+        Color4f::new(
+            channel(self.r, other.r),
+            channel(self.g, other.g),
+            channel(self.b, other.b),
+            a,
+        )
+    }
+}
+
+impl Lerp for Color {
+    fn lerp(&self, other: &Self, t: f32) -> Self {
+        Color4f::from(*self)
+            .lerp(&Color4f::from(*other), t)
+            .to_color()
+    }
+}
+
+/// Every value of the matrices is interpolated on its own. That is fine
+/// for translation and scale, but a rotation shrinks halfway through, so
+/// those are better animated by their angle.
+impl Lerp for Matrix {
+    fn lerp(&self, other: &Self, t: f32) -> Self {
+        let (mut from, mut to) = ([0.0; 9], [0.0; 9]);
+        self.get_9(&mut from);
+        other.get_9(&mut to);
+        for (a, b) in from.iter_mut().zip(to.iter()) {
+            *a = a.lerp(b, t);
+        }
+        let mut matrix = Matrix::default();
+        matrix.set_9(&from);
+        matrix
+    }
+}
+
+impl<A: Lerp, B: Lerp> Lerp for (A, B) {
+    fn lerp(&self, other: &Self, t: f32) -> Self {
+        (self.0.lerp(&other.0, t), self.1.lerp(&other.1, t))
+    }
+}
+
+impl<A: Lerp, B: Lerp, C: Lerp> Lerp for (A, B, C) {
+    fn lerp(&self, other: &Self, t: f32) -> Self {
+        (
+            self.0.lerp(&other.0, t),
+            self.1.lerp(&other.1, t),
+            self.2.lerp(&other.2, t),
+        )
+    }
+}
+
+impl<A: Lerp, B: Lerp, C: Lerp, D: Lerp> Lerp for (A, B, C, D) {
+    fn lerp(&self, other: &Self, t: f32) -> Self {
+        (
+            self.0.lerp(&other.0, t),
+            self.1.lerp(&other.1, t),
+            self.2.lerp(&other.2, t),
+            self.3.lerp(&other.3, t),
+        )
+    }
+}
@@ -1,34 +1,224 @@
+use core::fmt::{Display, Formatter, Result as FmtResult};
+use std::collections::HashMap;
+use std::error::Error as StdError;
 use std::io::Error as IoError;
 use std::path::Path;
+use std::sync::Mutex;
 use std::time::Duration;
-use std::{cell::RefCell, convert::TryInto};
+use std::{
+    cell::{Cell, RefCell},
+    convert::TryInto,
+};
 
+use super::audio_device::{AudioBuffer, AudioDevice};
+use super::audio_filter::{FilterChain, Filters};
+use super::audio_sync;
+use super::gst_plugins::{self, MissingElement};
 use gstreamer::{
-    glib::{BoolError, FlagsClass},
+    glib::{BoolError, Error as GlibError, FlagsClass, Object, WeakRef},
     prelude::*,
     query::Seeking,
     ClockTime, Element, Format, GenericFormattedValue as Gfv, MessageView, SeekFlags,
     State as GstState, StateChangeError,
 };
+use serde::{Deserialize, Serialize};
+
+thread_local! {
+    static MIXER: RefCell<Mixer> = RefCell::new(Mixer::default());
+}
+
+// Every pipeline made for a sound on any thread, to stop them all when
+// shutting down
+static PIPELINES: Mutex<Vec<WeakRef<Element>>> = Mutex::new(Vec::new());
+
+struct Mixer {
+    master: f64,
+    buses: HashMap<String, f64>,
+    // Bumped on every change, so sounds know to reapply their volume
+    version: u64,
+}
+
+impl Default for Mixer {
+    fn default() -> Self {
+        Self {
+            master: 1.0,
+            buses: HashMap::new(),
+            version: 0,
+        }
+    }
+}
+
+/// Volume buses shared by all sounds on the game thread.
+///
+/// Every sound belongs to the master bus, and optionally to one named bus
+/// like "music" or "voice" on top. The volume it is heard at is its own
+/// volume times that of its bus times the master volume. Buses that were
+/// never set are at full volume.
+pub struct AudioMixer;
+
+impl AudioMixer {
+    pub fn master() -> f64 {
+        MIXER.with(|x| x.borrow().master)
+    }
+
+    pub fn set_master(volume: f64) {
+        MIXER.with(|x| {
+            let mut x = x.borrow_mut();
+            x.master = volume.max(0.0);
+            x.version += 1;
+        });
+    }
+
+    pub fn bus_volume(bus: &str) -> f64 {
+        MIXER.with(|x| x.borrow().buses.get(bus).copied().unwrap_or(1.0))
+    }
+
+    pub fn set_bus_volume(bus: &str, volume: f64) {
+        MIXER.with(|x| {
+            let mut x = x.borrow_mut();
+            x.buses.insert(bus.to_owned(), volume.max(0.0));
+            x.version += 1;
+        });
+    }
+
+    /// Stop every sound at once by setting their pipelines to the null
+    /// state, including sounds made on task threads. The runner does this
+    /// when the game thread panics, so that audio doesn't go on playing
+    /// after a crash. Stopped sounds have to be played again to be heard.
+    pub fn stop_all() {
+        // This runs while unwinding, so it must not panic itself, even if
+        // some thread panicked while it had the list locked.
+        let pipelines = std::mem::take(
+            &mut *PIPELINES
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner()),
+        );
+        for pipeline in pipelines.iter().filter_map(WeakRef::upgrade) {
+            let _ = pipeline.set_state(GstState::Null);
+        }
+    }
+
+    /// Keep track of a sound's pipeline for [`stop_all`](Self::stop_all)
+    pub(crate) fn track(pipeline: &Element) {
+        let mut pipelines = PIPELINES
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        // Sweep pipelines that are gone, so that the list only grows with
+        // the number of live sounds
+        pipelines.retain(|p| p.upgrade().is_some());
+        pipelines.push(pipeline.downgrade());
+    }
+
+    /// What the volume of a sound on `bus` is multiplied with
+    pub fn gain(bus: Option<&str>) -> f64 {
+        Self::master() * bus.map_or(1.0, Self::bus_volume)
+    }
+
+    /// The volumes in a form that can be stored with
+    /// [`settings`](crate::utils::settings)
+    pub fn settings() -> MixerSettings {
+        MIXER.with(|x| {
+            let x = x.borrow();
+            MixerSettings {
+                master: x.master,
+                buses: x.buses.clone(),
+            }
+        })
+    }
+
+    pub fn apply_settings(settings: &MixerSettings) {
+        MIXER.with(|x| {
+            let mut x = x.borrow_mut();
+            x.master = settings.master;
+            x.buses = settings.buses.clone();
+            x.version += 1;
+        });
+    }
+
+    pub(crate) fn version() -> u64 {
+        MIXER.with(|x| x.borrow().version)
+    }
+}
+
+/// Saved [`AudioMixer`] volumes
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MixerSettings {
+    pub master: f64,
+    pub buses: HashMap<String, f64>,
+}
+
+impl Default for MixerSettings {
+    fn default() -> Self {
+        Self {
+            master: 1.0,
+            buses: HashMap::new(),
+        }
+    }
+}
 
 pub struct Music {
     player: Element,
+    panorama: Option<Element>,
+    filters: Option<Filters>,
+    // Where and how the player outputs, to rebuild the sink when either
+    // changes
+    device: Option<AudioDevice>,
+    buffer: AudioBuffer,
 
     // Current state
     state: GstState,
     duration: Option<Duration>,
     seekable: RefCell<Seekability>,
+    // Percentage while a stream is filling its buffer
+    buffering: Option<u8>,
+    // Whether to resume once buffering is done
+    wants_playing: Cell<bool>,
+    // Whether the track played to the end since it was last started
+    ended: Cell<bool>,
 
     // Position cache to work around seeking shenanigans
     last_position: RefCell<Duration>,
+
+    // Own volume and mixer bus, combined into the player's volume
+    volume: Cell<f64>,
+    mixer_bus: Option<String>,
+    mixer_version: u64,
+
+    // Callbacks, dispatched from `update`
+    on_end: Option<Box<dyn FnMut() + Send>>,
+    on_error: Option<Box<dyn FnMut(&GlibError) + Send>>,
+    on_state_changed: Option<Box<dyn FnMut(GstState, GstState) + Send>>,
 }
 
 impl Music {
     pub fn new<P: AsRef<Path>>(path: P) -> Result<Self, MusicCreateError> {
+        Self::from_uri(&Self::file_uri(path)?, &FilterChain::default())
+    }
+
+    /// Play a file through a chain of filters, like an equalizer
+    pub fn with_filters<P: AsRef<Path>>(
+        path: P,
+        filters: &FilterChain,
+    ) -> Result<Self, MusicCreateError> {
+        Self::from_uri(&Self::file_uri(path)?, filters)
+    }
+
+    /// Stream music from a URL, like an HTTP(S) internet radio. Use
+    /// [`buffering`](Self::buffering) to tell when the stream is loading, a
+    /// connection failure is reported as an error from
+    /// [`update`](Self::update).
+    pub fn from_url(url: &str) -> Result<Self, MusicCreateError> {
+        Self::from_uri(url, &FilterChain::default())
+    }
+
+    pub(crate) fn file_uri(path: impl AsRef<Path>) -> Result<String, MusicCreateError> {
         let path = path.as_ref().canonicalize()?;
-        let path = "file:///".to_owned() + path.to_str().ok_or(MusicCreateError::InvalidPath)?;
+        Ok("file:///".to_owned() + path.to_str().ok_or(MusicCreateError::InvalidPath)?)
+    }
 
-        let player = gstreamer::ElementFactory::make("playbin", None)?;
+    pub(crate) fn from_uri(uri: &str, filters: &FilterChain) -> Result<Self, MusicCreateError> {
+        let player = gst_plugins::make_element("playbin")?;
         // Since the file may contain more than just the audio stream,
         // we want to ignore everything but the audio.
         // Making flags through GStreamer Rust bindings is sort of clumsy,
@@ -43,28 +233,121 @@ impl Music {
             .build()
             .unwrap();
         player.set_property("flags", &flags)?;
-        player.set_property("uri", &path)?;
+        player.set_property("uri", &uri)?;
+
+        let (panorama, filters) = if filters.is_empty() {
+            // Panning is an extra, so a missing audiopanorama plugin doesn't
+            // stop the music from playing.
+            let panorama = gstreamer::ElementFactory::make("audiopanorama", None).ok();
+            if let Some(panorama) = &panorama {
+                player.set_property("audio-filter", panorama)?;
+            }
+            (panorama, None)
+        } else {
+            // Filters that were asked for are required though.
+            gst_plugins::require(&filters.elements())?;
+            let filters = Filters::build(filters)?;
+            player.set_property("audio-filter", &filters.bin)?;
+            (filters.panorama.clone(), Some(filters))
+        };
+
+        let device = AudioDevice::default_output();
+        let buffer = AudioBuffer::default_buffer();
+        player.set_property(
+            "audio-sink",
+            &AudioDevice::sink_for(device.as_ref(), buffer)?,
+        )?;
+
+        player.set_property("volume", &AudioMixer::gain(None))?;
+        AudioMixer::track(&player);
 
         let initial_state = GstState::Paused;
         player.set_state(initial_state)?;
 
         Ok(Self {
             player,
+            panorama,
+            filters,
+            device,
+            buffer,
             state: initial_state,
             duration: None,
             seekable: RefCell::new(Seekability::Unknown),
+            buffering: None,
+            wants_playing: Cell::new(false),
+            ended: Cell::new(false),
             last_position: RefCell::new(Duration::ZERO),
+            volume: Cell::new(1.0),
+            mixer_bus: None,
+            mixer_version: AudioMixer::version(),
+            on_end: None,
+            on_error: None,
+            on_state_changed: None,
         })
     }
 
+    /// Call `f` when the track has played to the end
+    pub fn on_end(&mut self, f: impl FnMut() + Send + 'static) {
+        self.on_end = Some(Box::new(f));
+    }
+
+    /// Call `f` when GStreamer reports an error, like a broken file
+    pub fn on_error(&mut self, f: impl FnMut(&GlibError) + Send + 'static) {
+        self.on_error = Some(Box::new(f));
+    }
+
+    /// Call `f` with the old and new state whenever the player changes
+    /// state, like when it starts playing or is paused
+    pub fn on_state_changed(&mut self, f: impl FnMut(GstState, GstState) + Send + 'static) {
+        self.on_state_changed = Some(Box::new(f));
+    }
+
+    /// Ask `next` for the URI to carry on with when the current track is
+    /// about to finish, so the player moves on to it without a gap. This
+    /// is called on one of GStreamer's threads, and the music ends as usual
+    /// if it returns `None`.
+    pub(crate) fn on_about_to_finish(
+        &self,
+        next: impl Fn() -> Option<String> + Send + Sync + 'static,
+    ) {
+        let connected = self.player.connect("about-to-finish", false, move |args| {
+            if let Some(uri) = next() {
+                if let Ok(Some(player)) = args[0].get::<Element>() {
+                    if let Err(e) = player.set_property("uri", &uri) {
+                        eprintln!("Failed to queue {}: {}", uri, e);
+                    }
+                }
+            }
+            None
+        });
+        if let Err(e) = connected {
+            eprintln!("Failed to connect to the player's about-to-finish: {}", e);
+        }
+    }
+
+    /// Handle messages from the player. The callbacks are called from here,
+    /// so on whichever thread this is called, which should be regularly.
     pub fn update(&mut self) -> Result<(), Option<String>> {
+        let mixer_version = AudioMixer::version();
+        if self.mixer_version != mixer_version {
+            self.mixer_version = mixer_version;
+            self.apply_volume();
+        }
+
         if let Some(bus) = self.player.get_bus() {
-            if let Some(msg) = bus.pop() {
+            while let Some(msg) = bus.pop() {
                 match msg.view() {
                     MessageView::Eos(..) => {
                         let _ = self.player.set_state(GstState::Ready);
+                        self.ended.set(true);
+                        if let Some(f) = &mut self.on_end {
+                            f();
+                        }
                     }
                     MessageView::Error(err) => {
+                        if let Some(f) = &mut self.on_error {
+                            f(&err.get_error());
+                        }
                         return Err(err.get_debug());
                     }
                     MessageView::StateChanged(state_changed) => {
@@ -72,11 +355,43 @@ impl Music {
                         if self.is_playing() {
                             self.trigger_seekable();
                         }
+                        // Every element in the pipeline reports its own
+                        // state changes, only the player's are of interest.
+                        let from_player = msg.get_src().as_ref()
+                            == Some(self.player.upcast_ref::<gstreamer::Object>());
+                        if from_player {
+                            if let Some(f) = &mut self.on_state_changed {
+                                f(state_changed.get_old(), state_changed.get_current());
+                            }
+                        }
+                    }
+                    MessageView::Buffering(buffering) => {
+                        // Streams pause while their buffer fills up, so they
+                        // don't stutter just after starting.
+                        let percent = buffering.get_percent().max(0).min(100) as u8;
+                        if percent < 100 {
+                            if self.buffering.is_none() && self.wants_playing.get() {
+                                let _ = self.player.set_state(GstState::Paused);
+                            }
+                            self.buffering = Some(percent);
+                        } else {
+                            self.buffering = None;
+                            if self.wants_playing.get() {
+                                let _ = self.player.set_state(GstState::Playing);
+                            }
+                        }
                     }
                     MessageView::DurationChanged(..) => {
                         // The duration was updated, invalidate old duration
                         self.duration = None;
                     }
+                    // A new track started, which after a gapless switch to
+                    // the next URI is the only sign of it
+                    MessageView::StreamStart(..) => {
+                        self.duration = None;
+                        *self.seekable.borrow_mut() = Seekability::Unknown;
+                        *self.last_position.borrow_mut() = Duration::ZERO;
+                    }
                     _ => {}
                 }
             }
@@ -109,6 +424,16 @@ impl Music {
         pos
     }
 
+    /// The position of what is audible right now, which lags behind
+    /// [`position`](Self::position) by the
+    /// [audio offset](super::audio_sync::audio_offset). Use this for anything
+    /// drawn in sync with the music.
+    pub fn visual_position(&self) -> Duration {
+        self.position()
+            .checked_sub(audio_sync::audio_offset())
+            .unwrap_or_default()
+    }
+
     pub fn position_f32(&self) -> f32 {
         self.position().as_secs_f32()
     }
@@ -139,20 +464,196 @@ impl Music {
         self.state == GstState::Playing
     }
 
+    /// Replace the current track with a stream from a URL and start playing
+    /// it, like [`from_url`](Self::from_url).
+    pub fn play_url(&mut self, url: &str) -> Result<(), MusicCreateError> {
+        self.load_uri(url, true)
+    }
+
+    /// Replace the current track, and start playing it if `play` is set or
+    /// leave it paused otherwise
+    pub(crate) fn load_uri(&mut self, uri: &str, play: bool) -> Result<(), MusicCreateError> {
+        self.player.set_state(GstState::Ready)?;
+        self.player.set_property("uri", &uri)?;
+        self.duration = None;
+        *self.seekable.borrow_mut() = Seekability::Unknown;
+        *self.last_position.borrow_mut() = Duration::ZERO;
+        self.buffering = None;
+        self.wants_playing.set(play);
+        self.ended.set(false);
+        let state = if play {
+            GstState::Playing
+        } else {
+            GstState::Paused
+        };
+        self.player.set_state(state)?;
+        Ok(())
+    }
+
+    /// How full the buffer is, from 0 to 100, while a stream is loading.
+    /// `None` when nothing is being buffered.
+    pub fn buffering(&self) -> Option<u8> {
+        self.buffering
+    }
+
+    /// Whether the track played to the end, and hasn't been started again
+    /// since
+    pub fn has_ended(&self) -> bool {
+        self.ended.get()
+    }
+
     pub fn play(&self) {
-        let _ = self.player.set_state(GstState::Playing);
+        self.wants_playing.set(true);
+        self.ended.set(false);
+        // Playback starts on its own once buffering is done.
+        if self.buffering.is_none() {
+            let _ = self.player.set_state(GstState::Playing);
+        }
     }
 
     pub fn pause(&self) {
+        self.wants_playing.set(false);
         let _ = self.player.set_state(GstState::Paused);
     }
 
     pub fn toggle_playing(&self) {
-        let _ = self.player.set_state(match self.state {
-            GstState::Playing => GstState::Paused,
-            GstState::Ready | GstState::Paused | GstState::Null => GstState::Playing,
-            _ => return,
-        });
+        // A stream that is waiting on its buffer counts as playing.
+        if self.is_playing() || self.buffering.is_some() && self.wants_playing.get() {
+            self.pause();
+        } else if let GstState::Ready | GstState::Paused | GstState::Null = self.state {
+            self.play();
+        }
+    }
+
+    /// Linear volume, where 1 is the volume of the file. This is before the
+    /// [`AudioMixer`] volumes are applied.
+    pub fn volume(&self) -> f64 {
+        self.volume.get()
+    }
+
+    pub fn set_volume(&self, volume: f64) {
+        self.volume.set(volume.max(0.0));
+        self.apply_volume();
+    }
+
+    /// The [`AudioMixer`] bus the music is on, besides the master bus
+    pub fn bus(&self) -> Option<&str> {
+        self.mixer_bus.as_deref()
+    }
+
+    pub fn set_bus(&mut self, bus: Option<&str>) {
+        self.mixer_bus = bus.map(str::to_owned);
+        self.apply_volume();
+    }
+
+    fn apply_volume(&self) {
+        let volume = self.volume.get() * AudioMixer::gain(self.bus());
+        let _ = self.player.set_property("volume", &volume);
+    }
+
+    /// Stereo position from -1 for fully left to 1 for fully right
+    pub fn pan(&self) -> f32 {
+        self.panorama
+            .as_ref()
+            .and_then(|p| p.get_property("panorama").ok())
+            .and_then(|v| v.get_some().ok())
+            .unwrap_or(0.0)
+    }
+
+    /// Move the music left or right. Does nothing if GStreamer's
+    /// audiopanorama plugin isn't available.
+    pub fn set_pan(&self, pan: f32) {
+        if let Some(panorama) = &self.panorama {
+            let _ = panorama.set_property("panorama", &pan.max(-1.0).min(1.0));
+        }
+    }
+
+    /// The number of equalizer bands in the filter chain
+    pub fn band_count(&self) -> usize {
+        self.filters.as_ref().map_or(0, |f| f.band_count())
+    }
+
+    fn band(&self, band: usize) -> Option<Object> {
+        self.filters.as_ref()?.band(band)
+    }
+
+    /// The gain of an equalizer band in dB, from -24 to 12
+    pub fn band_gain(&self, band: usize) -> f64 {
+        self.band(band)
+            .and_then(|b| b.get_property("gain").ok())
+            .and_then(|v| v.get_some().ok())
+            .unwrap_or(0.0)
+    }
+
+    pub fn set_band_gain(&self, band: usize, gain: f64) {
+        if let Some(band) = self.band(band) {
+            let _ = band.set_property("gain", &gain.max(-24.0).min(12.0));
+        }
+    }
+
+    /// The center frequency of an equalizer band in Hz
+    pub fn band_frequency(&self, band: usize) -> Option<f64> {
+        self.band(band)?.get_property("freq").ok()?.get_some().ok()
+    }
+
+    /// Move the cutoff of the low-pass filter, if the filter chain has one
+    pub fn set_low_pass(&self, cutoff: f32) {
+        if let Some(filter) = self.filters.as_ref().and_then(|f| f.low_pass.as_ref()) {
+            let _ = filter.set_property("cutoff", &cutoff.max(0.0));
+        }
+    }
+
+    /// Move the cutoff of the high-pass filter, if the filter chain has one
+    pub fn set_high_pass(&self, cutoff: f32) {
+        if let Some(filter) = self.filters.as_ref().and_then(|f| f.high_pass.as_ref()) {
+            let _ = filter.set_property("cutoff", &cutoff.max(0.0));
+        }
+    }
+
+    /// Move playback to another output, or to the system's default output
+    /// if `device` is `None`. Blocks for up to a second while the pipeline
+    /// restarts, after which playback continues where it was.
+    pub fn set_device(&mut self, device: Option<&AudioDevice>) -> Result<(), MusicCreateError> {
+        self.replace_sink(device.cloned(), self.buffer)
+    }
+
+    /// How much audio is queued up ahead of what is heard, see
+    /// [`AudioBuffer`] for the tradeoff
+    pub fn buffer(&self) -> AudioBuffer {
+        self.buffer
+    }
+
+    /// Change how much audio is queued up ahead. Like
+    /// [`set_device`](Self::set_device), this restarts the pipeline.
+    pub fn set_buffer(&mut self, buffer: AudioBuffer) -> Result<(), MusicCreateError> {
+        self.replace_sink(self.device.clone(), buffer)
+    }
+
+    fn replace_sink(
+        &mut self,
+        device: Option<AudioDevice>,
+        buffer: AudioBuffer,
+    ) -> Result<(), MusicCreateError> {
+        let sink = AudioDevice::sink_for(device.as_ref(), buffer)?;
+        self.device = device;
+        self.buffer = buffer;
+        let position = self.position();
+        let was_playing = self.is_playing();
+
+        // The sink can only be swapped out while the pipeline is stopped.
+        self.player.set_state(GstState::Ready)?;
+        self.player.set_property("audio-sink", &sink)?;
+        self.player.set_state(GstState::Paused)?;
+        // Seeking back only works once the new pipeline has prerolled.
+        let _ = self.player.get_state(ClockTime::from_seconds(1));
+        let seek_flags = SeekFlags::FLUSH | SeekFlags::ACCURATE;
+        let _ = self
+            .player
+            .seek_simple(seek_flags, ClockTime::from(position));
+        if was_playing {
+            self.player.set_state(GstState::Playing)?;
+        }
+        Ok(())
     }
 
     fn get_duration(&self) -> Option<Duration> {
@@ -218,10 +719,44 @@ enum Seekability {
 
 #[derive(Debug)]
 pub enum MusicCreateError {
+    GStreamerError(GlibError),
     GStreamerBoolError(BoolError),
     GStreamerStateChangeError(StateChangeError),
     IoError(IoError),
     InvalidPath,
+    MissingElement(MissingElement),
+}
+
+impl Display for MusicCreateError {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match self {
+            Self::GStreamerError(e) => e.fmt(f),
+            Self::GStreamerBoolError(e) => e.fmt(f),
+            Self::GStreamerStateChangeError(e) => e.fmt(f),
+            Self::IoError(e) => e.fmt(f),
+            Self::InvalidPath => write!(f, "The path is not valid UTF-8"),
+            Self::MissingElement(e) => e.fmt(f),
+        }
+    }
+}
+
+impl StdError for MusicCreateError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match self {
+            Self::GStreamerError(e) => Some(e),
+            Self::GStreamerBoolError(e) => Some(e),
+            Self::GStreamerStateChangeError(e) => Some(e),
+            Self::IoError(e) => Some(e),
+            Self::InvalidPath => None,
+            Self::MissingElement(e) => Some(e),
+        }
+    }
+}
+
+impl From<MissingElement> for MusicCreateError {
+    fn from(err: MissingElement) -> Self {
+        Self::MissingElement(err)
+    }
 }
 
 impl From<IoError> for MusicCreateError {
@@ -230,6 +765,12 @@ impl From<IoError> for MusicCreateError {
     }
 }
 
+impl From<GlibError> for MusicCreateError {
+    fn from(err: GlibError) -> Self {
+        Self::GStreamerError(err)
+    }
+}
+
 impl From<BoolError> for MusicCreateError {
     fn from(err: BoolError) -> Self {
         Self::GStreamerBoolError(err)
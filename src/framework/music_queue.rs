@@ -0,0 +1,176 @@
+use std::collections::VecDeque;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+use super::audio_filter::FilterChain;
+use super::music::{Music, MusicCreateError};
+
+/// Where a queued track is loaded from
+#[derive(Debug, Clone, PartialEq)]
+pub enum Track {
+    File(PathBuf),
+    Url(String),
+}
+
+impl Track {
+    fn uri(&self) -> Result<String, MusicCreateError> {
+        match self {
+            Track::File(path) => Music::file_uri(path),
+            Track::Url(url) => Ok(url.clone()),
+        }
+    }
+}
+
+/// Plays tracks one after another without a gap between them.
+///
+/// The tracks share a single player, which is told the next track when the
+/// current one is about to finish, so it goes straight on to it. A track
+/// pushed while the queue is playing but has run out starts right away.
+/// Tracks that can't be found are skipped.
+pub struct MusicQueue {
+    current: Option<Music>,
+    /// The URIs of the tracks after the current one. The player takes the
+    /// first one from here itself, on one of GStreamer's threads.
+    tracks: Arc<Mutex<VecDeque<String>>>,
+    playing: bool,
+}
+
+impl MusicQueue {
+    pub fn new() -> Self {
+        Self {
+            current: None,
+            tracks: Arc::new(Mutex::new(VecDeque::new())),
+            playing: false,
+        }
+    }
+
+    /// Add a track to the end of the queue
+    pub fn push(&mut self, track: Track) {
+        let uri = match track.uri() {
+            Ok(uri) => uri,
+            Err(_) => return,
+        };
+        let idle = self.current.as_ref().map_or(true, Music::has_ended);
+        if idle && self.is_empty() {
+            self.start(&uri);
+        } else {
+            self.tracks.lock().unwrap().push_back(uri);
+        }
+    }
+
+    pub fn push_file(&mut self, path: impl Into<PathBuf>) {
+        self.push(Track::File(path.into()));
+    }
+
+    pub fn push_url(&mut self, url: impl Into<String>) {
+        self.push(Track::Url(url.into()));
+    }
+
+    /// The track that is playing, or paused
+    pub fn current(&self) -> Option<&Music> {
+        self.current.as_ref()
+    }
+
+    pub fn current_mut(&mut self) -> Option<&mut Music> {
+        self.current.as_mut()
+    }
+
+    /// The number of tracks still to come after the current one
+    pub fn len(&self) -> usize {
+        self.tracks.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn is_playing(&self) -> bool {
+        self.playing
+    }
+
+    pub fn play(&mut self) {
+        self.playing = true;
+        match &self.current {
+            Some(music) if !music.has_ended() => music.play(),
+            _ => self.advance(),
+        }
+    }
+
+    pub fn pause(&mut self) {
+        self.playing = false;
+        if let Some(music) = &self.current {
+            music.pause();
+        }
+    }
+
+    /// Move on to the next track right away
+    pub fn skip(&mut self) {
+        self.advance();
+    }
+
+    /// Remove every track, stopping the current one
+    pub fn clear(&mut self) {
+        self.current = None;
+        self.tracks.lock().unwrap().clear();
+    }
+
+    /// Update the current track, and move on when it ended without the
+    /// player having gone on to the next one, like after an error or when a
+    /// track was pushed too late. Should be called regularly, like once per
+    /// update.
+    pub fn update(&mut self) -> Result<(), Option<String>> {
+        let result = match &mut self.current {
+            Some(music) => music.update(),
+            None => Ok(()),
+        };
+        let ended = self.current.as_ref().map_or(false, |m| m.has_ended());
+        // A broken track shouldn't stop the rest of the queue.
+        if self.playing && (ended || result.is_err()) {
+            self.advance();
+        }
+        result
+    }
+
+    fn advance(&mut self) {
+        loop {
+            let next = self.tracks.lock().unwrap().pop_front();
+            match next {
+                Some(uri) if self.start(&uri) => return,
+                Some(_) => {}
+                None => {
+                    self.current = None;
+                    return;
+                }
+            }
+        }
+    }
+
+    /// Load a track into the player, making the player first if there is
+    /// none yet, and start it if the queue is playing. Returns whether the
+    /// track could be loaded.
+    fn start(&mut self, uri: &str) -> bool {
+        if let Some(music) = &mut self.current {
+            if music.load_uri(uri, self.playing).is_ok() {
+                return true;
+            }
+        }
+        self.current = Music::from_uri(uri, &FilterChain::default()).ok();
+        match &self.current {
+            Some(music) => {
+                let tracks = self.tracks.clone();
+                music.on_about_to_finish(move || tracks.lock().unwrap().pop_front());
+                if self.playing {
+                    music.play();
+                }
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+impl Default for MusicQueue {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,59 @@
+use std::sync::{Arc, Mutex};
+
+struct Inner<T> {
+    value: T,
+    version: u64,
+}
+
+/// A value shared between game logic and the widgets displaying it.
+///
+/// Every change bumps a version number, which lets widgets like
+/// [`Bind`](super::widgets::Bind) tell when to refresh without comparing
+/// values. Clones refer to the same value, and can be changed from any
+/// thread.
+pub struct Observable<T>(Arc<Mutex<Inner<T>>>);
+
+impl<T> Observable<T> {
+    pub fn new(value: T) -> Self {
+        Self(Arc::new(Mutex::new(Inner { value, version: 0 })))
+    }
+
+    pub fn set(&self, value: T) {
+        self.update(|x| *x = value);
+    }
+
+    /// Change the value in place
+    pub fn update<R>(&self, f: impl FnOnce(&mut T) -> R) -> R {
+        let mut inner = self.0.lock().unwrap();
+        inner.version += 1;
+        f(&mut inner.value)
+    }
+
+    /// Look at the value without cloning it
+    pub fn with<R>(&self, f: impl FnOnce(&T) -> R) -> R {
+        f(&self.0.lock().unwrap().value)
+    }
+
+    /// Increases with every change
+    pub fn version(&self) -> u64 {
+        self.0.lock().unwrap().version
+    }
+}
+
+impl<T: Clone> Observable<T> {
+    pub fn get(&self) -> T {
+        self.with(T::clone)
+    }
+}
+
+impl<T> Clone for Observable<T> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<T: Default> Default for Observable<T> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
@@ -0,0 +1,121 @@
+use crate::game::State;
+use skulpin_renderer_sdl2::sdl2::keyboard::{Keycode, Mod};
+
+/// A key along with the modifiers that need to be held with it.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct KeyCombo {
+    pub key: Keycode,
+    pub ctrl: bool,
+    pub shift: bool,
+    pub alt: bool,
+    pub gui: bool,
+}
+
+impl KeyCombo {
+    pub fn new(key: Keycode) -> Self {
+        Self {
+            key,
+            ctrl: false,
+            shift: false,
+            alt: false,
+            gui: false,
+        }
+    }
+
+    pub fn ctrl(mut self) -> Self {
+        self.ctrl = true;
+        self
+    }
+
+    pub fn shift(mut self) -> Self {
+        self.shift = true;
+        self
+    }
+
+    pub fn alt(mut self) -> Self {
+        self.alt = true;
+        self
+    }
+
+    pub fn gui(mut self) -> Self {
+        self.gui = true;
+        self
+    }
+
+    /// Require the platform's primary shortcut modifier, which is Command on
+    /// macOS and Ctrl everywhere else.
+    pub fn primary(self) -> Self {
+        if cfg!(target_os = "macos") {
+            self.gui()
+        } else {
+            self.ctrl()
+        }
+    }
+
+    /// Whether the key pressed along with the held modifiers trigger this
+    /// combination. Either side's modifier keys count, and lock keys are
+    /// ignored, but no other modifiers may be held.
+    pub fn matches(&self, key: Keycode, modifiers: Mod) -> bool {
+        let held = |m: Mod| modifiers.intersects(m);
+        self.key == key
+            && self.ctrl == held(Mod::LCTRLMOD | Mod::RCTRLMOD)
+            && self.shift == held(Mod::LSHIFTMOD | Mod::RSHIFTMOD)
+            && self.alt == held(Mod::LALTMOD | Mod::RALTMOD)
+            && self.gui == held(Mod::LGUIMOD | Mod::RGUIMOD)
+    }
+}
+
+impl From<Keycode> for KeyCombo {
+    fn from(key: Keycode) -> Self {
+        Self::new(key)
+    }
+}
+
+/// Application-wide key combinations, checked before any widget receives the
+/// key press. A key press that triggers a shortcut is not passed on.
+#[derive(Default)]
+pub struct Shortcuts {
+    bindings: Vec<(KeyCombo, Box<dyn FnMut() + Send>)>,
+}
+
+impl Shortcuts {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with(
+        mut self,
+        combo: impl Into<KeyCombo>,
+        callback: impl FnMut() + Send + 'static,
+    ) -> Self {
+        self.register(combo, callback);
+        self
+    }
+
+    pub fn register(
+        &mut self,
+        combo: impl Into<KeyCombo>,
+        callback: impl FnMut() + Send + 'static,
+    ) {
+        self.bindings.push((combo.into(), Box::new(callback)));
+    }
+
+    pub fn unregister(&mut self, combo: impl Into<KeyCombo>) {
+        let combo = combo.into();
+        self.bindings.retain(|(c, _)| *c != combo);
+    }
+
+    /// Run every callback bound to the key pressed with the currently held
+    /// modifiers. Returns whether any shortcut was triggered.
+    pub fn trigger(&mut self, key: Keycode) -> bool {
+        let modifiers = State::modifiers();
+        let mut triggered = false;
+        for (combo, callback) in &mut self.bindings {
+            if combo.matches(key, modifiers) {
+                callback();
+                triggered = true;
+            }
+        }
+        triggered
+    }
+}
@@ -0,0 +1,141 @@
+//! Snapping dragged rectangles to a grid and to the edges of other things,
+//! for editor-style tools.
+//!
+//! A [`Snapping`] holds the places to snap to. Given where the user is
+//! dragging a rectangle, [`Snapping::snap`] moves it onto any nearby snap
+//! line and reports the [`Guide`]s it lined up with, so they can be drawn.
+
+use crate::game::State;
+use crate::skia::{scalar, Rect, Vector};
+
+/// An alignment line a rectangle was snapped to
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Guide {
+    /// A vertical line at this x
+    Vertical(scalar),
+    /// A horizontal line at this y
+    Horizontal(scalar),
+}
+
+/// Where a rectangle ended up after snapping
+#[derive(Debug, Clone, PartialEq)]
+pub struct Snapped {
+    pub rect: Rect,
+    /// The lines that the rectangle's edges or center line up with
+    pub guides: Vec<Guide>,
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Snapping {
+    /// The spacing of the grid, if there is one
+    pub grid: Option<scalar>,
+    /// How close an edge has to get to a snap line to be pulled onto it
+    pub threshold: scalar,
+    /// The x positions of the vertical lines to snap to
+    pub vertical: Vec<scalar>,
+    /// The y positions of the horizontal lines to snap to
+    pub horizontal: Vec<scalar>,
+}
+
+impl Snapping {
+    pub fn new(threshold: scalar) -> Self {
+        Self {
+            threshold,
+            ..Self::default()
+        }
+    }
+
+    pub fn with_grid(mut self, spacing: scalar) -> Self {
+        self.grid = Some(spacing);
+        self
+    }
+
+    /// Snap to the grid shown with [`DebugOptions::grid`], if it is shown,
+    /// so that what is dragged lines up with what is seen
+    ///
+    /// [`DebugOptions::grid`]: crate::game::DebugOptions::grid
+    pub fn with_debug_grid(mut self) -> Self {
+        if let Some(spacing) = State::debug().grid {
+            self.grid = Some(spacing);
+        }
+        self
+    }
+
+    /// Add the edges and centers of a rectangle as snap lines, so other
+    /// rectangles can be lined up with it
+    pub fn add_rect(&mut self, rect: Rect) {
+        self.vertical
+            .extend_from_slice(&[rect.left, rect.center_x(), rect.right]);
+        self.horizontal
+            .extend_from_slice(&[rect.top, rect.center_y(), rect.bottom]);
+    }
+
+    pub fn clear(&mut self) {
+        self.vertical.clear();
+        self.horizontal.clear();
+    }
+
+    /// Move a rectangle onto the nearest snap line within the threshold on
+    /// each axis, checking its edges and center. Lines take precedence over
+    /// the grid, which otherwise always moves its top left corner onto the
+    /// nearest grid point.
+    pub fn snap(&self, rect: Rect) -> Snapped {
+        let mut guides = Vec::new();
+        let dx = match self.nearest(&[rect.left, rect.center_x(), rect.right], &self.vertical) {
+            Some((line, d)) => {
+                guides.push(Guide::Vertical(line));
+                d
+            }
+            None => self.grid_offset(rect.left),
+        };
+        let dy = match self.nearest(&[rect.top, rect.center_y(), rect.bottom], &self.horizontal) {
+            Some((line, d)) => {
+                guides.push(Guide::Horizontal(line));
+                d
+            }
+            None => self.grid_offset(rect.top),
+        };
+        let rect = rect.with_offset(Vector::new(dx, dy));
+
+        // Other lines may line up as well once the rectangle has moved
+        for &x in &self.vertical {
+            let aligned = [rect.left, rect.center_x(), rect.right]
+                .iter()
+                .any(|e| (e - x).abs() < 0.5);
+            if aligned && !guides.contains(&Guide::Vertical(x)) {
+                guides.push(Guide::Vertical(x));
+            }
+        }
+        for &y in &self.horizontal {
+            let aligned = [rect.top, rect.center_y(), rect.bottom]
+                .iter()
+                .any(|e| (e - y).abs() < 0.5);
+            if aligned && !guides.contains(&Guide::Horizontal(y)) {
+                guides.push(Guide::Horizontal(y));
+            }
+        }
+        Snapped { rect, guides }
+    }
+
+    /// The closest line to any of the edges within the threshold, and how
+    /// far to move to reach it
+    fn nearest(&self, edges: &[scalar], lines: &[scalar]) -> Option<(scalar, scalar)> {
+        let mut best: Option<(scalar, scalar)> = None;
+        for &line in lines {
+            for &edge in edges {
+                let d = line - edge;
+                if d.abs() <= self.threshold && best.map_or(true, |(_, b)| d.abs() < b.abs()) {
+                    best = Some((line, d));
+                }
+            }
+        }
+        best
+    }
+
+    fn grid_offset(&self, position: scalar) -> scalar {
+        match self.grid {
+            Some(spacing) if spacing > 0.0 => (position / spacing).round() * spacing - position,
+            _ => 0.0,
+        }
+    }
+}
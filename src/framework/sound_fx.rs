@@ -0,0 +1,332 @@
+use core::fmt::{Display, Formatter, Result as FmtResult};
+use std::error::Error as StdError;
+use std::io::Error as IoError;
+use std::path::Path;
+
+use super::audio_device::{AudioBuffer, AudioDevice};
+use super::gst_plugins::{self, MissingElement};
+use super::music::AudioMixer;
+use gstreamer::{
+    glib::{BoolError, FlagsClass},
+    prelude::*,
+    ClockTime, Element, MessageView, SeekFlags, State as GstState, StateChangeError,
+};
+
+/// A short sample, like a click or a hit, that can be played many times over
+/// itself.
+///
+/// Each overlapping play gets its own voice, a GStreamer pipeline that is
+/// kept prerolled while idle, so the file is already decoded by the time it
+/// is played. Voices are created as needed, up to `max_voices`; past that,
+/// the voice that has been playing the longest is restarted.
+pub struct SoundFx {
+    uri: String,
+    device: Option<AudioDevice>,
+    buffer: AudioBuffer,
+    voices: Vec<Voice>,
+    preload: usize,
+    max_voices: usize,
+    /// Multiplied with the volume of every play
+    pub volume: f64,
+    /// The [`AudioMixer`] bus the sound is on, besides the master bus
+    pub bus: Option<String>,
+    mixer_version: u64,
+    // Incremented each play to find the oldest voice
+    clock: u64,
+}
+
+impl SoundFx {
+    /// Load a sample and preload `preload` voices for it. The first that
+    /// many overlapping plays won't have to wait for a pipeline to start.
+    pub fn new<P: AsRef<Path>>(
+        path: P,
+        preload: usize,
+        max_voices: usize,
+    ) -> Result<Self, SoundFxCreateError> {
+        let path = path.as_ref().canonicalize()?;
+        let uri = "file:///".to_owned() + path.to_str().ok_or(SoundFxCreateError::InvalidPath)?;
+        let max_voices = max_voices.max(1);
+        let mut fx = Self {
+            uri,
+            device: AudioDevice::default_output(),
+            buffer: AudioBuffer::default_buffer(),
+            voices: Vec::with_capacity(max_voices),
+            preload: preload.clamp(1, max_voices),
+            max_voices,
+            volume: 1.0,
+            bus: None,
+            mixer_version: AudioMixer::version(),
+            clock: 0,
+        };
+        fx.preload()?;
+        Ok(fx)
+    }
+
+    fn preload(&mut self) -> Result<(), SoundFxCreateError> {
+        for _ in self.voices.len()..self.preload {
+            self.voices
+                .push(Voice::new(&self.uri, self.device.as_ref(), self.buffer)?);
+        }
+        Ok(())
+    }
+
+    pub fn with_volume(mut self, volume: f64) -> Self {
+        self.volume = volume;
+        self
+    }
+
+    pub fn with_bus(mut self, bus: &str) -> Self {
+        self.bus = Some(bus.to_owned());
+        self
+    }
+
+    fn gain(&self) -> f64 {
+        self.volume * AudioMixer::gain(self.bus.as_deref())
+    }
+
+    /// Play the sample once at full volume, centered
+    pub fn play(&mut self) {
+        self.play_with(1.0, 0.0);
+    }
+
+    /// Play the sample once. Volume is linear, from 0 upwards. Pan goes from
+    /// -1 for fully left to 1 for fully right, and is ignored if the
+    /// audiopanorama plugin isn't available.
+    pub fn play_with(&mut self, volume: f64, pan: f32) {
+        self.clock += 1;
+        let index = match self.voices.iter().position(|v| !v.playing) {
+            Some(index) => index,
+            None if self.voices.len() < self.max_voices => {
+                match Voice::new(&self.uri, self.device.as_ref(), self.buffer) {
+                    Ok(voice) => {
+                        self.voices.push(voice);
+                        self.voices.len() - 1
+                    }
+                    Err(_) => return,
+                }
+            }
+            None => {
+                // Every voice is busy, so cut off the one that is closest to
+                // being done anyway.
+                let (index, _) = self
+                    .voices
+                    .iter()
+                    .enumerate()
+                    .min_by_key(|(_, v)| v.started)
+                    .unwrap();
+                index
+            }
+        };
+        let gain = self.gain();
+        self.voices[index].play(volume, gain, pan, self.clock);
+    }
+
+    /// Stop every play of the sample
+    pub fn stop(&mut self) {
+        for voice in &mut self.voices {
+            voice.rewind();
+        }
+    }
+
+    /// Play on another output from now on, or on the system's default output
+    /// if `device` is `None`. Plays that are still going are cut off.
+    pub fn set_device(&mut self, device: Option<&AudioDevice>) -> Result<(), SoundFxCreateError> {
+        self.device = device.cloned();
+        self.voices.clear();
+        self.preload()
+    }
+
+    /// Change how much audio is queued up ahead of what is heard, see
+    /// [`AudioBuffer`] for the tradeoff. Sound effects are usually best
+    /// kept short. Plays that are still going are cut off.
+    pub fn set_buffer(&mut self, buffer: AudioBuffer) -> Result<(), SoundFxCreateError> {
+        self.buffer = buffer;
+        self.voices.clear();
+        self.preload()
+    }
+
+    /// The number of plays currently audible
+    pub fn playing(&self) -> usize {
+        self.voices.iter().filter(|v| v.playing).count()
+    }
+
+    /// Handle messages from the voices' pipelines, making finished voices
+    /// available again. Should be called regularly, like once per update.
+    pub fn update(&mut self) -> Result<(), Option<String>> {
+        // Plays that are still going follow changes to the mixer.
+        let mixer_version = AudioMixer::version();
+        if self.mixer_version != mixer_version {
+            self.mixer_version = mixer_version;
+            let gain = self.gain();
+            for voice in self.voices.iter().filter(|v| v.playing) {
+                voice.set_volume(voice.volume * gain);
+            }
+        }
+        for voice in &mut self.voices {
+            voice.update()?;
+        }
+        Ok(())
+    }
+}
+
+struct Voice {
+    player: Element,
+    panorama: Option<Element>,
+    playing: bool,
+    started: u64,
+    // The volume of the current play, before the gain of the sound
+    volume: f64,
+}
+
+impl Voice {
+    fn new(
+        uri: &str,
+        device: Option<&AudioDevice>,
+        buffer: AudioBuffer,
+    ) -> Result<Self, SoundFxCreateError> {
+        let player = gst_plugins::make_element("playbin")?;
+        let flags = player.get_property("flags")?;
+        let flags_class = FlagsClass::new(flags.type_()).unwrap();
+        let flags = flags_class
+            .builder_with_value(flags)
+            .unwrap()
+            .unset_by_nick("text")
+            .unset_by_nick("video")
+            .build()
+            .unwrap();
+        player.set_property("flags", &flags)?;
+        player.set_property("uri", &uri)?;
+
+        // Panning is an extra, so like with music, a missing audiopanorama
+        // plugin only means sounds play centered.
+        let panorama = gstreamer::ElementFactory::make("audiopanorama", None).ok();
+        if let Some(panorama) = &panorama {
+            player.set_property("audio-filter", panorama)?;
+        }
+        player.set_property("audio-sink", &AudioDevice::sink_for(device, buffer)?)?;
+
+        AudioMixer::track(&player);
+        // Pausing prerolls the pipeline, decoding up to the first samples.
+        player.set_state(GstState::Paused)?;
+
+        Ok(Self {
+            player,
+            panorama,
+            playing: false,
+            started: 0,
+            volume: 1.0,
+        })
+    }
+
+    fn play(&mut self, volume: f64, gain: f64, pan: f32, started: u64) {
+        self.volume = volume.max(0.0);
+        self.set_volume(self.volume * gain);
+        if let Some(panorama) = &self.panorama {
+            let _ = panorama.set_property("panorama", &pan.max(-1.0).min(1.0));
+        }
+        if self.playing {
+            self.seek_start();
+        }
+        let _ = self.player.set_state(GstState::Playing);
+        self.playing = true;
+        self.started = started;
+    }
+
+    fn set_volume(&self, volume: f64) {
+        let _ = self.player.set_property("volume", &volume.max(0.0));
+    }
+
+    fn rewind(&mut self) {
+        let _ = self.player.set_state(GstState::Paused);
+        self.seek_start();
+        self.playing = false;
+    }
+
+    fn seek_start(&self) {
+        let seek_flags = SeekFlags::FLUSH | SeekFlags::ACCURATE;
+        let _ = self
+            .player
+            .seek_simple(seek_flags, ClockTime::from_seconds(0));
+    }
+
+    fn update(&mut self) -> Result<(), Option<String>> {
+        if let Some(bus) = self.player.get_bus() {
+            while let Some(msg) = bus.pop() {
+                match msg.view() {
+                    // Going back to the start right away keeps the voice
+                    // prerolled for its next play.
+                    MessageView::Eos(..) => self.rewind(),
+                    MessageView::Error(err) => {
+                        self.rewind();
+                        return Err(err.get_debug());
+                    }
+                    _ => {}
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Drop for Voice {
+    fn drop(&mut self) {
+        let _ = self.player.set_state(GstState::Null);
+    }
+}
+
+#[derive(Debug)]
+pub enum SoundFxCreateError {
+    GStreamerBoolError(BoolError),
+    GStreamerStateChangeError(StateChangeError),
+    IoError(IoError),
+    InvalidPath,
+    MissingElement(MissingElement),
+}
+
+impl Display for SoundFxCreateError {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match self {
+            Self::GStreamerBoolError(e) => e.fmt(f),
+            Self::GStreamerStateChangeError(e) => e.fmt(f),
+            Self::IoError(e) => e.fmt(f),
+            Self::InvalidPath => write!(f, "The path is not valid UTF-8"),
+            Self::MissingElement(e) => e.fmt(f),
+        }
+    }
+}
+
+impl StdError for SoundFxCreateError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match self {
+            Self::GStreamerBoolError(e) => Some(e),
+            Self::GStreamerStateChangeError(e) => Some(e),
+            Self::IoError(e) => Some(e),
+            Self::InvalidPath => None,
+            Self::MissingElement(e) => Some(e),
+        }
+    }
+}
+
+impl From<MissingElement> for SoundFxCreateError {
+    fn from(err: MissingElement) -> Self {
+        Self::MissingElement(err)
+    }
+}
+
+impl From<IoError> for SoundFxCreateError {
+    fn from(err: IoError) -> Self {
+        Self::IoError(err)
+    }
+}
+
+impl From<BoolError> for SoundFxCreateError {
+    fn from(err: BoolError) -> Self {
+        Self::GStreamerBoolError(err)
+    }
+}
+
+impl From<StateChangeError> for SoundFxCreateError {
+    fn from(err: StateChangeError) -> Self {
+        Self::GStreamerStateChangeError(err)
+    }
+}
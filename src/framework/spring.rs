@@ -0,0 +1,144 @@
+use std::time::Duration;
+
+use crate::game::State;
+
+/// A number pulled towards a target by a damped spring, for motion that
+/// follows the user instead of running for a fixed time like a
+/// [`Tween`](super::tween::Tween).
+///
+/// Changing the target mid-motion keeps the current velocity, so the value
+/// curves towards the new target instead of stopping and starting again.
+/// When a drag is released, giving the spring the drag's velocity with
+/// [`set_velocity`](Self::set_velocity) lets it carry on and settle with a
+/// bounce. Unlike a tween, a spring has to be advanced with
+/// [`update`](Self::update) once per update.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Spring {
+    pub stiffness: f32,
+    pub damping: f32,
+    pub mass: f32,
+    /// How close to the target and how slow the value has to be to count as
+    /// settled, at which point it snaps to the target
+    pub precision: f32,
+    value: f32,
+    velocity: f32,
+    target: f32,
+}
+
+impl Spring {
+    /// The longest a single integration step may be. Longer updates are
+    /// split into several steps, as stiff springs overshoot wildly when
+    /// stepped too coarsely.
+    pub const MAX_STEP: Duration = Duration::from_millis(4);
+    /// Updates longer than this, as after a hitch, only advance the spring
+    /// by this much, rather than having it jump
+    pub const MAX_DELTA: Duration = Duration::from_millis(100);
+
+    /// A spring resting at the given value, with a little bounce
+    pub fn new(value: f32) -> Self {
+        Self {
+            stiffness: 170.0,
+            damping: 26.0,
+            mass: 1.0,
+            precision: 0.01,
+            value,
+            velocity: 0.0,
+            target: value,
+        }
+    }
+
+    pub fn with_stiffness(mut self, stiffness: f32) -> Self {
+        self.stiffness = stiffness;
+        self
+    }
+
+    pub fn with_damping(mut self, damping: f32) -> Self {
+        self.damping = damping;
+        self
+    }
+
+    pub fn with_mass(mut self, mass: f32) -> Self {
+        self.mass = mass;
+        self
+    }
+
+    pub fn with_precision(mut self, precision: f32) -> Self {
+        self.precision = precision;
+        self
+    }
+
+    /// Set the damping so that the spring settles as quickly as possible
+    /// without overshooting, for the current stiffness and mass
+    pub fn critically_damped(mut self) -> Self {
+        self.damping = 2.0 * (self.stiffness * self.mass).sqrt();
+        self
+    }
+
+    pub fn value(&self) -> f32 {
+        self.value
+    }
+
+    pub fn target(&self) -> f32 {
+        self.target
+    }
+
+    /// In units per second
+    pub fn velocity(&self) -> f32 {
+        self.velocity
+    }
+
+    /// Start moving towards a new target, keeping the current velocity
+    pub fn set_target(&mut self, target: f32) {
+        self.target = target;
+    }
+
+    /// In units per second, like the velocity of a drag that was let go
+    pub fn set_velocity(&mut self, velocity: f32) {
+        self.velocity = velocity;
+    }
+
+    /// Jump to a value and rest there, without animating
+    pub fn set(&mut self, value: f32) {
+        self.value = value;
+        self.target = value;
+        self.velocity = 0.0;
+    }
+
+    pub fn is_settled(&self) -> bool {
+        self.value == self.target && self.velocity == 0.0
+    }
+
+    /// Advance by the time the last update took, with [`State::delta`].
+    /// Returns whether the spring is still moving.
+    pub fn update(&mut self) -> bool {
+        self.step(State::delta())
+    }
+
+    /// Advance by the given time. Returns whether the spring is still
+    /// moving.
+    pub fn step(&mut self, dt: Duration) -> bool {
+        if self.is_settled() {
+            return false;
+        }
+        let dt = dt.min(Self::MAX_DELTA).as_secs_f32();
+        let max_step = Self::MAX_STEP.as_secs_f32();
+        let steps = (dt / max_step).ceil().max(1.0);
+        let h = dt / steps;
+        let mass = self.mass.max(f32::EPSILON);
+        for _ in 0..steps as u32 {
+            // Semi-implicit Euler, which unlike the explicit kind doesn't
+            // gain energy, so a spring can't swing itself up
+            let force = -self.stiffness * (self.value - self.target) - self.damping * self.velocity;
+            self.velocity += force / mass * h;
+            self.value += self.velocity * h;
+        }
+        let close = (self.value - self.target).abs() < self.precision
+            && self.velocity.abs() < self.precision;
+        // Nonsensical parameters can blow up, which is better ended early
+        let broken = !self.value.is_finite() || !self.velocity.is_finite();
+        if close || broken {
+            self.set(self.target);
+        }
+        !self.is_settled()
+    }
+}
@@ -0,0 +1,51 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use crate::game::State;
+
+/// A flag shared between threads which is set once some work has completed.
+#[derive(Debug, Clone, Default)]
+pub struct DoneFlag(Arc<AtomicBool>);
+
+impl DoneFlag {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set(&self) {
+        // Release pairs with the Acquire in `is_done`, so whatever the work
+        // wrote is visible to whoever sees the flag set.
+        self.0.store(true, Ordering::Release);
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.0.load(Ordering::Acquire)
+    }
+}
+
+/// Sets the flag when dropped, so that it is set even if the work panics.
+struct SetOnDrop(DoneFlag);
+
+impl Drop for SetOnDrop {
+    fn drop(&mut self) {
+        self.0.set();
+    }
+}
+
+/// Run a closure on the background task pool, returning a flag which is set
+/// when it returns or panics. Use [`State::spawn_task`] instead when the
+/// result is needed.
+///
+/// This can only be called from the game thread, as it uses the game
+/// thread's task pool. A task that wants to start more work has to return
+/// and let the game thread spawn it, or start a thread of its own.
+pub fn run_in_background(f: impl FnOnce() + Send + 'static) -> DoneFlag {
+    let done = DoneFlag::new();
+    let guard = SetOnDrop(done.clone());
+    // Dropping the handle only discards the result, the task still runs.
+    let _ = State::spawn_task(move || {
+        let _guard = guard;
+        f();
+    });
+    done
+}
@@ -0,0 +1,279 @@
+use std::collections::HashMap;
+use std::process::Command;
+use std::time::Duration;
+
+use crate::prelude::*;
+use game::{State, TaskHandle};
+use skia::{scalar, Color4f, Paint};
+
+/// The colors a [`Theme`] is built around.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Palette {
+    pub primary: Color4f,
+    pub secondary: Color4f,
+    pub background: Color4f,
+    /// Panels, cards and other areas raised above the background
+    pub surface: Color4f,
+    pub text: Color4f,
+    /// Less prominent text, like hints and captions
+    pub muted: Color4f,
+    pub error: Color4f,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct FontSizes {
+    pub small: scalar,
+    pub regular: scalar,
+    pub large: scalar,
+    pub title: scalar,
+}
+
+impl Default for FontSizes {
+    fn default() -> Self {
+        Self {
+            small: 12.0,
+            regular: 16.0,
+            large: 20.0,
+            title: 28.0,
+        }
+    }
+}
+
+/// Shared styling for the whole UI, so that widgets can look up colors by
+/// role instead of every call site passing its own paints.
+///
+/// The current theme lives in [`State`](game::State), see
+/// [`State::with_theme`](game::State::with_theme) and
+/// [`State::set_theme`](game::State::set_theme). Besides the palette, paints
+/// can be registered under names for anything more specific.
+#[derive(Clone)]
+pub struct Theme {
+    pub palette: Palette,
+    pub font_sizes: FontSizes,
+    /// Whether shapes are anti-aliased when they don't say otherwise
+    pub anti_alias: bool,
+    paints: HashMap<String, Paint>,
+}
+
+impl Theme {
+    pub fn new(palette: Palette) -> Self {
+        Self {
+            palette,
+            font_sizes: FontSizes::default(),
+            anti_alias: true,
+            paints: HashMap::new(),
+        }
+    }
+
+    pub fn dark() -> Self {
+        Self::new(Palette {
+            primary: Color4f::new(0.36, 0.55, 0.96, 1.0),
+            secondary: Color4f::new(0.96, 0.62, 0.26, 1.0),
+            background: Color4f::new(0.04, 0.04, 0.04, 1.0),
+            surface: Color4f::new(0.12, 0.12, 0.13, 1.0),
+            text: Color4f::new(0.94, 0.94, 0.94, 1.0),
+            muted: Color4f::new(0.6, 0.6, 0.62, 1.0),
+            error: Color4f::new(0.94, 0.33, 0.31, 1.0),
+        })
+    }
+
+    pub fn light() -> Self {
+        Self::new(Palette {
+            primary: Color4f::new(0.15, 0.39, 0.92, 1.0),
+            secondary: Color4f::new(0.85, 0.45, 0.05, 1.0),
+            background: Color4f::new(0.98, 0.98, 0.98, 1.0),
+            surface: Color4f::new(1.0, 1.0, 1.0, 1.0),
+            text: Color4f::new(0.07, 0.07, 0.08, 1.0),
+            muted: Color4f::new(0.42, 0.42, 0.45, 1.0),
+            error: Color4f::new(0.8, 0.15, 0.15, 1.0),
+        })
+    }
+
+    pub fn with_font_sizes(mut self, font_sizes: FontSizes) -> Self {
+        self.font_sizes = font_sizes;
+        self
+    }
+
+    pub fn with_anti_alias(mut self, anti_alias: bool) -> Self {
+        self.anti_alias = anti_alias;
+        self
+    }
+
+    /// Register a paint under a name, replacing any paint already there
+    pub fn with_paint(mut self, name: impl Into<String>, paint: Paint) -> Self {
+        self.set_paint(name, paint);
+        self
+    }
+
+    pub fn set_paint(&mut self, name: impl Into<String>, paint: Paint) {
+        self.paints.insert(name.into(), paint);
+    }
+
+    /// A paint registered with [`with_paint`](Self::with_paint)
+    pub fn paint(&self, name: &str) -> Option<Paint> {
+        self.paints.get(name).cloned()
+    }
+
+    pub fn primary(&self) -> Paint {
+        Self::fill(self.palette.primary)
+    }
+
+    pub fn secondary(&self) -> Paint {
+        Self::fill(self.palette.secondary)
+    }
+
+    pub fn background(&self) -> Paint {
+        Self::fill(self.palette.background)
+    }
+
+    pub fn surface(&self) -> Paint {
+        Self::fill(self.palette.surface)
+    }
+
+    pub fn text(&self) -> Paint {
+        Self::fill(self.palette.text)
+    }
+
+    pub fn muted(&self) -> Paint {
+        Self::fill(self.palette.muted)
+    }
+
+    pub fn error(&self) -> Paint {
+        Self::fill(self.palette.error)
+    }
+
+    fn fill(c: Color4f) -> Paint {
+        let mut paint = Paint::new_color4f(c.r, c.g, c.b, c.a);
+        paint.set_anti_alias(true);
+        paint
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::dark()
+    }
+}
+
+/// Whether the operating system is set to prefer light or dark colors.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum ColorScheme {
+    Light,
+    Dark,
+}
+
+impl ColorScheme {
+    /// Ask the operating system for its color scheme preference. SDL has no
+    /// way to query this, so this asks the platform's settings tools, which
+    /// takes a few milliseconds. Returns `None` where the preference can't be
+    /// determined.
+    pub fn detect() -> Option<Self> {
+        if cfg!(target_os = "macos") {
+            // The key only exists while dark mode is on.
+            let out = Self::run("defaults", &["read", "-g", "AppleInterfaceStyle"]);
+            Some(match out {
+                Some(out) if out.contains("Dark") => Self::Dark,
+                _ => Self::Light,
+            })
+        } else if cfg!(target_os = "windows") {
+            let out = Self::run(
+                "reg",
+                &[
+                    "query",
+                    r"HKCU\Software\Microsoft\Windows\CurrentVersion\Themes\Personalize",
+                    "/v",
+                    "AppsUseLightTheme",
+                ],
+            )?;
+            if out.contains("0x0") {
+                Some(Self::Dark)
+            } else if out.contains("0x1") {
+                Some(Self::Light)
+            } else {
+                None
+            }
+        } else {
+            let scheme = Self::run(
+                "gsettings",
+                &["get", "org.gnome.desktop.interface", "color-scheme"],
+            );
+            match scheme {
+                Some(s) if s.contains("dark") => Some(Self::Dark),
+                Some(s) if s.contains("light") => Some(Self::Light),
+                // Older desktops only have a GTK theme name to go by.
+                _ => Self::run(
+                    "gsettings",
+                    &["get", "org.gnome.desktop.interface", "gtk-theme"],
+                )
+                .map(|s| {
+                    if s.to_lowercase().contains("dark") {
+                        Self::Dark
+                    } else {
+                        Self::Light
+                    }
+                }),
+            }
+        }
+    }
+
+    fn run(program: &str, args: &[&str]) -> Option<String> {
+        let output = Command::new(program).args(args).output().ok()?;
+        if output.status.success() {
+            String::from_utf8(output.stdout).ok()
+        } else {
+            None
+        }
+    }
+}
+
+/// Switches between a light and a dark theme following the operating
+/// system's color scheme, checking for changes every few seconds.
+pub(crate) struct ThemeFollower {
+    light: Theme,
+    dark: Theme,
+    task: Option<TaskHandle<Option<ColorScheme>>>,
+    last_check: Duration,
+}
+
+impl ThemeFollower {
+    const INTERVAL: Duration = Duration::from_secs(5);
+
+    pub fn new(light: Theme, dark: Theme) -> Self {
+        Self {
+            light,
+            dark,
+            task: None,
+            last_check: Duration::ZERO,
+        }
+    }
+
+    /// The theme to start with, detecting the color scheme right away
+    pub fn initial(&mut self) -> (Option<ColorScheme>, Theme) {
+        let scheme = ColorScheme::detect();
+        self.last_check = State::elapsed();
+        (scheme, self.theme_for(scheme))
+    }
+
+    /// Check on the background detection, and start a new one when due
+    pub fn poll(&mut self) {
+        if let Some(task) = &mut self.task {
+            if let Some(result) = task.poll() {
+                self.task = None;
+                let scheme = result.unwrap_or(None);
+                if scheme.is_some() && scheme != State::color_scheme() {
+                    State::set_color_scheme(scheme, self.theme_for(scheme));
+                }
+            }
+        } else if State::elapsed() - self.last_check >= Self::INTERVAL {
+            self.last_check = State::elapsed();
+            self.task = Some(State::spawn_task(ColorScheme::detect));
+        }
+    }
+
+    fn theme_for(&self, scheme: Option<ColorScheme>) -> Theme {
+        match scheme {
+            Some(ColorScheme::Light) => self.light.clone(),
+            _ => self.dark.clone(),
+        }
+    }
+}
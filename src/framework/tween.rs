@@ -0,0 +1,98 @@
+use std::time::Duration;
+
+use super::lerp::Lerp;
+use crate::game::State;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Easing {
+    Linear,
+    EaseIn,
+    EaseOut,
+    EaseInOut,
+}
+
+impl Easing {
+    /// Map linear progress in the 0..1 range onto this easing curve
+    pub fn apply(&self, t: f32) -> f32 {
+        let t = t.max(0.0).min(1.0);
+        match self {
+            Self::Linear => t,
+            Self::EaseIn => t * t * t,
+            Self::EaseOut => 1.0 - (1.0 - t).powi(3),
+            Self::EaseInOut => {
+                if t < 0.5 {
+                    4.0 * t * t * t
+                } else {
+                    1.0 - (-2.0 * t + 2.0).powi(3) * 0.5
+                }
+            }
+        }
+    }
+}
+
+/// A value animating towards a target over a fixed duration, timed by
+/// [`State::elapsed`]. Anything that implements [`Lerp`] can be animated,
+/// and numbers are the default.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Tween<T: Lerp + Clone = f32> {
+    pub easing: Easing,
+    from: T,
+    to: T,
+    start: Duration,
+    duration: Duration,
+}
+
+impl<T: Lerp + Clone> Tween<T> {
+    /// A tween resting at the given value
+    pub fn new(value: T) -> Self {
+        Self {
+            easing: Easing::EaseOut,
+            from: value.clone(),
+            to: value,
+            start: Duration::ZERO,
+            duration: Duration::ZERO,
+        }
+    }
+
+    pub fn with_easing(mut self, easing: Easing) -> Self {
+        self.easing = easing;
+        self
+    }
+
+    /// Start animating from the current value towards a new target. Calling
+    /// this again mid-animation picks up smoothly from wherever it is.
+    pub fn animate_to(&mut self, to: T, duration: Duration) {
+        self.from = self.value();
+        self.to = to;
+        self.start = State::elapsed();
+        self.duration = duration;
+    }
+
+    /// Jump to the given value without animating
+    pub fn set(&mut self, value: T) {
+        *self = Self::new(value).with_easing(self.easing);
+    }
+
+    pub fn value(&self) -> T {
+        let t = self.progress();
+        self.from.lerp(&self.to, self.easing.apply(t))
+    }
+
+    pub fn target(&self) -> T {
+        self.to.clone()
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.progress() >= 1.0
+    }
+
+    fn progress(&self) -> f32 {
+        if self.duration == Duration::ZERO {
+            return 1.0;
+        }
+        let elapsed = State::elapsed()
+            .checked_sub(self.start)
+            .unwrap_or(Duration::ZERO);
+        (elapsed.as_secs_f32() / self.duration.as_secs_f32()).min(1.0)
+    }
+}
@@ -1,20 +1,243 @@
+mod animated_number;
 mod audio_player;
+mod backdrop;
+mod badge;
+mod bind;
+mod border;
+mod color_filter;
+mod draggable;
+mod expandable;
+mod focusable;
+mod follow_transform;
+mod icon;
+mod layer;
 pub mod layout;
+mod loading;
+mod marquee;
+mod nine_patch;
+mod page_transition;
 mod parallax;
+mod particles;
+mod popup;
+mod reorderable_list;
+mod repeat_button;
+mod responsive;
+mod screen_fade;
+mod scroll_bar;
+mod scroll_view;
 pub mod shapes;
+mod skeleton;
+mod slider;
+mod split_pane;
+mod stepper;
+mod svg;
+mod tagged;
 mod text;
+mod text_field;
+mod tiled_image;
+mod timer;
 mod transform;
+mod transform_3d;
+mod typewriter;
+mod virtual_keyboard;
+mod zoomable;
 
+pub use animated_number::AnimatedNumber;
 pub use audio_player::AudioPlayer;
+pub use backdrop::Backdrop;
+pub use badge::Badge;
+pub use bind::Bind;
+pub use border::{Border, StrokeAlign};
+pub use color_filter::ColorFilter;
+pub use draggable::Draggable;
+pub use expandable::Expandable;
+pub use focusable::Focusable;
+pub use follow_transform::FollowTransform;
+pub use icon::{Icon, IconFont};
+pub use layer::Layer;
+pub use loading::Loading;
+pub use marquee::Marquee;
+pub use nine_patch::{Insets, NinePatch};
+pub use page_transition::{PageTransition, TransitionStyle};
 pub use parallax::Parallax;
-pub use text::{Font, FontStyle, Text};
+pub use particles::{Emission, ParticleConfig, Particles};
+pub use popup::{PopupHandle, PopupLayer};
+pub use reorderable_list::ReorderableList;
+pub use repeat_button::{RepeatButton, Repeater};
+pub use responsive::Responsive;
+pub use screen_fade::ScreenFade;
+pub use scroll_bar::ScrollBar;
+pub use scroll_view::{ScrollHandle, ScrollState, ScrollView};
+pub use skeleton::{Bone, Skeleton};
+pub use slider::{Slider, StepRange};
+pub use split_pane::SplitPane;
+pub use stepper::Stepper;
+pub use svg::{Svg, SvgError};
+pub use tagged::Tagged;
+pub use text::{Font, FontStyle, Text, TextAlign};
+pub use text_field::TextField;
+pub use tiled_image::TiledImage;
+pub use timer::{Timer, TimerFormat};
 pub use transform::Transform;
+pub use transform_3d::Transform3D;
+pub use typewriter::Typewriter;
+pub use virtual_keyboard::{KeyboardLayout, VirtualKey, VirtualKeyAction, VirtualKeyboard};
+pub use zoomable::Zoomable;
+
+use std::any::type_name;
+use std::mem::ManuallyDrop;
+use std::ptr;
+use std::time::Instant;
+
+use crate::framework::accessibility::{Accessibility, Role};
+use crate::game::{InputEvent, State, ID};
+use crate::skia::{scalar, Canvas, Matrix, Paint, Rect, Size, Vector};
+use crate::utils::PaintUtils;
+
+/// Implement [`Widget`] methods by passing them on to a child, for widgets
+/// that wrap a single [`Wrap`] and only change part of what it does.
+///
+/// Used inside an `impl Widget` block, with the name of the field holding
+/// the child and the methods to forward, like
+/// `forward_widget!(inner; size, set_size, height_for_width, get);`. Only
+/// the methods that do something different are then written out by hand.
+/// Methods that aren't listed or written keep the trait's defaults, so a
+/// child that should be drawn has to have `draw` forwarded. `on_mount` and
+/// `on_unmount` can't be forwarded, as the child's [`Wrap`] already takes
+/// care of them.
+#[macro_export]
+macro_rules! forward_widget {
+    ($field:ident; $($method:ident),* $(,)?) => {
+        $($crate::forward_widget!(@method $field $method);)*
+    };
+    (@method $field:ident update) => {
+        fn update(&mut self, _wrap: &mut $crate::framework::widgets::WrapState) {
+            self.$field.update();
+        }
+    };
+    (@method $field:ident input) => {
+        fn input(
+            &mut self,
+            _wrap: &mut $crate::framework::widgets::WrapState,
+            event: &$crate::game::InputEvent,
+        ) -> bool {
+            self.$field.input(event)
+        }
+    };
+    (@method $field:ident size) => {
+        fn size(
+            &mut self,
+            _wrap: &mut $crate::framework::widgets::WrapState,
+        ) -> ($crate::framework::widgets::LayoutSize, bool) {
+            self.$field.size()
+        }
+    };
+    (@method $field:ident set_size) => {
+        fn set_size(
+            &mut self,
+            _wrap: &mut $crate::framework::widgets::WrapState,
+            size: $crate::skia::Size,
+        ) {
+            self.$field.set_size(size);
+        }
+    };
+    (@method $field:ident height_for_width) => {
+        fn height_for_width(
+            &mut self,
+            _wrap: &mut $crate::framework::widgets::WrapState,
+            width: $crate::skia::scalar,
+        ) -> Option<$crate::skia::scalar> {
+            self.$field.height_for_width(width)
+        }
+    };
+    (@method $field:ident min_intrinsic_width) => {
+        fn min_intrinsic_width(
+            &mut self,
+            _wrap: &mut $crate::framework::widgets::WrapState,
+        ) -> $crate::skia::scalar {
+            self.$field.min_intrinsic_width()
+        }
+    };
+    (@method $field:ident max_intrinsic_width) => {
+        fn max_intrinsic_width(
+            &mut self,
+            _wrap: &mut $crate::framework::widgets::WrapState,
+        ) -> $crate::skia::scalar {
+            self.$field.max_intrinsic_width()
+        }
+    };
+    (@method $field:ident min_intrinsic_height) => {
+        fn min_intrinsic_height(
+            &mut self,
+            _wrap: &mut $crate::framework::widgets::WrapState,
+        ) -> $crate::skia::scalar {
+            self.$field.min_intrinsic_height()
+        }
+    };
+    (@method $field:ident max_intrinsic_height) => {
+        fn max_intrinsic_height(
+            &mut self,
+            _wrap: &mut $crate::framework::widgets::WrapState,
+        ) -> $crate::skia::scalar {
+            self.$field.max_intrinsic_height()
+        }
+    };
+    (@method $field:ident draw) => {
+        fn draw(
+            &mut self,
+            _wrap: &mut $crate::framework::widgets::WrapState,
+            canvas: &mut $crate::skia::Canvas,
+        ) {
+            self.$field.draw(canvas);
+        }
+    };
+    (@method $field:ident get) => {
+        fn get(
+            &mut self,
+            _wrap: &mut $crate::framework::widgets::WrapState,
+            id: $crate::game::ID,
+        ) -> Option<(
+            &mut dyn $crate::framework::widgets::Widget,
+            &mut $crate::framework::widgets::WrapState,
+        )> {
+            self.$field.get(id)
+        }
+    };
+}
 
-use crate::game::{InputEvent, ID};
-use crate::skia::{scalar, Canvas, Matrix, Rect, Size, Vector};
-
+/// The extension point for everything drawn on screen. Every method has a
+/// default that does nothing, so a widget only implements what it uses; see
+/// [`forward_widget!`](crate::forward_widget) for widgets wrapping a child.
 #[allow(unused_variables)]
 pub trait Widget: 'static + Send {
+    /// Called once when the widget enters the tree, for setup like starting
+    /// playback or subscribing to something.
+    ///
+    /// Mounting is lazy: adding a widget to a container doesn't mount it.
+    /// A widget is in the tree once its [`Wrap`] is first used by a parent,
+    /// so this runs right before the first call to any of `update`, `input`,
+    /// `size`, `set_size` or `draw`, whichever comes first, which is usually
+    /// the next frame's update. Widgets that are built but never shown are
+    /// never mounted. Children are mounted when their parent first passes a
+    /// call on to them, so after the parent.
+    ///
+    /// [`Keyed::sync`](layout::Keyed::sync) is the exception: once the
+    /// `Keyed` is mounted, the widgets of new keys are mounted during the
+    /// sync, in the order of the items, after the widgets of keys that are
+    /// gone were unmounted. Widgets of keys that stay are left as they are.
+    fn on_mount(&mut self, wrap: &mut WrapState) {}
+
+    /// Called once when a mounted widget leaves the tree, to release what
+    /// [`on_mount`](Widget::on_mount) set up.
+    ///
+    /// Leaving the tree is when the widget's [`Wrap`] is dropped, as when a
+    /// container removes the child, a [`Keyed`](layout::Keyed) sync drops
+    /// the widget of a key that is gone, or the whole tree is dropped at
+    /// exit, and when the widget is taken out of its `Wrap` with
+    /// [`Wrap::into_inner`]. The parent is unmounted before its children,
+    /// which are dropped with it.
+    fn on_unmount(&mut self, wrap: &mut WrapState) {}
+
     fn update(&mut self, wrap: &mut WrapState) {}
 
     fn input(&mut self, wrap: &mut WrapState, event: &InputEvent) -> bool {
@@ -27,6 +250,55 @@ pub trait Widget: 'static + Send {
 
     fn set_size(&mut self, wrap: &mut WrapState, size: Size) {}
 
+    /// The height the widget needs when given `width`, for widgets whose
+    /// height depends on their width, like wrapped text.
+    ///
+    /// This is the second pass of layout. [`size`](Widget::size) is asked
+    /// first, without knowing the space available, and its minimum height
+    /// can only reflect the last width the widget was given. Containers that
+    /// know a child's width before its height, as a [`VContainer`] does,
+    /// then ask this and treat the answer as the child's minimum height.
+    /// Expansion is unaffected: space left over after the measured heights
+    /// is still shared out by the expand factors. `None` means the height
+    /// doesn't depend on the width, and the minimum from
+    /// [`size`](Widget::size) is used as is.
+    ///
+    /// [`VContainer`]: layout::VContainer
+    fn height_for_width(&mut self, wrap: &mut WrapState, width: scalar) -> Option<scalar> {
+        None
+    }
+
+    /// The narrowest the widget can be without its content overflowing,
+    /// like the widest word of wrapped text. Unlike the allocated size,
+    /// this doesn't depend on where the widget is placed, so containers can
+    /// use it to give several widgets the same size. Defaults to the minimum
+    /// width last reported by [`size`](Widget::size).
+    fn min_intrinsic_width(&mut self, wrap: &mut WrapState) -> scalar {
+        wrap.layout_size().width.min
+    }
+
+    /// The width the widget would like to have, past which more space
+    /// doesn't help, like the length of text on a single line. Defaults to
+    /// the larger of the minimum and normal width last reported by
+    /// [`size`](Widget::size).
+    fn max_intrinsic_width(&mut self, wrap: &mut WrapState) -> scalar {
+        let width = wrap.layout_size().width;
+        width.min.max(width.size)
+    }
+
+    /// Like [`min_intrinsic_width`](Widget::min_intrinsic_width), for the
+    /// height
+    fn min_intrinsic_height(&mut self, wrap: &mut WrapState) -> scalar {
+        wrap.layout_size().height.min
+    }
+
+    /// Like [`max_intrinsic_width`](Widget::max_intrinsic_width), for the
+    /// height
+    fn max_intrinsic_height(&mut self, wrap: &mut WrapState) -> scalar {
+        let height = wrap.layout_size().height;
+        height.min.max(height.size)
+    }
+
     fn draw(&mut self, wrap: &mut WrapState, canvas: &mut Canvas) {}
 
     fn get(&mut self, wrap: &mut WrapState, id: ID) -> Option<(&mut dyn Widget, &mut WrapState)> {
@@ -35,6 +307,14 @@ pub trait Widget: 'static + Send {
 }
 
 impl Widget for Box<dyn Widget> {
+    fn on_mount(&mut self, wrap: &mut WrapState) {
+        self.as_mut().on_mount(wrap);
+    }
+
+    fn on_unmount(&mut self, wrap: &mut WrapState) {
+        self.as_mut().on_unmount(wrap);
+    }
+
     fn update(&mut self, wrap: &mut WrapState) {
         self.as_mut().update(wrap);
     }
@@ -51,6 +331,26 @@ impl Widget for Box<dyn Widget> {
         self.as_mut().set_size(wrap, size);
     }
 
+    fn height_for_width(&mut self, wrap: &mut WrapState, width: scalar) -> Option<scalar> {
+        self.as_mut().height_for_width(wrap, width)
+    }
+
+    fn min_intrinsic_width(&mut self, wrap: &mut WrapState) -> scalar {
+        self.as_mut().min_intrinsic_width(wrap)
+    }
+
+    fn max_intrinsic_width(&mut self, wrap: &mut WrapState) -> scalar {
+        self.as_mut().max_intrinsic_width(wrap)
+    }
+
+    fn min_intrinsic_height(&mut self, wrap: &mut WrapState) -> scalar {
+        self.as_mut().min_intrinsic_height(wrap)
+    }
+
+    fn max_intrinsic_height(&mut self, wrap: &mut WrapState) -> scalar {
+        self.as_mut().max_intrinsic_height(wrap)
+    }
+
     fn draw(&mut self, wrap: &mut WrapState, canvas: &mut Canvas) {
         self.as_mut().draw(wrap, canvas);
     }
@@ -73,24 +373,237 @@ impl<T: Widget> Wrap<T> {
         }
     }
 
+    /// Annotate the widget for assistive technology
+    pub fn with_accessibility(mut self, role: Role, label: impl Into<String>) -> Self {
+        self.state.accessibility = Some(Accessibility::new(role, label));
+        self
+    }
+
+    /// Clip everything the widget draws to its bounds
+    pub fn with_clip_to_bounds(mut self, clip: bool) -> Self {
+        self.state.clip_to_bounds = clip;
+        self
+    }
+
+    /// See [`WrapState::set_cull_margin`]
+    pub fn with_cull_margin(mut self, margin: Option<scalar>) -> Self {
+        self.state.cull_margin = margin;
+        self
+    }
+
+    /// Whether the widget is in the tree, see [`Widget::on_mount`]
+    pub fn is_mounted(&self) -> bool {
+        self.state.mounted
+    }
+
+    /// Take the widget out of the tree, unmounting it if it was mounted
+    pub fn into_inner(self) -> T {
+        let mut this = ManuallyDrop::new(self);
+        this.unmount();
+        // Moving the widget out of a type with a destructor isn't allowed,
+        // so it is read out and the rest dropped by hand instead, without
+        // running `Drop`, which would unmount it again
+        // SAFETY: `this` is never used again or dropped, as `ManuallyDrop`
+        // keeps `Wrap`'s `Drop` from running, so the widget is moved out
+        // exactly once by the read and the state dropped exactly once here.
+        // Skipping `Drop` only skips its unmount, which already happened
+        // above, so the widget isn't unmounted twice.
+        unsafe {
+            let inner = ptr::read(&this.inner);
+            ptr::drop_in_place(&mut this.state);
+            inner
+        }
+    }
+
+    pub(crate) fn mount(&mut self) {
+        if !self.state.mounted {
+            self.state.mounted = true;
+            self.inner.on_mount(&mut self.state);
+        }
+    }
+
+    fn unmount(&mut self) {
+        if self.state.mounted {
+            self.state.mounted = false;
+            self.inner.on_unmount(&mut self.state);
+        }
+    }
+
     pub fn update(&mut self) {
+        self.mount();
         self.inner.update(&mut self.state);
     }
 
     pub fn input(&mut self, event: &InputEvent) -> bool {
+        self.mount();
         self.inner.input(&mut self.state, event)
     }
 
     pub fn size(&mut self) -> (LayoutSize, bool) {
-        self.inner.size(&mut self.state)
+        self.mount();
+        let start = Self::begin_profile();
+        let s = self.inner.size(&mut self.state);
+        self.state.layout_size = s.0;
+        Self::end_profile(start, false);
+        s
     }
 
     pub fn set_size(&mut self, size: Size) {
+        self.mount();
+        let start = Self::begin_profile();
+        self.state.size = size;
         self.inner.set_size(&mut self.state, size);
+        Self::end_profile(start, false);
+    }
+
+    fn begin_profile() -> Option<Instant> {
+        State::with_mut(|x| {
+            if x.debug.profile_widgets {
+                x.profiler.begin();
+                Some(Instant::now())
+            } else {
+                None
+            }
+        })
+    }
+
+    fn end_profile(start: Option<Instant>, draw: bool) {
+        if let Some(start) = start {
+            let elapsed = start.elapsed();
+            let widget = type_name::<T>();
+            State::with_mut(|x| {
+                if draw {
+                    x.profiler.end_draw(widget, elapsed);
+                } else {
+                    x.profiler.end_layout(widget, elapsed);
+                }
+            });
+        }
+    }
+
+    pub fn height_for_width(&mut self, width: scalar) -> Option<scalar> {
+        self.inner.height_for_width(&mut self.state, width)
+    }
+
+    pub fn min_intrinsic_width(&mut self) -> scalar {
+        self.inner.min_intrinsic_width(&mut self.state)
+    }
+
+    pub fn max_intrinsic_width(&mut self) -> scalar {
+        self.inner.max_intrinsic_width(&mut self.state)
+    }
+
+    pub fn min_intrinsic_height(&mut self) -> scalar {
+        self.inner.min_intrinsic_height(&mut self.state)
+    }
+
+    pub fn max_intrinsic_height(&mut self) -> scalar {
+        self.inner.max_intrinsic_height(&mut self.state)
     }
 
     pub fn draw(&mut self, canvas: &mut Canvas) {
-        self.inner.draw(&mut self.state, canvas);
+        self.mount();
+        // Where the widget ends up is only known once everything above it
+        // has applied its transforms, which is when it is drawn.
+        let (bounds, _) = State::window_matrix(canvas).map_rect(Rect::from_size(self.state.size));
+        self.state.bounds = bounds;
+        let (cull, outer) = State::with_mut(|x| {
+            let cull = x.cull_offscreen && !x.drawing_culled;
+            (cull, std::mem::take(&mut x.drew_unbounded))
+        });
+        if cull && self.is_out_of_view(canvas) {
+            self.draw_culled(canvas, bounds);
+        } else {
+            self.draw_in_view(canvas, bounds);
+        }
+        // A widget drawing past its bounds can reach past the bounds of
+        // everything around it too, so they all stop being culled
+        let unbounded = State::with_mut(|x| {
+            let unbounded = x.drew_unbounded || self.state.cull_margin.is_none();
+            x.drew_unbounded = outer || unbounded;
+            unbounded
+        });
+        self.state.unbounded = unbounded;
+    }
+
+    /// Draw the widget onto a canvas without pixels, with the same matrix
+    /// as the real one. Nothing of it is recorded into the frame, but the
+    /// widgets inside still register their bounds, focus, accessibility and
+    /// hit testing as they would when seen, so an offscreen row can still be
+    /// focused and scrolled to or read out.
+    fn draw_culled(&mut self, canvas: &mut Canvas, bounds: Rect) {
+        let mut blank = match Canvas::new(canvas.base_layer_size(), None) {
+            Some(blank) => blank,
+            None => {
+                State::with_mut(|x| x.bounds.insert(self.state.id, bounds));
+                return;
+            }
+        };
+        blank.concat(&canvas.total_matrix());
+        State::with_mut(|x| x.drawing_culled = true);
+        self.draw_in_view(&mut blank, bounds);
+        State::with_mut(|x| x.drawing_culled = false);
+    }
+
+    fn draw_in_view(&mut self, canvas: &mut Canvas, bounds: Rect) {
+        let start = Self::begin_profile();
+        let hovered = State::with_mut(|x| {
+            x.bounds.insert(self.state.id, bounds);
+            if let Some(a) = &self.state.accessibility {
+                x.accessibility.open(self.state.id, a, bounds);
+            }
+            if x.debug.hit_test {
+                let mouse = x.input_state.raw_mouse_position;
+                x.hit_test
+                    .visit(self.state.id, type_name::<T>(), bounds, mouse);
+                x.hit_test.is_hovered(self.state.id)
+            } else {
+                false
+            }
+        });
+        if self.state.clip_to_bounds {
+            canvas.save();
+            canvas.clip_rect(Rect::from_size(self.state.size), None, None);
+            self.inner.draw(&mut self.state, canvas);
+            canvas.restore();
+        } else {
+            self.inner.draw(&mut self.state, canvas);
+        }
+        if self.state.accessibility.is_some() {
+            State::with_mut(|x| x.accessibility.close());
+        }
+        Self::end_profile(start, true);
+        let debug = State::debug();
+        if debug.layout_bounds {
+            self.state.draw_layout_bounds(canvas, debug.layout_labels);
+        }
+        if hovered {
+            self.state.draw_hit_test(canvas, type_name::<T>());
+        }
+    }
+
+    /// Whether the widget, grown by its cull margin, is entirely outside of
+    /// the canvas's clip, so nothing it draws could be seen. Widgets that
+    /// had something without a cull margin inside them last frame are never
+    /// out of view.
+    fn is_out_of_view(&self, canvas: &Canvas) -> bool {
+        let margin = match self.state.cull_margin {
+            Some(margin) if !self.state.unbounded => margin,
+            _ => return false,
+        };
+        let clip = match canvas.device_clip_bounds() {
+            Some(clip) => clip,
+            None => return true,
+        };
+        let (reach, _) = canvas
+            .total_matrix()
+            .map_rect(Rect::from_size(self.state.size).with_outset(Vector::new(margin, margin)));
+        // Touching the clip counts as in view, so that widgets without a
+        // size, which may still draw, aren't lost on the edge.
+        reach.right < clip.left as scalar
+            || reach.left > clip.right as scalar
+            || reach.bottom < clip.top as scalar
+            || reach.top > clip.bottom as scalar
     }
 
     pub fn get(&mut self, id: ID) -> Option<(&mut dyn Widget, &mut WrapState)> {
@@ -102,6 +615,14 @@ impl<T: Widget> Wrap<T> {
     }
 }
 
+/// Unmounting on drop is what lets removal from any container count as
+/// leaving the tree, without containers having to report it
+impl<T: Widget> Drop for Wrap<T> {
+    fn drop(&mut self) {
+        self.unmount();
+    }
+}
+
 impl<T: Widget> From<T> for Wrap<T> {
     fn from(t: T) -> Self {
         Self::new(t)
@@ -126,11 +647,171 @@ impl<T: 'static + Widget> Wrappable<T> for T {
 #[derive(Debug)]
 pub struct WrapState {
     id: ID,
+    layout_size: LayoutSize,
+    size: Size,
+    bounds: Rect,
+    collapsed: bool,
+    split_ratio: scalar,
+    zoom: scalar,
+    pan: Vector,
+    clip_to_bounds: bool,
+    cull_margin: Option<scalar>,
+    /// Whether the widget or anything inside it had no cull margin when it
+    /// was last drawn
+    unbounded: bool,
+    accessibility: Option<Accessibility>,
+    mounted: bool,
 }
 
 impl WrapState {
     pub fn new() -> Self {
-        Self { id: ID::next() }
+        Self {
+            id: ID::next(),
+            layout_size: LayoutSize::ZERO,
+            size: Size::new_empty(),
+            bounds: Rect::new_empty(),
+            collapsed: false,
+            split_ratio: 0.5,
+            zoom: 1.0,
+            pan: Vector::new(0.0, 0.0),
+            clip_to_bounds: false,
+            cull_margin: Some(0.0),
+            unbounded: false,
+            accessibility: None,
+            mounted: false,
+        }
+    }
+
+    pub fn id(&self) -> ID {
+        self.id
+    }
+
+    /// Whether a collapsible widget such as [`Expandable`] is folded away.
+    /// Kept here so that it can be changed from outside by looking the
+    /// widget up with [`Wrap::get`].
+    pub fn is_collapsed(&self) -> bool {
+        self.collapsed
+    }
+
+    pub fn set_collapsed(&mut self, collapsed: bool) {
+        self.collapsed = collapsed;
+    }
+
+    /// The share of the space given to the first pane of a [`SplitPane`],
+    /// from 0 to 1. Half by default.
+    pub fn split_ratio(&self) -> scalar {
+        self.split_ratio
+    }
+
+    pub fn set_split_ratio(&mut self, ratio: scalar) {
+        self.split_ratio = ratio.max(0.0).min(1.0);
+    }
+
+    /// How far a [`Zoomable`] is zoomed in, 1 by default
+    pub fn zoom(&self) -> scalar {
+        self.zoom
+    }
+
+    pub fn set_zoom(&mut self, zoom: scalar) {
+        self.zoom = zoom;
+    }
+
+    /// Where a [`Zoomable`] puts the origin of its child, after zooming
+    pub fn pan(&self) -> Vector {
+        self.pan
+    }
+
+    pub fn set_pan(&mut self, pan: Vector) {
+        self.pan = pan;
+    }
+
+    /// Whether the widget's drawing is clipped to its bounds, so it can't
+    /// bleed over its siblings. Off by default.
+    pub fn clips_to_bounds(&self) -> bool {
+        self.clip_to_bounds
+    }
+
+    pub fn set_clip_to_bounds(&mut self, clip: bool) {
+        self.clip_to_bounds = clip;
+    }
+
+    /// How far past its bounds the widget may draw, for skipping it when
+    /// it is out of view, see
+    /// [`Builder::cull_offscreen`](crate::game::Builder::cull_offscreen).
+    /// `None` means it is always drawn, for widgets whose drawing isn't
+    /// bounded by their size, like those that move their child, and so are
+    /// the widgets around it from the next frame on. 0 by default.
+    pub fn cull_margin(&self) -> Option<scalar> {
+        self.cull_margin
+    }
+
+    pub fn set_cull_margin(&mut self, margin: Option<scalar>) {
+        self.cull_margin = margin;
+    }
+
+    pub fn accessibility(&self) -> Option<&Accessibility> {
+        self.accessibility.as_ref()
+    }
+
+    /// Change the widget's role and label, for example when a button's
+    /// label changes, or remove them with `None`
+    pub fn set_accessibility(&mut self, accessibility: Option<Accessibility>) {
+        self.accessibility = accessibility;
+    }
+
+    /// The layout size last reported by the widget
+    pub fn layout_size(&self) -> LayoutSize {
+        self.layout_size
+    }
+
+    /// The size last allocated to the widget
+    pub fn size(&self) -> Size {
+        self.size
+    }
+
+    /// Where the widget was last drawn, in canvas coordinates. Empty until
+    /// it is first drawn. For a transformed widget this is the bounding box
+    /// of its transformed bounds.
+    pub fn bounds(&self) -> Rect {
+        self.bounds
+    }
+
+    fn draw_layout_bounds(&self, canvas: &mut Canvas, labels: bool) {
+        let bounds = Paint::new_color4f(0.0, 1.0, 1.0, 0.8).stroke_style();
+        let min = Paint::new_color4f(1.0, 0.0, 1.0, 0.6).stroke_style();
+        canvas.draw_rect(Rect::from_size(self.size), &bounds);
+        canvas.draw_rect(
+            Rect::from_wh(self.layout_size.width.min, self.layout_size.height.min),
+            &min,
+        );
+        if labels {
+            let font = Font::Default.resolve(&FontStyle::Regular);
+            let text = format!("{}x{}", self.size.width, self.size.height);
+            let fill = Paint::new_color4f(0.0, 1.0, 1.0, 0.8);
+            canvas.draw_str(text, (2.0, font.size()), &font, &fill);
+        }
+    }
+
+    fn draw_hit_test(&self, canvas: &mut Canvas, name: &str) {
+        let rect = Rect::from_size(self.size);
+        canvas.draw_rect(rect, &Paint::new_color4f(1.0, 0.8, 0.0, 0.2));
+        let outline = Paint::new_color4f(1.0, 0.8, 0.0, 1.0)
+            .stroke_style()
+            .with_stroke_width(2.0);
+        canvas.draw_rect(rect, &outline);
+        let font = Font::Default.resolve(&FontStyle::Regular);
+        // Module paths make the names long, the last part is enough to tell
+        // widgets apart
+        let short = name.split('<').next().unwrap_or(name);
+        let short = short.rsplit("::").next().unwrap_or(short);
+        let text = format!("{} {:?}", short, self.id);
+        let fill = Paint::new_color4f(1.0, 0.8, 0.0, 1.0);
+        canvas.draw_str(
+            text,
+            (2.0, rect.bottom - font.metrics().1.descent),
+            &font,
+            &fill,
+        );
     }
 }
 
@@ -140,6 +821,12 @@ impl Default for WrapState {
     }
 }
 
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum Orientation {
+    Horizontal,
+    Vertical,
+}
+
 #[derive(Copy, Clone, PartialEq, Debug)]
 pub struct LayoutSize {
     pub width: LayoutDimension,
@@ -0,0 +1,151 @@
+use std::time::Duration;
+
+use crate::framework::tween::{Easing, Tween};
+use crate::prelude::*;
+use game::State;
+
+use super::Text;
+
+/// A number shown in a [`Text`] that rolls to its new value when changed,
+/// for scores and counters.
+///
+/// Setting a value while it's still rolling starts a new roll from wherever
+/// the shown number is, so rapid updates keep moving smoothly instead of
+/// jumping. The value is kept as an `f64` so large scores count exactly.
+pub struct AnimatedNumber {
+    pub inner: Wrap<Text>,
+    pub duration: Duration,
+    /// Digits shown after the decimal point
+    pub decimals: usize,
+    /// Put between groups of three digits, if set
+    pub separator: Option<char>,
+    pub decimal_point: char,
+    value: Tween<f64>,
+    /// The text last put into the `Text`
+    shown: Option<String>,
+}
+
+impl AnimatedNumber {
+    /// Show `value` in `text`, replacing whatever text it had
+    pub fn new(text: impl Into<Wrap<Text>>, value: f64) -> Self {
+        let mut number = Self {
+            inner: text.into(),
+            duration: Duration::from_millis(500),
+            decimals: 0,
+            separator: None,
+            decimal_point: '.',
+            value: Tween::new(value),
+            shown: None,
+        };
+        number.refresh();
+        number
+    }
+
+    pub fn with_duration(mut self, duration: Duration) -> Self {
+        self.duration = duration;
+        self
+    }
+
+    pub fn with_easing(mut self, easing: Easing) -> Self {
+        self.value.easing = easing;
+        self
+    }
+
+    pub fn with_decimals(mut self, decimals: usize) -> Self {
+        self.decimals = decimals;
+        self.refresh();
+        self
+    }
+
+    /// Group the digits in threes, like `1,234,567`
+    pub fn with_separator(mut self, separator: char) -> Self {
+        self.separator = Some(separator);
+        self.refresh();
+        self
+    }
+
+    pub fn with_decimal_point(mut self, decimal_point: char) -> Self {
+        self.decimal_point = decimal_point;
+        self.refresh();
+        self
+    }
+
+    /// The value being rolled to
+    pub fn value(&self) -> f64 {
+        self.value.target()
+    }
+
+    /// The value currently shown
+    pub fn shown_value(&self) -> f64 {
+        self.value.value()
+    }
+
+    /// Roll to a new value
+    pub fn set_value(&mut self, value: f64) {
+        if value == self.value.target() {
+            return;
+        }
+        self.value.animate_to(value, self.duration);
+        State::request_redraw();
+    }
+
+    /// Show a new value straight away, without rolling
+    pub fn jump_to(&mut self, value: f64) {
+        self.value.set(value);
+        self.refresh();
+    }
+
+    pub fn is_rolling(&self) -> bool {
+        !self.value.is_finished()
+    }
+
+    pub fn format(&self, value: f64) -> String {
+        let formatted = format!("{:.*}", self.decimals, value.abs());
+        let (whole, fraction) = match formatted.find('.') {
+            Some(i) => (&formatted[..i], Some(&formatted[i + 1..])),
+            None => (&formatted[..], None),
+        };
+        let mut out = String::with_capacity(formatted.len() + whole.len() / 3 + 1);
+        // Rounding can make small negative numbers show as zero, which
+        // shouldn't get a sign
+        if value < 0.0 && formatted.bytes().any(|b| b.is_ascii_digit() && b != b'0') {
+            out.push('-');
+        }
+        for (i, c) in whole.chars().enumerate() {
+            if i > 0 && (whole.len() - i) % 3 == 0 {
+                if let Some(separator) = self.separator {
+                    out.push(separator);
+                }
+            }
+            out.push(c);
+        }
+        if let Some(fraction) = fraction {
+            out.push(self.decimal_point);
+            out.push_str(fraction);
+        }
+        out
+    }
+
+    /// Update the text to the shown value. Most frames of a roll only
+    /// change the value below what is shown, so the text is only shaped
+    /// again when the formatted number changes.
+    fn refresh(&mut self) {
+        let text = self.format(self.shown_value());
+        if self.shown.as_ref() != Some(&text) {
+            self.inner.inner.set_text(&text);
+            self.shown = Some(text);
+        }
+    }
+}
+
+impl Widget for AnimatedNumber {
+    fn update(&mut self, _wrap: &mut WrapState) {
+        self.refresh();
+        if self.is_rolling() {
+            State::request_redraw();
+        }
+        self.inner.update();
+    }
+
+    forward_widget!(inner; input, size, set_size, height_for_width, draw, get);
+}
@@ -1,49 +1,192 @@
+use std::time::Duration;
+
+use crate::framework::music::MusicCreateError;
 use crate::prelude::*;
-use game::InputEvent;
-use skia::{Canvas, Contains, Paint, Rect, Size};
+use game::{InputEvent, State};
+use skia::{scalar, Canvas, Contains, Paint, RRect, Rect, Size};
 use skulpin_renderer_sdl2::sdl2::{keyboard::Keycode, mouse::MouseButton};
 
+use super::{Font, FontStyle, Text, TextAlign};
+
+/// A progress bar for a track that seeks when clicked.
+///
+/// Dragging scrubs through the track, with a bubble above the bar showing
+/// the time that will be seeked to, also shown while hovering. The seek
+/// only happens on release so that the pipeline isn't flushed on every
+/// mouse move, unless [`continuous_seek`](Self::continuous_seek) is set.
 pub struct AudioPlayer {
     pub layout_size: LayoutSize,
     pub foreground: Paint,
     pub background: Paint,
+    /// The mouse button that seeks when clicking the player
+    pub seek_button: MouseButton,
+    /// Seek while dragging instead of only on release
+    pub continuous_seek: bool,
+    /// The time shown above the bar while hovering or dragging
+    pub preview: Wrap<Text>,
     size: Size,
-    music: Music,
+    /// `None` if the track couldn't be loaded
+    music: Option<Music>,
+    /// Where the mouse is over the bar, from 0 to 1
+    hover: Option<scalar>,
+    /// Where the bar is being dragged to, from 0 to 1
+    drag: Option<scalar>,
 }
 
 impl AudioPlayer {
     pub fn new(size: LayoutSize, foreground: Paint, background: Paint) -> Self {
-        let music = Music::new("./src/resources/sound.ogg").unwrap();
+        // A missing plugin means no audio can play at all, so that stops
+        // the app with the reason. Anything else only concerns this track,
+        // and the player is left without one.
+        let music = match Music::new("./src/resources/sound.ogg") {
+            Ok(music) => Some(music),
+            Err(e @ MusicCreateError::MissingElement(_)) => {
+                State::crash(e);
+                None
+            }
+            Err(e) => {
+                eprintln!("Failed to load the audio player's track: {}", e);
+                None
+            }
+        };
+        let text_paint = State::with_theme(|t| t.text());
+        let preview = Text::new(
+            LayoutSize::ZERO,
+            "",
+            Font::Default,
+            FontStyle::Regular,
+            text_paint,
+        )
+        .with_align(TextAlign::Center);
         Self {
             layout_size: size,
             size: Size::new_empty(),
             foreground,
             background,
+            seek_button: MouseButton::Left,
+            continuous_seek: false,
+            preview: preview.into(),
             music,
+            hover: None,
+            drag: None,
+        }
+    }
+
+    pub fn with_continuous_seek(mut self, continuous: bool) -> Self {
+        self.continuous_seek = continuous;
+        self
+    }
+
+    /// Whether the bar is being dragged
+    pub fn is_scrubbing(&self) -> bool {
+        self.drag.is_some()
+    }
+
+    /// Minutes and seconds, like `03:07`
+    pub fn format_time(time: Duration) -> String {
+        let seconds = time.as_secs();
+        format!("{:02}:{:02}", seconds / 60, seconds % 60)
+    }
+
+    fn seek(&self, fraction: scalar) {
+        if let Some(music) = &self.music {
+            music.seek_percentage(fraction);
         }
     }
+
+    fn fraction_at(&self, x: scalar) -> scalar {
+        if self.size.width <= 0.0 {
+            0.0
+        } else {
+            (x / self.size.width).max(0.0).min(1.0)
+        }
+    }
+
+    fn draw_preview(&mut self, canvas: &mut Canvas, fraction: scalar) {
+        let duration = match self.music.as_ref().and_then(Music::duration) {
+            Some(d) => d,
+            None => return,
+        };
+        let label = Self::format_time(duration.mul_f32(fraction));
+        if self.preview.inner.text() != label {
+            self.preview.inner.set_text(&label);
+        }
+        let font = &self.preview.inner.font;
+        let padding = 4.0;
+        let (width, _) = font.measure_str(&label, None);
+        let bubble = Size::new(width + padding * 2.0, font.spacing() + padding);
+        // Kept over the bar at the ends, rather than hanging off its sides
+        let x = (self.size.width * fraction - bubble.width / 2.0)
+            .min(self.size.width - bubble.width)
+            .max(0.0);
+        let y = -bubble.height - padding;
+        let rect = Rect::from_xywh(x, y, bubble.width, bubble.height);
+        canvas.draw_rrect(RRect::new_rect_xy(rect, 4.0, 4.0), &self.background);
+        canvas.save();
+        canvas.translate((x, y + padding / 2.0));
+        self.preview
+            .set_size(Size::new(bubble.width, bubble.height - padding));
+        self.preview.draw(canvas);
+        canvas.restore();
+    }
 }
 
 impl Widget for AudioPlayer {
     fn update(&mut self, _wrap: &mut WrapState) {
-        if let Err(Some(s)) = self.music.update() {
-            eprintln!("Music player received error: {}", s);
+        if let Some(music) = &mut self.music {
+            if let Err(Some(s)) = music.update() {
+                eprintln!("Music player received error: {}", s);
+            }
         }
     }
 
     fn input(&mut self, _wrap: &mut WrapState, event: &InputEvent) -> bool {
         match event {
-            InputEvent::KeyDown(Keycode::Space) => {
-                self.music.toggle_playing();
+            InputEvent::KeyDown(Keycode::Space, _) => {
+                if let Some(music) = &mut self.music {
+                    music.toggle_playing();
+                }
                 true
             }
-            InputEvent::MouseDown(MouseButton::Left, pos) => {
+            InputEvent::MouseDown(button, pos, _) if *button == self.seek_button => {
                 let c = Rect::from_size(self.size).contains(*pos);
                 if c {
-                    self.music.seek_percentage(pos.x / self.size.width);
+                    let fraction = self.fraction_at(pos.x);
+                    self.drag = Some(fraction);
+                    if self.continuous_seek {
+                        self.seek(fraction);
+                    }
+                    State::request_redraw();
                 }
                 c
             }
+            InputEvent::MouseMove(pos, _) => {
+                let fraction = self.fraction_at(pos.x);
+                if self.drag.is_some() {
+                    self.drag = Some(fraction);
+                    if self.continuous_seek {
+                        self.seek(fraction);
+                    }
+                    State::request_redraw();
+                    return true;
+                }
+                let hover = Some(fraction).filter(|_| Rect::from_size(self.size).contains(*pos));
+                if hover != self.hover {
+                    self.hover = hover;
+                    State::request_redraw();
+                }
+                false
+            }
+            InputEvent::MouseUp(button, ..) if *button == self.seek_button => {
+                match self.drag.take() {
+                    Some(fraction) => {
+                        self.seek(fraction);
+                        State::request_redraw();
+                        true
+                    }
+                    None => false,
+                }
+            }
             _ => false,
         }
     }
@@ -58,9 +201,17 @@ impl Widget for AudioPlayer {
 
     fn draw(&mut self, _wrap: &mut WrapState, canvas: &mut Canvas) {
         canvas.draw_rect(Rect::from_size(self.size), &self.background);
-        if let Some(percentage) = self.music.position_percentage() {
+        // While dragging, the bar follows the mouse rather than the track,
+        // which hasn't been seeked yet
+        let percentage = self
+            .drag
+            .or_else(|| self.music.as_ref()?.position_percentage());
+        if let Some(percentage) = percentage {
             let foreground = Rect::from_wh(self.size.width * percentage, self.size.height);
             canvas.draw_rect(foreground, &self.foreground);
         }
+        if let Some(fraction) = self.drag.or(self.hover) {
+            self.draw_preview(canvas, fraction);
+        }
     }
 }
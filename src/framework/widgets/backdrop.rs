@@ -0,0 +1,84 @@
+use crate::prelude::*;
+use skia::{
+    canvas::SaveLayerRec, image_filters, scalar, Canvas, ImageFilter, Paint, RRect, Rect, Size,
+};
+
+/// Blurs whatever has already been drawn behind it, then draws its child on
+/// top, for frosted glass panels.
+///
+/// The blur only covers the backdrop's own bounds. An optional tint is drawn
+/// over the blurred backdrop, below the child.
+pub struct Backdrop<T: Widget> {
+    pub inner: Wrap<T>,
+    pub tint: Option<Paint>,
+    pub radius: scalar,
+    sigma: scalar,
+    filter: Option<ImageFilter>,
+    size: Size,
+}
+
+impl<T: Widget> Backdrop<T> {
+    pub fn new(inner: impl Into<Wrap<T>>, sigma: scalar) -> Self {
+        Self {
+            inner: inner.into(),
+            tint: None,
+            radius: 0.0,
+            sigma,
+            filter: image_filters::blur((sigma, sigma), None, None, None),
+            size: Size::new_empty(),
+        }
+    }
+
+    pub fn with_tint(mut self, tint: Paint) -> Self {
+        self.tint = Some(tint);
+        self
+    }
+
+    /// Round the corners of the blurred area
+    pub fn with_radius(mut self, radius: scalar) -> Self {
+        self.radius = radius;
+        self
+    }
+
+    pub fn sigma(&self) -> scalar {
+        self.sigma
+    }
+
+    pub fn set_sigma(&mut self, sigma: scalar) {
+        self.sigma = sigma;
+        self.filter = image_filters::blur((sigma, sigma), None, None, None);
+    }
+}
+
+impl<T: Widget> Widget for Backdrop<T> {
+    forward_widget!(inner; update, input, size, height_for_width, get);
+
+    fn set_size(&mut self, _wrap: &mut WrapState, size: Size) {
+        self.size = size;
+        self.inner.set_size(size);
+    }
+
+    fn draw(&mut self, _wrap: &mut WrapState, canvas: &mut Canvas) {
+        let bounds = Rect::from_size(self.size);
+        if let Some(filter) = &self.filter {
+            canvas.save();
+            if self.radius > 0.0 {
+                let rrect = RRect::new_rect_xy(bounds, self.radius, self.radius);
+                canvas.clip_rrect(rrect, None, Some(true));
+            } else {
+                canvas.clip_rect(bounds, None, None);
+            }
+            // A layer with a backdrop filter starts out as a filtered copy of
+            // what is beneath it, which is drawn back when it is restored.
+            canvas.save_layer(&SaveLayerRec::default().bounds(&bounds).backdrop(filter));
+            if let Some(tint) = &self.tint {
+                canvas.draw_rect(bounds, tint);
+            }
+            canvas.restore();
+            canvas.restore();
+        } else if let Some(tint) = &self.tint {
+            canvas.draw_rect(bounds, tint);
+        }
+        self.inner.draw(canvas);
+    }
+}
@@ -0,0 +1,107 @@
+use crate::prelude::*;
+use game::State;
+use skia::{Canvas, Font as SkFont, Paint, RRect, Rect, Size};
+
+use super::{Font, FontStyle};
+
+/// Draws a count in a small pill over the top right corner of its child, like
+/// the number of unread messages on an icon.
+///
+/// The badge is centered on the corner and grows with the number of digits,
+/// counts past `max` are shown as `max+`, and nothing is drawn at zero. It
+/// takes no layout space, so the child is laid out as if it weren't there,
+/// and sticks out of the child's bounds unless they are clipped.
+pub struct Badge<T: Widget> {
+    pub inner: Wrap<T>,
+    pub count: u32,
+    pub max: u32,
+    /// The pill's paint, the theme's error color by default
+    pub paint: Paint,
+    pub text_paint: Paint,
+    pub font: SkFont,
+    size: Size,
+}
+
+impl<T: Widget> Badge<T> {
+    pub fn new(inner: impl Into<Wrap<T>>, count: u32) -> Self {
+        let (paint, text_paint, font_size) =
+            State::with_theme(|t| (t.error(), t.text(), t.font_sizes.small));
+        let mut font = Font::Default.resolve(&FontStyle::Bold);
+        font.set_size(font_size);
+        Self {
+            inner: inner.into(),
+            count,
+            max: 99,
+            paint,
+            text_paint,
+            font,
+            size: Size::new_empty(),
+        }
+    }
+
+    pub fn with_max(mut self, max: u32) -> Self {
+        self.max = max;
+        self
+    }
+
+    pub fn with_paint(mut self, paint: Paint) -> Self {
+        self.paint = paint;
+        self
+    }
+
+    pub fn with_text_paint(mut self, paint: Paint) -> Self {
+        self.text_paint = paint;
+        self
+    }
+
+    pub fn set_count(&mut self, count: u32) {
+        if self.count != count {
+            self.count = count;
+            State::request_redraw();
+        }
+    }
+
+    fn label(&self) -> String {
+        if self.count > self.max {
+            format!("{}+", self.max)
+        } else {
+            self.count.to_string()
+        }
+    }
+}
+
+impl<T: Widget> Widget for Badge<T> {
+    // The badge sticks out of the corner
+    fn on_mount(&mut self, wrap: &mut WrapState) {
+        wrap.set_cull_margin(None);
+    }
+
+    forward_widget!(inner; update, input, size, height_for_width, get);
+
+    fn set_size(&mut self, _wrap: &mut WrapState, size: Size) {
+        self.size = size;
+        self.inner.set_size(size);
+    }
+
+    fn draw(&mut self, _wrap: &mut WrapState, canvas: &mut Canvas) {
+        self.inner.draw(canvas);
+        if self.count == 0 {
+            return;
+        }
+        let label = self.label();
+        let (text_width, bounds) = self.font.measure_str(&label, Some(&self.text_paint));
+        let (_, metrics) = self.font.metrics();
+        let height = (metrics.descent - metrics.ascent) * 1.25;
+        // A single digit gets a circle, wider counts stretch it into a pill
+        let padding = height * 0.3;
+        let width = (text_width + padding * 2.0).max(height);
+        let rect = Rect::from_xywh(self.size.width - width * 0.5, -height * 0.5, width, height);
+        canvas.draw_rrect(
+            RRect::new_rect_xy(rect, height * 0.5, height * 0.5),
+            &self.paint,
+        );
+        let x = rect.center_x() - bounds.width() * 0.5 - bounds.left;
+        let y = rect.center_y() - bounds.height() * 0.5 - bounds.top;
+        canvas.draw_str(&label, (x, y), &self.font, &self.text_paint);
+    }
+}
@@ -0,0 +1,73 @@
+use crate::framework::observable::Observable;
+use crate::prelude::*;
+use game::State;
+
+/// Keeps its child in sync with an [`Observable`], calling a closure to apply
+/// the value whenever it changes, for example to set the text of a label
+/// showing a score.
+///
+/// The value is applied during update. Changes are laid out again and
+/// redrawn right away, even while the frame rate is lowered when idle.
+pub struct Bind<W: Widget, T: 'static> {
+    pub inner: Wrap<W>,
+    source: Observable<T>,
+    apply: Box<dyn FnMut(&mut W, &T) + Send>,
+    seen: Option<u64>,
+    changed: bool,
+}
+
+impl<W: Widget, T: 'static + Send> Bind<W, T> {
+    pub fn new(
+        inner: impl Into<Wrap<W>>,
+        source: Observable<T>,
+        apply: impl FnMut(&mut W, &T) + Send + 'static,
+    ) -> Self {
+        let mut bind = Self {
+            inner: inner.into(),
+            source,
+            apply: Box::new(apply),
+            seen: None,
+            changed: false,
+        };
+        bind.refresh();
+        bind
+    }
+
+    pub fn source(&self) -> &Observable<T> {
+        &self.source
+    }
+
+    fn refresh(&mut self) {
+        let version = self.source.version();
+        if self.seen == Some(version) {
+            return;
+        }
+        let Self {
+            inner,
+            source,
+            apply,
+            ..
+        } = self;
+        source.with(|x| apply(&mut inner.inner, x));
+        // Anything changed in between is picked up next time around.
+        self.seen = Some(version);
+        self.changed = true;
+    }
+}
+
+impl<W: Widget, T: 'static + Send> Widget for Bind<W, T> {
+    fn update(&mut self, _wrap: &mut WrapState) {
+        self.refresh();
+        if self.changed {
+            State::request_redraw();
+        }
+        self.inner.update();
+    }
+
+    forward_widget!(inner; input, set_size, height_for_width, draw, get);
+
+    fn size(&mut self, _wrap: &mut WrapState) -> (LayoutSize, bool) {
+        let (s, c) = self.inner.size();
+        (s, c || std::mem::take(&mut self.changed))
+    }
+}
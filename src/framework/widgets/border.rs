@@ -0,0 +1,137 @@
+use crate::prelude::*;
+use game::InputEvent;
+use skia::{scalar, Canvas, Matrix, Paint, PaintStyle, Rect, Size, Vector};
+
+/// Where a [`Border`] stroke is drawn relative to the bounds of its child.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum StrokeAlign {
+    Inside,
+    Center,
+    Outside,
+}
+
+/// Strokes an outline around its child.
+pub struct Border<T: Widget> {
+    pub inner: Wrap<T>,
+    pub paint: Paint,
+    pub width: scalar,
+    pub radius: scalar,
+    pub align: StrokeAlign,
+    /// Whether the border reserves layout space around the child. When
+    /// `false`, the child's layout is left untouched.
+    pub padded: bool,
+    size: Size,
+}
+
+impl<T: Widget> Border<T> {
+    pub fn new(inner: impl Into<Wrap<T>>, paint: Paint, width: scalar) -> Self {
+        Self {
+            inner: inner.into(),
+            paint,
+            width,
+            radius: 0.0,
+            align: StrokeAlign::Inside,
+            padded: false,
+            size: Size::new_empty(),
+        }
+    }
+
+    pub fn with_radius(mut self, radius: scalar) -> Self {
+        self.radius = radius;
+        self
+    }
+
+    pub fn with_align(mut self, align: StrokeAlign) -> Self {
+        self.align = align;
+        self
+    }
+
+    pub fn padded(mut self) -> Self {
+        self.padded = true;
+        self
+    }
+
+    fn padding(&self) -> scalar {
+        if self.padded {
+            self.width
+        } else {
+            0.0
+        }
+    }
+
+    /// How far the stroke reaches past the bounds
+    fn overhang(&self) -> scalar {
+        let reach = match self.align {
+            StrokeAlign::Inside => 0.0,
+            StrokeAlign::Center => self.width * 0.5,
+            StrokeAlign::Outside => self.width,
+        };
+        (reach - self.padding()).max(0.0)
+    }
+}
+
+impl<T: Widget> Widget for Border<T> {
+    fn update(&mut self, wrap: &mut WrapState) {
+        wrap.set_cull_margin(Some(self.overhang()));
+        self.inner.update();
+    }
+
+    fn input(&mut self, _wrap: &mut WrapState, event: &InputEvent) -> bool {
+        let p = self.padding();
+        event
+            .reverse_map_position(Matrix::translate((p, p)))
+            .map_or(false, |event| self.inner.input(&event))
+    }
+
+    fn size(&mut self, _wrap: &mut WrapState) -> (LayoutSize, bool) {
+        let (mut s, c) = self.inner.size();
+        let p = self.padding() * 2.0;
+        s.width.min += p;
+        s.width.size += p;
+        s.height.min += p;
+        s.height.size += p;
+        (s, c)
+    }
+
+    fn set_size(&mut self, _wrap: &mut WrapState, size: Size) {
+        self.size = size;
+        let p = self.padding() * 2.0;
+        self.inner.set_size(Size::new(
+            (size.width - p).max(0.0),
+            (size.height - p).max(0.0),
+        ));
+    }
+
+    fn height_for_width(&mut self, _wrap: &mut WrapState, width: scalar) -> Option<scalar> {
+        let p = self.padding() * 2.0;
+        self.inner
+            .height_for_width((width - p).max(0.0))
+            .map(|h| h + p)
+    }
+
+    fn draw(&mut self, _wrap: &mut WrapState, canvas: &mut Canvas) {
+        let p = self.padding();
+        canvas.save();
+        canvas.concat(&Matrix::translate((p, p)));
+        self.inner.draw(canvas);
+        canvas.restore();
+
+        let bounds = Rect::from_size(self.size).with_inset(Vector::new(p, p));
+        let half = self.width * 0.5;
+        let rect = match self.align {
+            StrokeAlign::Inside => bounds.with_inset(Vector::new(half, half)),
+            StrokeAlign::Center => bounds,
+            StrokeAlign::Outside => bounds.with_outset(Vector::new(half, half)),
+        };
+        let mut paint = self.paint.clone();
+        paint.set_style(PaintStyle::Stroke);
+        paint.set_stroke_width(self.width);
+        if self.radius > 0.0 {
+            canvas.draw_round_rect(rect, self.radius, self.radius, &paint);
+        } else {
+            canvas.draw_rect(rect, &paint);
+        }
+    }
+
+    forward_widget!(inner; get);
+}
@@ -0,0 +1,89 @@
+use crate::prelude::*;
+use skia::{
+    canvas::SaveLayerRec, color_filters, scalar, Canvas, ColorFilter as SkColorFilter, Paint,
+};
+
+/// Draws its child through a color filter, for effects like desaturating
+/// disabled controls or tinting an image.
+pub struct ColorFilter<T: Widget> {
+    pub inner: Wrap<T>,
+    paint: Paint,
+}
+
+impl<T: Widget> ColorFilter<T> {
+    pub fn new(inner: impl Into<Wrap<T>>, filter: SkColorFilter) -> Self {
+        let mut paint = Paint::default();
+        paint.set_color_filter(filter);
+        Self {
+            inner: inner.into(),
+            paint,
+        }
+    }
+
+    /// Apply a 4x5 color matrix in row major order. Each row computes one of
+    /// the red, green, blue and alpha outputs from the inputs, with the last
+    /// column being an offset in the 0 to 1 range.
+    pub fn matrix(inner: impl Into<Wrap<T>>, matrix: &[scalar; 20]) -> Self {
+        Self::new(inner, color_filters::matrix_row_major(matrix))
+    }
+
+    pub fn grayscale(inner: impl Into<Wrap<T>>) -> Self {
+        // Rec. 709 luma weights
+        let (r, g, b) = (0.2126, 0.7152, 0.0722);
+        #[rustfmt::skip]
+        let matrix = [
+            r, g, b, 0.0, 0.0,
+            r, g, b, 0.0, 0.0,
+            r, g, b, 0.0, 0.0,
+            0.0, 0.0, 0.0, 1.0, 0.0,
+        ];
+        Self::matrix(inner, &matrix)
+    }
+
+    pub fn invert(inner: impl Into<Wrap<T>>) -> Self {
+        #[rustfmt::skip]
+        let matrix = [
+            -1.0, 0.0, 0.0, 0.0, 1.0,
+            0.0, -1.0, 0.0, 0.0, 1.0,
+            0.0, 0.0, -1.0, 0.0, 1.0,
+            0.0, 0.0, 0.0, 1.0, 0.0,
+        ];
+        Self::matrix(inner, &matrix)
+    }
+
+    /// Brightness is added to every channel, so 0 leaves colors unchanged.
+    /// Contrast scales colors around the middle gray, so 1 leaves them
+    /// unchanged.
+    pub fn brightness_contrast(
+        inner: impl Into<Wrap<T>>,
+        brightness: scalar,
+        contrast: scalar,
+    ) -> Self {
+        let c = contrast;
+        let o = brightness + 0.5 * (1.0 - contrast);
+        #[rustfmt::skip]
+        let matrix = [
+            c, 0.0, 0.0, 0.0, o,
+            0.0, c, 0.0, 0.0, o,
+            0.0, 0.0, c, 0.0, o,
+            0.0, 0.0, 0.0, 1.0, 0.0,
+        ];
+        Self::matrix(inner, &matrix)
+    }
+
+    pub fn set_filter(&mut self, filter: SkColorFilter) {
+        self.paint.set_color_filter(filter);
+    }
+}
+
+impl<T: Widget> Widget for ColorFilter<T> {
+    forward_widget!(inner; update, input, size, set_size, height_for_width, get);
+
+    fn draw(&mut self, _wrap: &mut WrapState, canvas: &mut Canvas) {
+        // Filtering the child's draws one by one would get overlaps wrong,
+        // so it is flattened into a layer first.
+        canvas.save_layer(&SaveLayerRec::default().paint(&self.paint));
+        self.inner.draw(canvas);
+        canvas.restore();
+    }
+}
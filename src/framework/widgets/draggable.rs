@@ -0,0 +1,175 @@
+use crate::framework::snapping::{Guide, Snapping};
+use crate::prelude::*;
+use game::{InputEvent, MouseButton, State};
+use skia::{Canvas, Contains, Matrix, Paint, Point, Rect, Size, Vector};
+
+/// Lets its child be moved around freely with the mouse, for layout editors
+/// and other canvas-style tools.
+///
+/// The draggable fills the space it is given, and its child is shrunk to
+/// its minimum size and placed at `position` within it. While the child is
+/// dragged, its position can be snapped with a [`Snapping`], and the guides
+/// it lines up with are drawn across the whole area if a guide paint is
+/// set. Call [`snapping_mut`](Self::snapping_mut) to keep the snap lines up
+/// to date with the other elements being edited.
+pub struct Draggable<T: Widget> {
+    pub inner: Wrap<T>,
+    pub layout_size: LayoutSize,
+    pub position: Vector,
+    pub snapping: Option<Snapping>,
+    pub guide_paint: Option<Paint>,
+    on_move: Option<Box<dyn FnMut(Vector) + Send>>,
+    /// Where the child was grabbed, from its top left corner
+    grab: Option<Vector>,
+    guides: Vec<Guide>,
+    child_size: Size,
+    size: Size,
+}
+
+impl<T: Widget> Draggable<T> {
+    pub fn new(inner: impl Into<Wrap<T>>, size: LayoutSize, position: impl Into<Vector>) -> Self {
+        Self {
+            inner: inner.into(),
+            layout_size: size,
+            position: position.into(),
+            snapping: None,
+            guide_paint: None,
+            on_move: None,
+            grab: None,
+            guides: Vec::new(),
+            child_size: Size::new_empty(),
+            size: Size::new_empty(),
+        }
+    }
+
+    pub fn with_snapping(mut self, snapping: Snapping) -> Self {
+        self.snapping = Some(snapping);
+        self
+    }
+
+    pub fn with_guide_paint(mut self, paint: Paint) -> Self {
+        self.guide_paint = Some(paint);
+        self
+    }
+
+    /// Called with the new position whenever the child is moved
+    pub fn with_on_move(mut self, f: impl FnMut(Vector) + Send + 'static) -> Self {
+        self.on_move = Some(Box::new(f));
+        self
+    }
+
+    pub fn snapping_mut(&mut self) -> Option<&mut Snapping> {
+        self.snapping.as_mut()
+    }
+
+    pub fn is_dragging(&self) -> bool {
+        self.grab.is_some()
+    }
+
+    /// The guides the child lines up with while it is dragged
+    pub fn guides(&self) -> &[Guide] {
+        &self.guides
+    }
+
+    /// Where the child is, in the draggable's coordinates
+    pub fn child_rect(&self) -> Rect {
+        Rect::from_point_and_size(
+            Point::new(self.position.x, self.position.y),
+            self.child_size,
+        )
+    }
+
+    fn move_to(&mut self, p: Point, grab: Vector) {
+        let rect = Rect::from_point_and_size(p - grab, self.child_size);
+        let (rect, guides) = match &self.snapping {
+            Some(snapping) => {
+                let snapped = snapping.snap(rect);
+                (snapped.rect, snapped.guides)
+            }
+            None => (rect, Vec::new()),
+        };
+        self.guides = guides;
+        let position = Vector::new(rect.left, rect.top);
+        if position != self.position {
+            self.position = position;
+            if let Some(f) = &mut self.on_move {
+                f(position);
+            }
+        }
+        State::request_redraw();
+    }
+
+    fn layout_child(&mut self) {
+        let (s, changed) = self.inner.size();
+        let size = Size::new(s.width.min, s.height.min);
+        if changed || size != self.child_size {
+            self.child_size = size;
+            self.inner.set_size(size);
+        }
+    }
+}
+
+impl<T: Widget> Widget for Draggable<T> {
+    // The child can be dragged anywhere
+    fn on_mount(&mut self, wrap: &mut WrapState) {
+        wrap.set_cull_margin(None);
+    }
+
+    forward_widget!(inner; update, get);
+
+    fn input(&mut self, _wrap: &mut WrapState, event: &InputEvent) -> bool {
+        match event {
+            InputEvent::MouseDown(MouseButton::Left, p, _) if self.child_rect().contains(*p) => {
+                let local = event.reverse_map_position(Matrix::translate(self.position));
+                // The child gets the first chance to use the press, like
+                // a button inside of a draggable panel
+                if local.map_or(false, |e| self.inner.input(&e)) {
+                    return true;
+                }
+                self.grab = Some(*p - Point::new(self.position.x, self.position.y));
+                return true;
+            }
+            InputEvent::MouseMove(p, _) => {
+                if let Some(grab) = self.grab {
+                    self.move_to(*p, grab);
+                    return true;
+                }
+            }
+            InputEvent::MouseUp(MouseButton::Left, ..) if self.grab.is_some() => {
+                self.grab = None;
+                self.guides.clear();
+                State::request_redraw();
+                return true;
+            }
+            _ => {}
+        }
+        event
+            .reverse_map_position(Matrix::translate(self.position))
+            .map_or(false, |e| self.inner.input(&e))
+    }
+
+    fn size(&mut self, _wrap: &mut WrapState) -> (LayoutSize, bool) {
+        self.layout_child();
+        (self.layout_size, false)
+    }
+
+    fn set_size(&mut self, _wrap: &mut WrapState, size: Size) {
+        self.size = size;
+    }
+
+    fn draw(&mut self, _wrap: &mut WrapState, canvas: &mut Canvas) {
+        canvas.save();
+        canvas.translate(self.position);
+        self.inner.draw(canvas);
+        canvas.restore();
+        if let Some(paint) = &self.guide_paint {
+            for guide in &self.guides {
+                let (from, to) = match *guide {
+                    Guide::Vertical(x) => ((x, 0.0), (x, self.size.height)),
+                    Guide::Horizontal(y) => ((0.0, y), (self.size.width, y)),
+                };
+                canvas.draw_line(from, to, paint);
+            }
+        }
+    }
+}
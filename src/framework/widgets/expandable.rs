@@ -0,0 +1,181 @@
+use std::time::Duration;
+
+use crate::framework::tween::Tween;
+use crate::prelude::*;
+use game::{InputEvent, MouseButton, ID};
+use skia::{scalar, Canvas, Contains, Matrix, Rect, Size};
+
+/// A header with a body below it that folds away when the header is clicked.
+///
+/// The body slides open and closed by animating its visible height between
+/// zero and its minimum height, so containers holding several of these move
+/// their other children along smoothly. Whether it is collapsed is kept in
+/// its [`WrapState`], see [`WrapState::set_collapsed`].
+pub struct Expandable<H: Widget, B: Widget> {
+    pub header: Wrap<H>,
+    pub body: Wrap<B>,
+    pub duration: Duration,
+    pub button: MouseButton,
+    /// How far open the body is, from 0 to 1
+    openness: Tween,
+    laid_out: bool,
+    header_height: scalar,
+    body_height: scalar,
+    width: scalar,
+}
+
+impl<H: Widget, B: Widget> Expandable<H, B> {
+    pub fn new(header: impl Into<Wrap<H>>, body: impl Into<Wrap<B>>) -> Self {
+        Self {
+            header: header.into(),
+            body: body.into(),
+            duration: Duration::from_millis(200),
+            button: MouseButton::Left,
+            openness: Tween::new(1.0),
+            laid_out: false,
+            header_height: 0.0,
+            body_height: 0.0,
+            width: 0.0,
+        }
+    }
+
+    pub fn with_duration(mut self, duration: Duration) -> Self {
+        self.duration = duration;
+        self
+    }
+
+    pub fn with_button(mut self, button: MouseButton) -> Self {
+        self.button = button;
+        self
+    }
+
+    /// Start animating towards the collapsed state stored in the wrap. Before
+    /// the first layout it's applied immediately, so widgets that start out
+    /// collapsed don't animate shut when first shown.
+    fn sync(&mut self, wrap: &WrapState) {
+        let target = if wrap.is_collapsed() { 0.0 } else { 1.0 };
+        if self.openness.target() != target {
+            if self.laid_out {
+                self.openness.animate_to(target, self.duration);
+            } else {
+                self.openness.set(target);
+            }
+        }
+    }
+
+    fn visible_height(&self) -> scalar {
+        self.body_height * self.openness.value()
+    }
+
+    fn body_matrix(&self) -> Matrix {
+        Matrix::translate((0.0, self.header_height))
+    }
+
+    /// The height of a child at a width, at least its minimum height
+    fn child_height<T: Widget>(child: &mut Wrap<T>, width: scalar) -> scalar {
+        let min = child.state.layout_size().height.min;
+        child.height_for_width(width).map_or(min, |h| h.max(min))
+    }
+}
+
+impl<H: Widget, B: Widget> Widget for Expandable<H, B> {
+    fn update(&mut self, wrap: &mut WrapState) {
+        self.sync(wrap);
+        self.header.update();
+        self.body.update();
+    }
+
+    fn input(&mut self, wrap: &mut WrapState, event: &InputEvent) -> bool {
+        if self.header.input(event) {
+            return true;
+        }
+        let header = Rect::from_wh(self.width, self.header_height);
+        if let InputEvent::MouseDown(b, p, _) = event {
+            if *b == self.button && header.contains(*p) {
+                wrap.set_collapsed(!wrap.is_collapsed());
+                self.sync(wrap);
+                return true;
+            }
+        }
+        let visible = self.visible_height();
+        if visible <= 0.0 {
+            return false;
+        }
+        if let Some(p) = event.position() {
+            let body = Rect::from_xywh(0.0, self.header_height, self.width, visible);
+            let release = matches!(event, InputEvent::MouseUp(..));
+            if !release && !body.contains(p) {
+                return false;
+            }
+        }
+        event
+            .reverse_map_position(self.body_matrix())
+            .map_or(false, |event| self.body.input(&event))
+    }
+
+    fn size(&mut self, wrap: &mut WrapState) -> (LayoutSize, bool) {
+        self.sync(wrap);
+        let (h, hc) = self.header.size();
+        let (b, bc) = self.body.size();
+        let openness = self.openness.value();
+        (
+            LayoutSize {
+                width: LayoutDimension {
+                    min: h.width.min.max(b.width.min),
+                    size: h.width.size.max(b.width.size),
+                    expand: h.width.expand.or(b.width.expand),
+                },
+                height: LayoutDimension {
+                    min: h.height.min + b.height.min * openness,
+                    size: h.height.size + b.height.size * openness,
+                    expand: None,
+                },
+            },
+            hc || bc,
+        )
+    }
+
+    fn set_size(&mut self, _wrap: &mut WrapState, size: Size) {
+        self.laid_out = true;
+        self.width = size.width;
+        self.header_height = Self::child_height(&mut self.header, size.width);
+        self.body_height = Self::child_height(&mut self.body, size.width);
+        self.header
+            .set_size(Size::new(size.width, self.header_height));
+        self.body.set_size(Size::new(size.width, self.body_height));
+    }
+
+    fn height_for_width(&mut self, _wrap: &mut WrapState, width: scalar) -> Option<scalar> {
+        let header = self.header.height_for_width(width);
+        let body = self.body.height_for_width(width);
+        if header.is_none() && body.is_none() {
+            return None;
+        }
+        let header = Self::child_height(&mut self.header, width);
+        let body = Self::child_height(&mut self.body, width);
+        Some(header + body * self.openness.value())
+    }
+
+    fn draw(&mut self, _wrap: &mut WrapState, canvas: &mut Canvas) {
+        self.header.draw(canvas);
+        let visible = self.visible_height();
+        if visible > 0.0 {
+            canvas.save();
+            canvas.clip_rect(
+                Rect::from_xywh(0.0, self.header_height, self.width, visible),
+                None,
+                None,
+            );
+            canvas.concat(&self.body_matrix());
+            self.body.draw(canvas);
+            canvas.restore();
+        }
+    }
+
+    fn get(&mut self, _wrap: &mut WrapState, id: ID) -> Option<(&mut dyn Widget, &mut WrapState)> {
+        if let Some(x) = self.header.get(id) {
+            return Some(x);
+        }
+        self.body.get(id)
+    }
+}
@@ -0,0 +1,101 @@
+use crate::prelude::*;
+use game::{InputEvent, MouseButton, State};
+use skia::{scalar, Canvas, Contains, Paint, Point, RRect, Rect, Size};
+use skulpin_renderer_sdl2::sdl2::keyboard::Keycode;
+
+/// Lets its child take keyboard focus, through Tab, the arrow keys or a
+/// click. See [`Focus`](game::Focus) for how focus moves.
+///
+/// While focused, Enter and Space activate the child with a click at its
+/// center, so buttons work from the keyboard without knowing about focus.
+/// A ring is drawn around the child when focus was moved there with the
+/// keyboard, in the theme's primary color unless a paint is set.
+pub struct Focusable<T: Widget> {
+    pub inner: Wrap<T>,
+    pub ring: Option<Paint>,
+    pub ring_width: scalar,
+    /// How far the ring is drawn outside of the child's bounds
+    pub ring_offset: scalar,
+    pub ring_radius: scalar,
+    size: Size,
+}
+
+impl<T: Widget> Focusable<T> {
+    pub fn new(inner: impl Into<Wrap<T>>) -> Self {
+        Self {
+            inner: inner.into(),
+            ring: None,
+            ring_width: 2.0,
+            ring_offset: 2.0,
+            ring_radius: 4.0,
+            size: Size::new_empty(),
+        }
+    }
+
+    pub fn with_ring(mut self, paint: Paint) -> Self {
+        self.ring = Some(paint);
+        self
+    }
+
+    fn draw_ring(&self, canvas: &mut Canvas) {
+        let paint = self
+            .ring
+            .clone()
+            .unwrap_or_else(|| State::with_theme(|t| t.primary()))
+            .stroke_style()
+            .with_stroke_width(self.ring_width)
+            .anti_alias();
+        let rect = Rect::from_size(self.size).with_outset((self.ring_offset, self.ring_offset));
+        let rrect = RRect::new_rect_xy(rect, self.ring_radius, self.ring_radius);
+        canvas.draw_rrect(rrect, &paint);
+    }
+}
+
+impl<T: Widget> Widget for Focusable<T> {
+    fn update(&mut self, wrap: &mut WrapState) {
+        // The ring is drawn around the widget
+        wrap.set_cull_margin(Some(self.ring_offset + self.ring_width / 2.0));
+        self.inner.update();
+    }
+
+    fn input(&mut self, wrap: &mut WrapState, event: &InputEvent) -> bool {
+        let focused = State::focused() == Some(wrap.id());
+        match event {
+            InputEvent::MouseDown(_, p, _) if Rect::from_size(self.size).contains(*p) => {
+                State::set_focus(Some(wrap.id()));
+            }
+            InputEvent::KeyDown(Keycode::Return, _) | InputEvent::KeyDown(Keycode::Space, _)
+                if focused =>
+            {
+                let center = Point::new(self.size.width / 2.0, self.size.height / 2.0);
+                let time = event.time();
+                self.inner
+                    .input(&InputEvent::MouseDown(MouseButton::Left, center, time));
+                self.inner
+                    .input(&InputEvent::MouseUp(MouseButton::Left, center, time));
+                return true;
+            }
+            _ => {}
+        }
+        self.inner.input(event)
+    }
+
+    forward_widget!(inner; size, height_for_width, get);
+
+    fn set_size(&mut self, _wrap: &mut WrapState, size: Size) {
+        self.size = size;
+        self.inner.set_size(size);
+    }
+
+    fn draw(&mut self, wrap: &mut WrapState, canvas: &mut Canvas) {
+        let id = wrap.id();
+        let show_ring = State::with_mut(|x| {
+            x.focus.register(id, wrap.bounds());
+            x.focus.focused() == Some(id) && x.focus.is_visible()
+        });
+        self.inner.draw(canvas);
+        if show_ring {
+            self.draw_ring(canvas);
+        }
+    }
+}
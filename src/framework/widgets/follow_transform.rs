@@ -0,0 +1,123 @@
+use crate::prelude::*;
+use game::{InputEvent, State, ID};
+use skia::{Canvas, Matrix, Point, Size, Vector};
+
+/// Draws its child next to another widget, wherever that widget ends up on
+/// screen, for tooltips, callouts and the like.
+///
+/// The target is found by its [`ID`] through [`State::widget_bounds`]. The
+/// anchor is a point on the target and the pivot a point on the child, both
+/// relative to their bounds from 0 to 1; the child is moved so that its
+/// pivot lands on the target's anchor, plus an offset. The child is shrunk to
+/// its minimum size and takes up no space of its own, so this is best put in
+/// a container that stacks its children, or at the root. Nothing is drawn
+/// while the target hasn't been drawn.
+pub struct FollowTransform<T: Widget> {
+    pub inner: Wrap<T>,
+    pub target: ID,
+    pub anchor: Point,
+    pub pivot: Point,
+    pub offset: Vector,
+    position: Option<Point>,
+    size: Size,
+}
+
+impl<T: Widget> FollowTransform<T> {
+    /// Follow the target with the child's top left corner on the target's
+    /// bottom left corner
+    pub fn new(inner: impl Into<Wrap<T>>, target: ID) -> Self {
+        Self {
+            inner: inner.into(),
+            target,
+            anchor: Point::new(0.0, 1.0),
+            pivot: Point::new(0.0, 0.0),
+            offset: Vector::new(0.0, 0.0),
+            position: None,
+            size: Size::new_empty(),
+        }
+    }
+
+    pub fn with_anchor(mut self, anchor: impl Into<Point>) -> Self {
+        self.anchor = anchor.into();
+        self
+    }
+
+    pub fn with_pivot(mut self, pivot: impl Into<Point>) -> Self {
+        self.pivot = pivot.into();
+        self
+    }
+
+    pub fn with_offset(mut self, offset: impl Into<Vector>) -> Self {
+        self.offset = offset.into();
+        self
+    }
+
+    fn layout_child(&mut self) {
+        let (s, changed) = self.inner.size();
+        let size = Size::new(s.width.min, s.height.min);
+        if changed || size != self.size {
+            self.size = size;
+            self.inner.set_size(size);
+        }
+    }
+
+    /// Where the child goes in local coordinates, given the transform the
+    /// widget is drawn with
+    fn place(&self, matrix: &Matrix) -> Option<Point> {
+        let target = State::widget_bounds(self.target)?;
+        let anchor = Point::new(
+            target.left + target.width() * self.anchor.x,
+            target.top + target.height() * self.anchor.y,
+        );
+        let anchor = matrix.invert()?.map_point(anchor);
+        let pivot = Vector::new(
+            self.size.width * self.pivot.x,
+            self.size.height * self.pivot.y,
+        );
+        Some(anchor - pivot + self.offset)
+    }
+}
+
+impl<T: Widget> Widget for FollowTransform<T> {
+    // The child is drawn by the target, not within the own bounds
+    fn on_mount(&mut self, wrap: &mut WrapState) {
+        wrap.set_cull_margin(None);
+    }
+
+    fn update(&mut self, _wrap: &mut WrapState) {
+        self.layout_child();
+        self.inner.update();
+    }
+
+    fn input(&mut self, _wrap: &mut WrapState, event: &InputEvent) -> bool {
+        let position = match self.position {
+            Some(position) => position,
+            None => return false,
+        };
+        event
+            .reverse_map_position(Matrix::translate(position))
+            .map_or(false, |event| self.inner.input(&event))
+    }
+
+    fn size(&mut self, _wrap: &mut WrapState) -> (LayoutSize, bool) {
+        (LayoutSize::ZERO, false)
+    }
+
+    fn set_size(&mut self, _wrap: &mut WrapState, _size: Size) {
+        self.layout_child();
+    }
+
+    fn draw(&mut self, _wrap: &mut WrapState, canvas: &mut Canvas) {
+        // The target may have moved since the last frame, so the position is
+        // worked out anew right before drawing.
+        self.position = self.place(&State::window_matrix(canvas));
+        if let Some(position) = self.position {
+            canvas.save();
+            canvas.concat(&Matrix::translate(position));
+            self.inner.draw(canvas);
+            canvas.restore();
+        }
+    }
+
+    forward_widget!(inner; get);
+}
@@ -0,0 +1,162 @@
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+
+use crate::framework::assets::{AssetCache, AssetError};
+use crate::prelude::*;
+use game::State;
+use skia::{scalar, Canvas, Font as SkFont, Paint, Size, Typeface};
+
+/// An icon font, like Material Icons, with names for the icons it has.
+///
+/// Icon fonts don't agree on how their icons are named, so the names are
+/// whatever is given to [`with_name`](Self::with_name) or
+/// [`with_names`](Self::with_names), usually from the codepoint list that
+/// comes with the font. It is shared between [`Icon`]s through an [`Arc`].
+pub struct IconFont {
+    typeface: Typeface,
+    names: HashMap<String, char>,
+}
+
+impl IconFont {
+    pub fn new(typeface: Typeface) -> Self {
+        Self {
+            typeface,
+            names: HashMap::new(),
+        }
+    }
+
+    /// Load the font from a file through the [`AssetCache`]
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, AssetError> {
+        Ok(Self::new(AssetCache::typeface(path)?))
+    }
+
+    pub fn with_name(mut self, name: impl Into<String>, codepoint: char) -> Self {
+        self.names.insert(name.into(), codepoint);
+        self
+    }
+
+    pub fn with_names<S: Into<String>>(
+        mut self,
+        names: impl IntoIterator<Item = (S, char)>,
+    ) -> Self {
+        self.names
+            .extend(names.into_iter().map(|(name, c)| (name.into(), c)));
+        self
+    }
+
+    pub fn typeface(&self) -> &Typeface {
+        &self.typeface
+    }
+
+    /// The codepoint of a named icon
+    pub fn codepoint(&self, name: &str) -> Option<char> {
+        self.names.get(name).copied()
+    }
+}
+
+/// A single glyph of an [`IconFont`], centered in a square, for toolbars and
+/// buttons.
+///
+/// The square is `size` on each side and the glyph is drawn at that font
+/// size, which is what icon fonts are designed for, so a 24 point icon fills
+/// a 24 point square. The glyph is centered by its ink rather than its
+/// advance and placed on whole pixels to keep it crisp. The paint is the
+/// theme's text paint by default.
+pub struct Icon {
+    pub font: Arc<IconFont>,
+    pub paint: Paint,
+    codepoint: char,
+    size: scalar,
+    sk_font: SkFont,
+    bounds: Size,
+}
+
+impl Icon {
+    pub fn new(font: &Arc<IconFont>, codepoint: char, size: scalar) -> Self {
+        let paint = State::with_theme(|t| t.text());
+        Self {
+            sk_font: Self::resolve(font, size),
+            font: font.clone(),
+            paint,
+            codepoint,
+            size,
+            bounds: Size::new_empty(),
+        }
+    }
+
+    /// An icon by the name it was given in the font, or `None` if the font
+    /// has no icon by that name
+    pub fn named(font: &Arc<IconFont>, name: &str, size: scalar) -> Option<Self> {
+        font.codepoint(name).map(|c| Self::new(font, c, size))
+    }
+
+    pub fn with_paint(mut self, paint: Paint) -> Self {
+        self.paint = paint;
+        self
+    }
+
+    pub fn codepoint(&self) -> char {
+        self.codepoint
+    }
+
+    pub fn set_codepoint(&mut self, codepoint: char) {
+        if self.codepoint != codepoint {
+            self.codepoint = codepoint;
+            State::request_redraw();
+        }
+    }
+
+    /// Switch to another named icon of the same font. Returns whether the
+    /// font has an icon by that name, the icon is left as it was if not.
+    pub fn set_name(&mut self, name: &str) -> bool {
+        match self.font.codepoint(name) {
+            Some(c) => {
+                self.set_codepoint(c);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn glyph_size(&self) -> scalar {
+        self.size
+    }
+
+    /// Resize the icon, which changes its layout size as well
+    pub fn set_glyph_size(&mut self, size: scalar) {
+        if self.size != size {
+            self.size = size;
+            self.sk_font = Self::resolve(&self.font, size);
+            State::request_redraw();
+        }
+    }
+
+    fn resolve(font: &IconFont, size: scalar) -> SkFont {
+        let mut sk_font = SkFont::new(&font.typeface, size);
+        State::with(|x| x.font_rendering.apply(&mut sk_font));
+        sk_font
+    }
+}
+
+impl Widget for Icon {
+    fn size(&mut self, wrap: &mut WrapState) -> (LayoutSize, bool) {
+        let size = LayoutSize::min(self.size, self.size);
+        (size, size != wrap.layout_size())
+    }
+
+    fn set_size(&mut self, _wrap: &mut WrapState, size: Size) {
+        self.bounds = size;
+    }
+
+    fn draw(&mut self, _wrap: &mut WrapState, canvas: &mut Canvas) {
+        let glyph = self.codepoint.to_string();
+        let (_, ink) = self.sk_font.measure_str(&glyph, Some(&self.paint));
+        if ink.is_empty() {
+            return;
+        }
+        let x = self.bounds.width * 0.5 - ink.center_x();
+        let y = self.bounds.height * 0.5 - ink.center_y();
+        canvas.draw_str(&glyph, (x.round(), y.round()), &self.sk_font, &self.paint);
+    }
+}
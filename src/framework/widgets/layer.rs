@@ -0,0 +1,93 @@
+use crate::prelude::*;
+use game::State;
+use skia::{scalar, Canvas, Color, Image, ImageFilter, Matrix, Paint, Rect, Size, Surface};
+
+/// Rasterizes its child into an offscreen image each frame, then draws that
+/// image through a paint.
+///
+/// Unlike drawing the child directly, any image filter or shader on the
+/// paint sees the child as a single flattened image. The last rendered image
+/// is also available through [`image`](Self::image), for use as an input to
+/// other effects like reflections.
+pub struct Layer<T: Widget> {
+    pub inner: Wrap<T>,
+    pub paint: Paint,
+    /// Pixels per logical pixel in the offscreen image, on top of the
+    /// display's [`scale_factor`](State::scale_factor). Raise this when the
+    /// canvas is scaled up to keep the layer sharp.
+    pub resolution: scalar,
+    image: Option<Image>,
+    size: Size,
+}
+
+impl<T: Widget> Layer<T> {
+    pub fn new(inner: impl Into<Wrap<T>>) -> Self {
+        Self {
+            inner: inner.into(),
+            paint: Paint::default(),
+            resolution: 1.0,
+            image: None,
+            size: Size::new_empty(),
+        }
+    }
+
+    pub fn with_paint(mut self, paint: Paint) -> Self {
+        self.paint = paint;
+        self
+    }
+
+    pub fn with_filter(mut self, filter: ImageFilter) -> Self {
+        self.paint.set_image_filter(filter);
+        self
+    }
+
+    pub fn with_resolution(mut self, resolution: scalar) -> Self {
+        self.resolution = resolution;
+        self
+    }
+
+    /// The child as rendered in the last frame
+    pub fn image(&self) -> Option<&Image> {
+        self.image.as_ref()
+    }
+
+    /// Draw the child offscreen, with `matrix` mapping the layer to the
+    /// frame, so that the child's bounds are still registered where it is
+    /// shown
+    fn render(&mut self, matrix: Matrix) -> Option<Image> {
+        let scale = self.resolution * State::scale_factor();
+        let width = (self.size.width * scale).ceil() as i32;
+        let height = (self.size.height * scale).ceil() as i32;
+        if width <= 0 || height <= 0 {
+            return None;
+        }
+        // Pictures are recorded without a GPU context, so the offscreen
+        // surface has to be a raster one.
+        let mut surface = Surface::new_raster_n32_premul((width, height))?;
+        let canvas = surface.canvas();
+        canvas.clear(Color::TRANSPARENT);
+        let offscreen = Matrix::scale((scale, scale));
+        canvas.concat(&offscreen);
+        let to_frame = Matrix::concat(&matrix, &offscreen.invert()?);
+        let outer = State::with_mut(|x| std::mem::replace(&mut x.offscreen_matrix, to_frame));
+        self.inner.draw(canvas);
+        State::with_mut(|x| x.offscreen_matrix = outer);
+        Some(surface.image_snapshot())
+    }
+}
+
+impl<T: Widget> Widget for Layer<T> {
+    forward_widget!(inner; update, input, size, height_for_width, get);
+
+    fn set_size(&mut self, _wrap: &mut WrapState, size: Size) {
+        self.size = size;
+        self.inner.set_size(size);
+    }
+
+    fn draw(&mut self, _wrap: &mut WrapState, canvas: &mut Canvas) {
+        self.image = self.render(State::window_matrix(canvas));
+        if let Some(image) = &self.image {
+            canvas.draw_image_rect(image, None, Rect::from_size(self.size), &self.paint);
+        }
+    }
+}
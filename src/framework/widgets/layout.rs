@@ -1,7 +1,11 @@
 mod container;
 mod h_container;
+mod keyed;
+mod uniform_row;
 mod v_container;
 
 pub use container::{ContainerDimension, ContainerSize};
 pub use h_container::HContainer;
+pub use keyed::Keyed;
+pub use uniform_row::{UniformRow, UniformSizing};
 pub use v_container::VContainer;
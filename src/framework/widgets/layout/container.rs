@@ -134,6 +134,15 @@ impl<T: Widget> ContainerWidget<T> {
         (s, self.changed, c)
     }
 
+    /// The child's minimum height once it is given `width`, see
+    /// [`Widget::height_for_width`]
+    pub fn min_height_for(&mut self, width: scalar) -> scalar {
+        let min = self.layout_size.height.min;
+        self.inner
+            .height_for_width(width)
+            .map_or(min, |h| h.max(min))
+    }
+
     pub fn maybe_set_size(&mut self, size: Size) {
         if self.changed || self.children_changed || size != self.size {
             self.size = size;
@@ -1,6 +1,6 @@
 use crate::prelude::*;
 use game::{InputEvent, ID};
-use skia::{Canvas, Matrix, Size};
+use skia::{scalar, Canvas, Matrix, Size};
 
 use super::container::{ContainerSize, ContainerWidget};
 
@@ -27,13 +27,20 @@ impl<T: Widget> HContainer<T> {
         }
     }
 
-    fn layout(&mut self, size: Size) {
-        let total_space = size.width;
+    /// Clip each child to the space it was given, see
+    /// [`WrapState::set_clip_to_bounds`]
+    pub fn with_clip_children(mut self, clip: bool) -> Self {
+        for i in &mut self.inner {
+            i.inner.state.set_clip_to_bounds(clip);
+        }
+        self
+    }
 
+    fn widths(&self, total_space: scalar) -> Vec<scalar> {
         let mut min = 0.0f32;
         let mut expand = 0.0f32;
 
-        for i in &mut self.inner {
+        for i in &self.inner {
             min += i.layout_size.width.min;
             if let Some(e) = i.layout_size.width.expand {
                 expand += e;
@@ -41,18 +48,28 @@ impl<T: Widget> HContainer<T> {
         }
 
         let space_left = (total_space - min).max(0.0);
+        self.inner
+            .iter()
+            .map(|i| {
+                let mut width = i.layout_size.width.min;
+                if let Some(e) = i.layout_size.width.expand {
+                    width += space_left * e / expand;
+                }
+                width
+            })
+            .collect()
+    }
+
+    fn layout(&mut self, size: Size) {
+        let widths = self.widths(size.width);
         let mut offset = 0.0;
-        for i in &mut self.inner {
-            let mut width = i.layout_size.width.min;
-            if let Some(e) = i.layout_size.width.expand {
-                width += space_left * e / expand;
-            }
+        for (i, width) in self.inner.iter_mut().zip(widths) {
             i.position.set(offset, 0.0);
             offset += width;
             let height = if i.layout_size.height.expand.is_some() {
                 size.height
             } else {
-                i.layout_size.height.min.min(size.height)
+                i.min_height_for(width).min(size.height)
             };
             i.maybe_set_size(Size::new(width, height));
         }
@@ -124,6 +141,49 @@ impl<T: Widget> Widget for HContainer<T> {
         self.layout(size);
     }
 
+    fn height_for_width(&mut self, _wrap: &mut WrapState, width: scalar) -> Option<scalar> {
+        let widths = self.widths(width);
+        let mut depends = false;
+        let mut height = 0.0f32;
+        for (i, width) in self.inner.iter_mut().zip(widths) {
+            depends |= i.inner.height_for_width(width).is_some();
+            height = height.max(i.min_height_for(width));
+        }
+        if depends {
+            Some(height)
+        } else {
+            None
+        }
+    }
+
+    fn min_intrinsic_width(&mut self, _wrap: &mut WrapState) -> scalar {
+        self.inner
+            .iter_mut()
+            .map(|i| i.inner.min_intrinsic_width())
+            .sum()
+    }
+
+    fn max_intrinsic_width(&mut self, _wrap: &mut WrapState) -> scalar {
+        self.inner
+            .iter_mut()
+            .map(|i| i.inner.max_intrinsic_width())
+            .sum()
+    }
+
+    fn min_intrinsic_height(&mut self, _wrap: &mut WrapState) -> scalar {
+        self.inner
+            .iter_mut()
+            .map(|i| i.inner.min_intrinsic_height())
+            .fold(0.0, scalar::max)
+    }
+
+    fn max_intrinsic_height(&mut self, _wrap: &mut WrapState) -> scalar {
+        self.inner
+            .iter_mut()
+            .map(|i| i.inner.max_intrinsic_height())
+            .fold(0.0, scalar::max)
+    }
+
     fn draw(&mut self, _wrap: &mut WrapState, canvas: &mut Canvas) {
         for i in &mut self.inner {
             let m = Matrix::translate(i.position);
@@ -0,0 +1,205 @@
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+
+use crate::prelude::*;
+use game::{InputEvent, ID};
+use skia::{scalar, Canvas, Matrix, Size};
+
+use super::container::{ContainerSize, ContainerWidget};
+use super::VContainer;
+
+/// Stacks children vertically like a [`VContainer`], with each child
+/// identified by a key.
+///
+/// Changing the list of children through [`sync`](Self::sync) keeps the
+/// widgets of keys that are still present, so their state like scroll
+/// position or a running animation survives. Only widgets for new keys are
+/// created, and widgets for keys that are gone are dropped.
+///
+/// The sync first unmounts and drops the widgets of keys that are gone, in
+/// the order they were in, then creates the widgets of new keys. While the
+/// `Keyed` is mounted, these are mounted during the sync, in the order of the
+/// items, rather than on their first update like children of other
+/// containers. Widgets of keys that stay aren't unmounted, even if they move.
+///
+/// Keys must be unique. If a sync is given the same key more than once, only
+/// the first item with it is used and the rest are skipped with a warning.
+pub struct Keyed<K: Eq + Hash + Clone + Send + 'static, T: Widget> {
+    keys: Vec<K>,
+    inner: Vec<ContainerWidget<T>>,
+    synced: bool,
+    mounted: bool,
+    pub size: ContainerSize,
+}
+
+impl<K: Eq + Hash + Clone + Send + 'static, T: Widget> Keyed<K, T> {
+    pub fn new(size: ContainerSize) -> Self {
+        Self {
+            keys: Vec::new(),
+            inner: Vec::new(),
+            synced: false,
+            mounted: false,
+            size,
+        }
+    }
+
+    /// Update the children to match the given items, in their order.
+    ///
+    /// `create` makes the widget for a key that wasn't there before, and
+    /// `update` passes the new data on to the widget of a key that was.
+    pub fn sync<D, I>(
+        &mut self,
+        items: I,
+        mut create: impl FnMut(&K, D) -> T,
+        mut update: impl FnMut(&mut T, D),
+    ) where
+        I: IntoIterator<Item = (K, D)>,
+    {
+        let items: Vec<(K, D)> = items.into_iter().collect();
+        let keys: HashSet<&K> = items.iter().map(|(k, _)| k).collect();
+        let mut old = HashMap::new();
+        for (key, widget) in self.keys.drain(..).zip(self.inner.drain(..)) {
+            if keys.contains(&key) {
+                old.insert(key, widget);
+            } else {
+                // Dropping the wrap unmounts the widget
+                drop(widget);
+            }
+        }
+
+        let mut seen = HashSet::new();
+        for (key, data) in items {
+            if !seen.insert(key.clone()) {
+                eprintln!("Keyed::sync was given a key more than once, skipping the repeats");
+                continue;
+            }
+            let widget = match old.remove(&key) {
+                Some(mut widget) => {
+                    update(&mut widget.inner.inner, data);
+                    widget
+                }
+                None => {
+                    let mut widget = ContainerWidget::new(Wrap::new(create(&key, data)));
+                    if self.mounted {
+                        widget.inner.mount();
+                    }
+                    widget
+                }
+            };
+            self.keys.push(key);
+            self.inner.push(widget);
+        }
+        self.synced = true;
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &K> {
+        self.keys.iter()
+    }
+
+    pub fn get_child(&mut self, key: &K) -> Option<&mut Wrap<T>> {
+        let index = self.keys.iter().position(|k| k == key)?;
+        Some(&mut self.inner[index].inner)
+    }
+
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+}
+
+impl<K: Eq + Hash + Clone + Send + 'static, T: Widget> Widget for Keyed<K, T> {
+    fn on_mount(&mut self, _wrap: &mut WrapState) {
+        self.mounted = true;
+    }
+
+    fn on_unmount(&mut self, _wrap: &mut WrapState) {
+        self.mounted = false;
+    }
+
+    fn update(&mut self, _wrap: &mut WrapState) {
+        for i in &mut self.inner {
+            i.inner.update();
+        }
+    }
+
+    fn input(&mut self, _wrap: &mut WrapState, event: &InputEvent) -> bool {
+        self.inner.iter_mut().rev().any(|i| {
+            event
+                .reverse_map_position(Matrix::translate(i.position))
+                .map_or(false, |event| i.inner.input(&event))
+        })
+    }
+
+    fn size(&mut self, _wrap: &mut WrapState) -> (LayoutSize, bool) {
+        let mut height = 0.0f32;
+        let mut height_min = 0.0f32;
+        let mut width = 0.0f32;
+        let mut width_min = 0.0f32;
+        // Adding, removing or reordering children needs a new layout even if
+        // no child's size changed.
+        let mut children_changed = std::mem::take(&mut self.synced);
+
+        for i in &mut self.inner {
+            let (size, s, c) = i.size();
+            children_changed |= s || c;
+            height += size.height.size;
+            height_min += size.height.min;
+            width = width.max(size.width.size);
+            width_min = width_min.max(size.width.min);
+        }
+
+        (
+            LayoutSize {
+                width: LayoutDimension {
+                    size: width,
+                    min: self
+                        .size
+                        .width
+                        .min
+                        .map_or(width_min, |min| min.max(width_min)),
+                    expand: self.size.width.expand,
+                },
+                height: LayoutDimension {
+                    size: height,
+                    min: self
+                        .size
+                        .height
+                        .min
+                        .map_or(height_min, |min| min.max(height_min)),
+                    expand: self.size.height.expand,
+                },
+            },
+            children_changed,
+        )
+    }
+
+    fn set_size(&mut self, _wrap: &mut WrapState, size: Size) {
+        VContainer::layout_children(&mut self.inner, size);
+    }
+
+    fn height_for_width(&mut self, _wrap: &mut WrapState, width: scalar) -> Option<scalar> {
+        VContainer::children_height_for_width(&mut self.inner, width)
+    }
+
+    fn draw(&mut self, _wrap: &mut WrapState, canvas: &mut Canvas) {
+        for i in &mut self.inner {
+            canvas.save();
+            canvas.concat(&Matrix::translate(i.position));
+            i.inner.draw(canvas);
+            canvas.restore();
+        }
+    }
+
+    fn get(&mut self, _wrap: &mut WrapState, id: ID) -> Option<(&mut dyn Widget, &mut WrapState)> {
+        for i in &mut self.inner {
+            let x = i.inner.get(id);
+            if x.is_some() {
+                return x;
+            }
+        }
+        None
+    }
+}
@@ -0,0 +1,165 @@
+use crate::prelude::*;
+use game::{InputEvent, ID};
+use skia::{scalar, Canvas, Matrix, Size};
+
+use super::container::{ContainerSize, ContainerWidget};
+
+/// How a [`UniformRow`] decides the width shared by its cells
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum UniformSizing {
+    /// Every cell is as wide as the widest child needs, measured with
+    /// [`Widget::min_intrinsic_width`]
+    Widest,
+    /// The available width is divided evenly between the cells
+    Fill,
+}
+
+/// Lays children out horizontally like an [`HContainer`](super::HContainer),
+/// but gives all of them the same width regardless of their content, for
+/// button groups and toolbars.
+///
+/// With [`UniformSizing::Fill`], cells never get narrower than the widest
+/// child needs, so the row as a whole reports that as its minimum width.
+pub struct UniformRow<T: Widget> {
+    inner: Vec<ContainerWidget<T>>,
+    pub size: ContainerSize,
+    pub sizing: UniformSizing,
+    /// The gap between two cells
+    pub spacing: scalar,
+    widest: scalar,
+}
+
+impl<T: Widget> UniformRow<T> {
+    pub fn new<I, W>(inner: I, size: ContainerSize, sizing: UniformSizing) -> Self
+    where
+        W: Into<Wrap<T>>,
+        I: IntoIterator<Item = W>,
+    {
+        Self {
+            inner: inner
+                .into_iter()
+                .map(Into::into)
+                .map(ContainerWidget::new)
+                .collect(),
+            size,
+            sizing,
+            spacing: 0.0,
+            widest: 0.0,
+        }
+    }
+
+    pub fn with_spacing(mut self, spacing: scalar) -> Self {
+        self.spacing = spacing;
+        self
+    }
+
+    fn gaps(&self) -> scalar {
+        self.inner.len().saturating_sub(1) as scalar * self.spacing
+    }
+
+    fn cell_width(&self, total_space: scalar) -> scalar {
+        match self.sizing {
+            UniformSizing::Widest => self.widest,
+            UniformSizing::Fill if self.inner.is_empty() => 0.0,
+            UniformSizing::Fill => {
+                let even = (total_space - self.gaps()) / self.inner.len() as scalar;
+                even.max(self.widest)
+            }
+        }
+    }
+}
+
+impl<T: Widget> Widget for UniformRow<T> {
+    fn update(&mut self, _wrap: &mut WrapState) {
+        for i in &mut self.inner {
+            i.inner.update();
+        }
+    }
+
+    fn input(&mut self, _wrap: &mut WrapState, event: &InputEvent) -> bool {
+        self.inner.iter_mut().rev().any(|i| {
+            if let Some(event) = event.reverse_map_position(Matrix::translate(i.position)) {
+                i.inner.input(&event)
+            } else {
+                false
+            }
+        })
+    }
+
+    fn size(&mut self, _wrap: &mut WrapState) -> (LayoutSize, bool) {
+        let mut widest = 0.0f32;
+        let mut height = 0.0f32;
+        let mut height_min = 0.0f32;
+        let mut children_changed = false;
+
+        for i in &mut self.inner {
+            let (size, s, c) = i.size();
+            children_changed |= s || c;
+            widest = widest.max(i.inner.min_intrinsic_width());
+            height = height.max(size.height.size);
+            height_min = height_min.max(size.height.min);
+        }
+        // A change in the widest child resizes every cell
+        children_changed |= widest != self.widest;
+        self.widest = widest;
+
+        let width = widest * self.inner.len() as scalar + self.gaps();
+        (
+            LayoutSize {
+                width: LayoutDimension {
+                    size: width,
+                    min: self.size.width.min.map_or(width, |min| min.max(width)),
+                    expand: self.size.width.expand,
+                },
+                height: LayoutDimension {
+                    size: height,
+                    min: self
+                        .size
+                        .height
+                        .min
+                        .map_or(height_min, |min| min.max(height_min)),
+                    expand: self.size.height.expand,
+                },
+            },
+            children_changed,
+        )
+    }
+
+    fn set_size(&mut self, _wrap: &mut WrapState, size: Size) {
+        let width = self.cell_width(size.width);
+        let spacing = self.spacing;
+        for (n, i) in self.inner.iter_mut().enumerate() {
+            i.position.set(n as scalar * (width + spacing), 0.0);
+            let height = if i.layout_size.height.expand.is_some() {
+                size.height
+            } else {
+                i.min_height_for(width).min(size.height)
+            };
+            i.maybe_set_size(Size::new(width, height));
+        }
+    }
+
+    fn min_intrinsic_width(&mut self, _wrap: &mut WrapState) -> scalar {
+        self.widest * self.inner.len() as scalar + self.gaps()
+    }
+
+    fn draw(&mut self, _wrap: &mut WrapState, canvas: &mut Canvas) {
+        for i in &mut self.inner {
+            let m = Matrix::translate(i.position);
+            canvas.save();
+            canvas.concat(&m);
+            i.inner.draw(canvas);
+            canvas.restore();
+        }
+    }
+
+    fn get(&mut self, _wrap: &mut WrapState, id: ID) -> Option<(&mut dyn Widget, &mut WrapState)> {
+        for i in &mut self.inner {
+            let x = i.inner.get(id);
+            if x.is_some() {
+                return x;
+            }
+        }
+        None
+    }
+}
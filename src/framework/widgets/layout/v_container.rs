@@ -1,6 +1,6 @@
 use crate::prelude::*;
 use game::{InputEvent, ID};
-use skia::{Canvas, Matrix, Size};
+use skia::{scalar, Canvas, Matrix, Size};
 
 use super::container::{ContainerSize, ContainerWidget};
 
@@ -27,14 +27,43 @@ impl<T: Widget> VContainer<T> {
         }
     }
 
+    /// Clip each child to the space it was given, see
+    /// [`WrapState::set_clip_to_bounds`]
+    pub fn with_clip_children(mut self, clip: bool) -> Self {
+        for i in &mut self.inner {
+            i.inner.state.set_clip_to_bounds(clip);
+        }
+        self
+    }
+
     fn layout(&mut self, size: Size) {
+        Self::layout_children(&mut self.inner, size);
+    }
+
+    fn child_width(i: &ContainerWidget<T>, width: scalar) -> scalar {
+        if i.layout_size.width.expand.is_some() {
+            width
+        } else {
+            i.layout_size.width.min.min(width)
+        }
+    }
+
+    /// Stack `inner` from the top down in `size`, shared with
+    /// [`Keyed`](super::Keyed)
+    pub(super) fn layout_children(inner: &mut [ContainerWidget<T>], size: Size) {
         let total_space = size.height;
 
         let mut min = 0.0f32;
         let mut expand = 0.0f32;
-
-        for i in &mut self.inner {
-            min += i.layout_size.height.min;
+        let mut heights = Vec::with_capacity(inner.len());
+
+        // The widths are known up front, so children whose height depends
+        // on their width are measured before the space is shared out.
+        for i in inner.iter_mut() {
+            let width = Self::child_width(i, size.width);
+            let height = i.min_height_for(width);
+            min += height;
+            heights.push(height);
             if let Some(e) = i.layout_size.height.expand {
                 expand += e;
             }
@@ -42,21 +71,36 @@ impl<T: Widget> VContainer<T> {
 
         let space_left = (total_space - min).max(0.0);
         let mut offset = 0.0;
-        for i in &mut self.inner {
-            let mut height = i.layout_size.height.min;
+        for (i, mut height) in inner.iter_mut().zip(heights) {
             if let Some(e) = i.layout_size.height.expand {
                 height += space_left * e / expand;
             }
             i.position.set(0.0, offset);
             offset += height;
-            let width = if i.layout_size.width.expand.is_some() {
-                size.width
-            } else {
-                i.layout_size.width.min.min(size.width)
-            };
+            let width = Self::child_width(i, size.width);
             i.maybe_set_size(Size::new(width, height));
         }
     }
+
+    /// The height of `inner` stacked at `width`, if any of them depends on
+    /// it, see [`Widget::height_for_width`]
+    pub(super) fn children_height_for_width(
+        inner: &mut [ContainerWidget<T>],
+        width: scalar,
+    ) -> Option<scalar> {
+        let mut depends = false;
+        let mut height = 0.0;
+        for i in inner.iter_mut() {
+            let child_width = Self::child_width(i, width);
+            depends |= i.inner.height_for_width(child_width).is_some();
+            height += i.min_height_for(child_width);
+        }
+        if depends {
+            Some(height)
+        } else {
+            None
+        }
+    }
 }
 
 impl<T: Widget> Widget for VContainer<T> {
@@ -124,6 +168,38 @@ impl<T: Widget> Widget for VContainer<T> {
         self.layout(size);
     }
 
+    fn height_for_width(&mut self, _wrap: &mut WrapState, width: scalar) -> Option<scalar> {
+        Self::children_height_for_width(&mut self.inner, width)
+    }
+
+    fn min_intrinsic_width(&mut self, _wrap: &mut WrapState) -> scalar {
+        self.inner
+            .iter_mut()
+            .map(|i| i.inner.min_intrinsic_width())
+            .fold(0.0, scalar::max)
+    }
+
+    fn max_intrinsic_width(&mut self, _wrap: &mut WrapState) -> scalar {
+        self.inner
+            .iter_mut()
+            .map(|i| i.inner.max_intrinsic_width())
+            .fold(0.0, scalar::max)
+    }
+
+    fn min_intrinsic_height(&mut self, _wrap: &mut WrapState) -> scalar {
+        self.inner
+            .iter_mut()
+            .map(|i| i.inner.min_intrinsic_height())
+            .sum()
+    }
+
+    fn max_intrinsic_height(&mut self, _wrap: &mut WrapState) -> scalar {
+        self.inner
+            .iter_mut()
+            .map(|i| i.inner.max_intrinsic_height())
+            .sum()
+    }
+
     fn draw(&mut self, _wrap: &mut WrapState, canvas: &mut Canvas) {
         for i in &mut self.inner {
             let m = Matrix::translate(i.position);
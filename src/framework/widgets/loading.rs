@@ -0,0 +1,109 @@
+use crate::framework::task::DoneFlag;
+use crate::prelude::*;
+use game::{InputEvent, ID};
+use skia::{Canvas, Contains, Matrix, Paint, Rect, Size, Vector};
+
+use super::shapes::Throbber;
+
+/// Covers its child with a spinning [`Throbber`] until a [`DoneFlag`] is
+/// set, for example by [`run_in_background`](crate::framework::task::run_in_background).
+///
+/// While loading the child is still drawn under a dimming scrim, but receives
+/// no input.
+pub struct Loading<T: Widget> {
+    pub inner: Wrap<T>,
+    pub throbber: Wrap<Throbber>,
+    pub done: DoneFlag,
+    pub scrim: Paint,
+    size: Size,
+    throbber_size: Size,
+}
+
+impl<T: Widget> Loading<T> {
+    pub fn new(
+        inner: impl Into<Wrap<T>>,
+        throbber: impl Into<Wrap<Throbber>>,
+        done: DoneFlag,
+    ) -> Self {
+        Self {
+            inner: inner.into(),
+            throbber: throbber.into(),
+            done,
+            scrim: Paint::new_color4f(0.0, 0.0, 0.0, 0.5),
+            size: Size::new_empty(),
+            throbber_size: Size::new_empty(),
+        }
+    }
+
+    pub fn with_scrim(mut self, scrim: Paint) -> Self {
+        self.scrim = scrim;
+        self
+    }
+
+    pub fn is_loading(&self) -> bool {
+        !self.done.is_done()
+    }
+
+    fn throbber_offset(&self) -> Vector {
+        Vector::new(
+            (self.size.width - self.throbber_size.width) * 0.5,
+            (self.size.height - self.throbber_size.height) * 0.5,
+        )
+    }
+}
+
+impl<T: Widget> Widget for Loading<T> {
+    fn update(&mut self, _wrap: &mut WrapState) {
+        self.inner.update();
+        if self.is_loading() {
+            self.throbber.update();
+        }
+    }
+
+    fn input(&mut self, _wrap: &mut WrapState, event: &InputEvent) -> bool {
+        if self.is_loading() {
+            // Swallow everything aimed at the child, but let releases through
+            // so drags started before the load don't get stuck.
+            if let InputEvent::MouseUp(..) = event {
+                self.inner.input(event);
+            }
+            return event
+                .position()
+                .map_or(true, |p| Rect::from_size(self.size).contains(p));
+        }
+        self.inner.input(event)
+    }
+
+    fn size(&mut self, _wrap: &mut WrapState) -> (LayoutSize, bool) {
+        let (t, _) = self.throbber.size();
+        let size = Size::new(t.width.min, t.height.min);
+        if size != self.throbber_size {
+            self.throbber_size = size;
+            self.throbber.set_size(size);
+        }
+        self.inner.size()
+    }
+
+    fn set_size(&mut self, _wrap: &mut WrapState, size: Size) {
+        self.size = size;
+        self.inner.set_size(size);
+    }
+
+    fn draw(&mut self, _wrap: &mut WrapState, canvas: &mut Canvas) {
+        self.inner.draw(canvas);
+        if self.is_loading() {
+            canvas.draw_rect(Rect::from_size(self.size), &self.scrim);
+            canvas.save();
+            canvas.concat(&Matrix::translate(self.throbber_offset()));
+            self.throbber.draw(canvas);
+            canvas.restore();
+        }
+    }
+
+    fn get(&mut self, _wrap: &mut WrapState, id: ID) -> Option<(&mut dyn Widget, &mut WrapState)> {
+        if let Some(x) = self.inner.get(id) {
+            return Some(x);
+        }
+        self.throbber.get(id)
+    }
+}
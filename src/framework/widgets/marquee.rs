@@ -0,0 +1,115 @@
+use std::time::Duration;
+
+use crate::prelude::*;
+use game::{InputEvent, State};
+use skia::{scalar, Canvas, Matrix, Rect, Size};
+
+/// Clips its child to its own width and scrolls it back and forth
+/// horizontally when it does not fit, pausing at both ends.
+pub struct Marquee<T: Widget> {
+    pub inner: Wrap<T>,
+    pub layout_size: LayoutSize,
+    /// Scrolling speed in logical pixels per second.
+    pub speed: scalar,
+    /// How long to rest at either end before scrolling again.
+    pub pause: Duration,
+    size: Size,
+    /// The width the child would like, which is what can't fit
+    content_width: scalar,
+    offset: scalar,
+    start: Duration,
+}
+
+impl<T: Widget> Marquee<T> {
+    pub fn new(inner: impl Into<Wrap<T>>, size: LayoutSize) -> Self {
+        Self {
+            inner: inner.into(),
+            layout_size: size,
+            speed: 40.0,
+            pause: Duration::SECOND,
+            size: Size::new_empty(),
+            content_width: 0.0,
+            offset: 0.0,
+            start: Duration::ZERO,
+        }
+    }
+
+    pub fn with_speed(mut self, speed: scalar) -> Self {
+        self.speed = speed;
+        self
+    }
+
+    pub fn with_pause(mut self, pause: Duration) -> Self {
+        self.pause = pause;
+        self
+    }
+
+    fn overflow(&self) -> scalar {
+        (self.content_width - self.size.width).max(0.0)
+    }
+
+    fn calc_offset(&self) -> scalar {
+        let overflow = self.overflow();
+        if overflow <= 0.0 || self.speed <= 0.0 {
+            return 0.0;
+        }
+        let pause = self.pause.as_secs_f32();
+        let travel = overflow / self.speed;
+        let t = (State::elapsed() - self.start).as_secs_f32() % (2.0 * (pause + travel));
+        if t < pause {
+            0.0
+        } else if t < pause + travel {
+            (t - pause) * self.speed
+        } else if t < 2.0 * pause + travel {
+            overflow
+        } else {
+            overflow - (t - 2.0 * pause - travel) * self.speed
+        }
+    }
+}
+
+impl<T: Widget> Widget for Marquee<T> {
+    forward_widget!(inner; update, get);
+
+    fn input(&mut self, _wrap: &mut WrapState, event: &InputEvent) -> bool {
+        event
+            .reverse_map_position(Matrix::translate((-self.offset, 0.0)))
+            .map_or(false, |event| self.inner.input(&event))
+    }
+
+    fn size(&mut self, _wrap: &mut WrapState) -> (LayoutSize, bool) {
+        let (s, c) = self.inner.size();
+        // The minimum width is only what the child can't do without, text
+        // doesn't report its length there
+        let width = self.inner.max_intrinsic_width().max(s.width.min);
+        if width != self.content_width {
+            // Restart scrolling from the beginning whenever the content
+            // changes, so it doesn't jump around.
+            self.start = State::elapsed();
+        }
+        self.content_width = width;
+        let mut size = self.layout_size;
+        size.height.min = size.height.min.max(s.height.min);
+        (size, c)
+    }
+
+    fn set_size(&mut self, _wrap: &mut WrapState, size: Size) {
+        let overflow = self.overflow();
+        self.size = size;
+        // The same goes for the marquee itself being resized
+        if overflow != self.overflow() {
+            self.start = State::elapsed();
+        }
+        self.inner
+            .set_size(Size::new(self.content_width.max(size.width), size.height));
+    }
+
+    fn draw(&mut self, _wrap: &mut WrapState, canvas: &mut Canvas) {
+        self.offset = self.calc_offset();
+        canvas.save();
+        canvas.clip_rect(Rect::from_size(self.size), None, None);
+        canvas.concat(&Matrix::translate((-self.offset, 0.0)));
+        self.inner.draw(canvas);
+        canvas.restore();
+    }
+}
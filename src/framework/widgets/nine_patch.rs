@@ -0,0 +1,114 @@
+use crate::prelude::*;
+use skia::{canvas::SrcRectConstraint, scalar, Canvas, Image, Paint, Rect, Size};
+
+/// Distances from each edge, in pixels.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Insets {
+    pub left: scalar,
+    pub top: scalar,
+    pub right: scalar,
+    pub bottom: scalar,
+}
+
+impl Insets {
+    pub fn new(left: scalar, top: scalar, right: scalar, bottom: scalar) -> Self {
+        Self {
+            left,
+            top,
+            right,
+            bottom,
+        }
+    }
+
+    pub fn uniform(inset: scalar) -> Self {
+        Self::new(inset, inset, inset, inset)
+    }
+}
+
+/// Stretches an image to fill its bounds while keeping its corners intact.
+///
+/// The insets split the image into nine regions: the corners are drawn
+/// unscaled, the edges are stretched along their length, and the center is
+/// stretched both ways. When the bounds are too small for the corners, they
+/// are shrunk proportionally.
+pub struct NinePatch {
+    pub image: Image,
+    pub insets: Insets,
+    pub layout_size: LayoutSize,
+    /// Scale of the corners relative to the image, for using high resolution
+    /// images on a lower resolution canvas
+    pub scale: scalar,
+    pub paint: Paint,
+    size: Size,
+}
+
+impl NinePatch {
+    pub fn new(image: Image, insets: Insets, size: LayoutSize) -> Self {
+        Self {
+            image,
+            insets,
+            layout_size: size,
+            scale: 1.0,
+            paint: Paint::default(),
+            size: Size::new_empty(),
+        }
+    }
+
+    pub fn with_scale(mut self, scale: scalar) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    pub fn with_paint(mut self, paint: Paint) -> Self {
+        self.paint = paint;
+        self
+    }
+
+    /// Split a span into its three parts, shrinking both ends if they don't
+    /// fit into the span
+    fn split(start: scalar, end: scalar, length: scalar) -> [scalar; 4] {
+        let ends = start + end;
+        let fit = if ends > length && ends > 0.0 {
+            length / ends
+        } else {
+            1.0
+        };
+        [0.0, start * fit, length - end * fit, length]
+    }
+}
+
+impl Widget for NinePatch {
+    fn size(&mut self, _wrap: &mut WrapState) -> (LayoutSize, bool) {
+        (self.layout_size, false)
+    }
+
+    fn set_size(&mut self, _wrap: &mut WrapState, size: Size) {
+        self.size = size;
+    }
+
+    fn draw(&mut self, _wrap: &mut WrapState, canvas: &mut Canvas) {
+        let i = self.insets;
+        let (w, h) = (self.image.width() as scalar, self.image.height() as scalar);
+        let src_x = [0.0, i.left, w - i.right, w];
+        let src_y = [0.0, i.top, h - i.bottom, h];
+        let s = self.scale;
+        let dst_x = Self::split(i.left * s, i.right * s, self.size.width);
+        let dst_y = Self::split(i.top * s, i.bottom * s, self.size.height);
+
+        for (sy, dy) in src_y.windows(2).zip(dst_y.windows(2)) {
+            for (sx, dx) in src_x.windows(2).zip(dst_x.windows(2)) {
+                let src = Rect::new(sx[0], sy[0], sx[1], sy[1]);
+                let dst = Rect::new(dx[0], dy[0], dx[1], dy[1]);
+                if src.is_empty() || dst.is_empty() {
+                    continue;
+                }
+                canvas.draw_image_rect(
+                    &self.image,
+                    Some((&src, SrcRectConstraint::Strict)),
+                    dst,
+                    &self.paint,
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,211 @@
+use std::time::Duration;
+
+use crate::framework::tween::{Easing, Tween};
+use crate::prelude::*;
+use game::State;
+use skia::{scalar, Canvas, Size};
+
+/// How a [`PageTransition`] moves from one page to the next
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum TransitionStyle {
+    /// The old page fades out as the new one fades in
+    Fade,
+    /// The new page pushes the old one out towards the left
+    SlideLeft,
+    /// The new page pushes the old one out towards the right
+    SlideRight,
+    /// The new page pushes the old one out towards the top
+    SlideUp,
+    /// The new page pushes the old one out towards the bottom
+    SlideDown,
+    /// The old page grows and fades out while the new one grows into place
+    Scale,
+}
+
+/// Shows one page at a time and animates between pages when
+/// [`set_page`](Self::set_page) is called, drawing both the outgoing and the
+/// incoming page while the transition runs.
+///
+/// Only the current page receives input. Changing the page again before a
+/// transition is over doesn't make the interrupted page jump: it leaves
+/// from wherever it had come in to, and the next transition is shortened
+/// to match.
+pub struct PageTransition {
+    pub layout_size: LayoutSize,
+    /// The style [`set_page`](Self::set_page) uses
+    pub style: TransitionStyle,
+    pub duration: Duration,
+    /// The style of the transition running now
+    active_style: TransitionStyle,
+    current: Wrap<Box<dyn Widget>>,
+    outgoing: Option<Wrap<Box<dyn Widget>>>,
+    progress: Tween,
+    size: Size,
+}
+
+impl PageTransition {
+    pub fn new(page: impl Widget, size: LayoutSize) -> Self {
+        Self {
+            layout_size: size,
+            style: TransitionStyle::Fade,
+            active_style: TransitionStyle::Fade,
+            duration: Duration::from_millis(300),
+            current: Wrap::new(Box::new(page)),
+            outgoing: None,
+            progress: Tween::new(1.0).with_easing(Easing::EaseInOut),
+            size: Size::new_empty(),
+        }
+    }
+
+    pub fn with_style(mut self, style: TransitionStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    pub fn with_duration(mut self, duration: Duration) -> Self {
+        self.duration = duration;
+        self
+    }
+
+    pub fn with_easing(mut self, easing: Easing) -> Self {
+        self.progress.easing = easing;
+        self
+    }
+
+    /// Move to a new page with the default style
+    pub fn set_page(&mut self, page: impl Widget) {
+        self.set_page_with(page, self.style);
+    }
+
+    /// Move to a new page with a style for just this transition
+    pub fn set_page_with(&mut self, page: impl Widget, style: TransitionStyle) {
+        self.active_style = style;
+        let mut page = Wrap::new(Box::new(page) as Box<dyn Widget>);
+        page.size();
+        page.set_size(self.size);
+        let previous = std::mem::replace(&mut self.current, page);
+        self.outgoing = Some(previous);
+
+        // An interrupted page that had come in part of the way leaves from
+        // there, so the new transition starts that far along.
+        let start = 1.0 - self.progress.value();
+        let remaining = self.duration.mul_f32(1.0 - start);
+        self.progress.set(start);
+        self.progress.animate_to(1.0, remaining);
+        State::request_redraw();
+    }
+
+    pub fn page(&mut self) -> &mut dyn Widget {
+        self.current.inner.as_mut()
+    }
+
+    pub fn is_transitioning(&self) -> bool {
+        self.outgoing.is_some()
+    }
+
+    fn draw_page(
+        page: &mut Wrap<Box<dyn Widget>>,
+        canvas: &mut Canvas,
+        size: Size,
+        style: TransitionStyle,
+        t: scalar,
+        incoming: bool,
+    ) {
+        // How far the page is from resting in place, from 0 to 1, and to
+        // which side
+        let (away, sign) = if incoming { (1.0 - t, 1.0) } else { (t, -1.0) };
+        let count = canvas.save();
+        match style {
+            TransitionStyle::Fade => {
+                let alpha = if incoming { t } else { 1.0 - t };
+                canvas.save_layer_alpha(None, (alpha * 255.0) as _);
+            }
+            TransitionStyle::SlideLeft => {
+                canvas.translate((sign * away * size.width, 0.0));
+            }
+            TransitionStyle::SlideRight => {
+                canvas.translate((-sign * away * size.width, 0.0));
+            }
+            TransitionStyle::SlideUp => {
+                canvas.translate((0.0, sign * away * size.height));
+            }
+            TransitionStyle::SlideDown => {
+                canvas.translate((0.0, -sign * away * size.height));
+            }
+            TransitionStyle::Scale => {
+                let scale = if incoming {
+                    1.0 - 0.1 * away
+                } else {
+                    1.0 + 0.1 * away
+                };
+                let (cx, cy) = (size.width / 2.0, size.height / 2.0);
+                canvas.translate((cx, cy));
+                canvas.scale((scale, scale));
+                canvas.translate((-cx, -cy));
+                canvas.save_layer_alpha(None, ((1.0 - away) * 255.0) as _);
+            }
+        }
+        page.draw(canvas);
+        // Undoes the transform along with any layer
+        canvas.restore_to_count(count);
+    }
+}
+
+impl Widget for PageTransition {
+    // Pages slide in and out from past the edges
+    fn on_mount(&mut self, wrap: &mut WrapState) {
+        wrap.set_cull_margin(None);
+    }
+
+    fn update(&mut self, _wrap: &mut WrapState) {
+        self.current.update();
+        if let Some(outgoing) = &mut self.outgoing {
+            outgoing.update();
+        }
+        if self.progress.is_finished() {
+            self.outgoing = None;
+        }
+    }
+
+    forward_widget!(current; input, get);
+
+    fn size(&mut self, _wrap: &mut WrapState) -> (LayoutSize, bool) {
+        let (page, changed) = self.current.size();
+        if let Some(outgoing) = &mut self.outgoing {
+            outgoing.size();
+        }
+        let mut size = self.layout_size;
+        size.width.min = size.width.min.max(page.width.min);
+        size.height.min = size.height.min.max(page.height.min);
+        (size, changed)
+    }
+
+    fn set_size(&mut self, _wrap: &mut WrapState, size: Size) {
+        self.size = size;
+        self.current.set_size(size);
+        if let Some(outgoing) = &mut self.outgoing {
+            outgoing.set_size(size);
+        }
+    }
+
+    fn draw(&mut self, _wrap: &mut WrapState, canvas: &mut Canvas) {
+        let outgoing = match &mut self.outgoing {
+            Some(outgoing) if !self.progress.is_finished() => outgoing,
+            _ => {
+                self.current.draw(canvas);
+                return;
+            }
+        };
+        let t = self.progress.value();
+        Self::draw_page(outgoing, canvas, self.size, self.active_style, t, false);
+        Self::draw_page(
+            &mut self.current,
+            canvas,
+            self.size,
+            self.active_style,
+            t,
+            true,
+        );
+        State::request_redraw();
+    }
+}
@@ -1,5 +1,5 @@
 use crate::prelude::*;
-use game::{InputEvent, State, ID};
+use game::{InputEvent, State};
 use skia::{scalar, Canvas, Matrix, Point, Size};
 
 pub struct Parallax<T: Widget> {
@@ -34,12 +34,15 @@ impl<T: Widget> Parallax<T> {
 }
 
 impl<T: Widget> Widget for Parallax<T> {
-    fn update(&mut self, _wrap: &mut WrapState) {
-        self.inner.update();
+    // The child follows the mouse, which can be anywhere
+    fn on_mount(&mut self, wrap: &mut WrapState) {
+        wrap.set_cull_margin(None);
     }
 
+    forward_widget!(inner; update, size, get);
+
     fn input(&mut self, _wrap: &mut WrapState, event: &InputEvent) -> bool {
-        if let InputEvent::MouseMove(p) = event {
+        if let InputEvent::MouseMove(p, _) = event {
             self.last_mouse_position = *p;
         }
         let m = self.calc_parallax();
@@ -48,10 +51,6 @@ impl<T: Widget> Widget for Parallax<T> {
             .map_or(false, |event| self.inner.input(&event))
     }
 
-    fn size(&mut self, _wrap: &mut WrapState) -> (LayoutSize, bool) {
-        self.inner.size()
-    }
-
     fn set_size(&mut self, _wrap: &mut WrapState, size: Size) {
         self.size = size;
         self.inner.set_size(size);
@@ -64,8 +63,4 @@ impl<T: Widget> Widget for Parallax<T> {
         self.inner.draw(canvas);
         canvas.restore();
     }
-
-    fn get(&mut self, _wrap: &mut WrapState, id: ID) -> Option<(&mut dyn Widget, &mut WrapState)> {
-        self.inner.get(id)
-    }
 }
@@ -0,0 +1,220 @@
+use std::ops::Range;
+use std::time::Duration;
+
+use crate::framework::lerp::Lerp;
+use crate::prelude::*;
+use game::State;
+use skia::{scalar, Canvas, Color4f, Paint, Point, Rect, Size, Vector};
+
+/// How a [`Particles`] widget spawns new particles on its own.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Emission {
+    /// A steady stream of this many particles per second
+    Continuous(f32),
+    /// A number of particles at once, repeated at an interval
+    Burst { count: usize, interval: Duration },
+    /// Only spawn particles through [`Particles::burst`]
+    Manual,
+}
+
+/// What particles look like and how they move. Ranges are picked from
+/// uniformly for each new particle.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParticleConfig {
+    /// Seconds each particle lives
+    pub lifetime: Range<f32>,
+    /// Initial speed in logical pixels per second
+    pub speed: Range<f32>,
+    /// Initial direction in degrees, clockwise from the positive x axis
+    pub direction: Range<f32>,
+    /// Acceleration in logical pixels per second squared
+    pub gravity: Vector,
+    /// Radius at birth and at death
+    pub size: (scalar, scalar),
+    /// Color at birth and at death
+    pub color: (Color4f, Color4f),
+}
+
+impl Default for ParticleConfig {
+    fn default() -> Self {
+        Self {
+            lifetime: 1.0..2.0,
+            speed: 50.0..150.0,
+            direction: 0.0..360.0,
+            gravity: Vector::new(0.0, 200.0),
+            size: (3.0, 1.0),
+            color: (
+                Color4f::new(1.0, 1.0, 1.0, 1.0),
+                Color4f::new(1.0, 1.0, 1.0, 0.0),
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+struct Particle {
+    position: Point,
+    velocity: Vector,
+    age: f32,
+    lifetime: f32,
+}
+
+/// Spawns, moves and draws small circles for effects like sparks or snow.
+///
+/// Particles spawn at random within the emitter area, given relative to the
+/// widget's bounds from 0 to 1. At most `max_particles` are alive at once;
+/// the storage for them is allocated up front and reused.
+pub struct Particles {
+    pub config: ParticleConfig,
+    pub emission: Emission,
+    pub emitter: Rect,
+    pub layout_size: LayoutSize,
+    particles: Vec<Particle>,
+    max_particles: usize,
+    pending: f32,
+    since_burst: Duration,
+    rng: u64,
+    size: Size,
+}
+
+impl Particles {
+    pub fn new(config: ParticleConfig, emission: Emission, max_particles: usize) -> Self {
+        Self {
+            config,
+            emission,
+            emitter: Rect::from_wh(1.0, 1.0),
+            layout_size: LayoutSize::ZERO.expand_width().expand_height(),
+            particles: Vec::with_capacity(max_particles),
+            max_particles,
+            pending: 0.0,
+            since_burst: Duration::ZERO,
+            rng: 0x2545_f491_4f6c_dd1d ^ State::elapsed().as_nanos() as u64,
+            size: Size::new_empty(),
+        }
+    }
+
+    pub fn with_emitter(mut self, emitter: Rect) -> Self {
+        self.emitter = emitter;
+        self
+    }
+
+    pub fn with_layout_size(mut self, size: LayoutSize) -> Self {
+        self.layout_size = size;
+        self
+    }
+
+    /// Spawn a number of particles right away, as far as the cap allows
+    pub fn burst(&mut self, count: usize) {
+        for _ in 0..count {
+            if self.particles.len() >= self.max_particles {
+                break;
+            }
+            let particle = self.spawn();
+            self.particles.push(particle);
+        }
+    }
+
+    pub fn particle_count(&self) -> usize {
+        self.particles.len()
+    }
+
+    pub fn clear(&mut self) {
+        self.particles.clear();
+    }
+
+    /// A xorshift generator is plenty for scattering particles, and saves
+    /// pulling in a dependency.
+    fn random(&mut self) -> f32 {
+        self.rng ^= self.rng << 13;
+        self.rng ^= self.rng >> 7;
+        self.rng ^= self.rng << 17;
+        (self.rng >> 40) as f32 / (1u64 << 24) as f32
+    }
+
+    fn random_in(&mut self, range: Range<f32>) -> f32 {
+        range.start + (range.end - range.start) * self.random()
+    }
+
+    fn spawn(&mut self) -> Particle {
+        let e = self.emitter;
+        let position = Point::new(
+            (e.left + e.width() * self.random()) * self.size.width,
+            (e.top + e.height() * self.random()) * self.size.height,
+        );
+        let speed = self.random_in(self.config.speed.clone());
+        let direction = self.random_in(self.config.direction.clone()).to_radians();
+        Particle {
+            position,
+            velocity: Vector::new(direction.cos(), direction.sin()) * speed,
+            age: 0.0,
+            lifetime: self.random_in(self.config.lifetime.clone()),
+        }
+    }
+
+    fn emit(&mut self, delta: Duration) {
+        match self.emission {
+            Emission::Continuous(rate) => {
+                self.pending += rate * delta.as_secs_f32();
+                let count = self.pending as usize;
+                self.pending -= count as f32;
+                self.burst(count);
+            }
+            Emission::Burst { count, interval } => {
+                self.since_burst += delta;
+                if self.since_burst >= interval {
+                    self.since_burst = Duration::ZERO;
+                    self.burst(count);
+                }
+            }
+            Emission::Manual => {}
+        }
+    }
+}
+
+impl Widget for Particles {
+    // Particles fly off past the emitter
+    fn on_mount(&mut self, wrap: &mut WrapState) {
+        wrap.set_cull_margin(None);
+    }
+
+    fn update(&mut self, _wrap: &mut WrapState) {
+        let delta = State::delta();
+        let dt = delta.as_secs_f32();
+        let gravity = self.config.gravity * dt;
+        let mut i = 0;
+        while i < self.particles.len() {
+            let p = &mut self.particles[i];
+            p.age += dt;
+            if p.age >= p.lifetime {
+                // Order doesn't matter, so dead particles are swapped out
+                // without shifting the rest.
+                self.particles.swap_remove(i);
+                continue;
+            }
+            p.velocity += gravity;
+            p.position += p.velocity * dt;
+            i += 1;
+        }
+        self.emit(delta);
+    }
+
+    fn size(&mut self, _wrap: &mut WrapState) -> (LayoutSize, bool) {
+        (self.layout_size, false)
+    }
+
+    fn set_size(&mut self, _wrap: &mut WrapState, size: Size) {
+        self.size = size;
+    }
+
+    fn draw(&mut self, _wrap: &mut WrapState, canvas: &mut Canvas) {
+        let (from, to) = self.config.color;
+        let (size_from, size_to) = self.config.size;
+        let mut paint = Paint::default();
+        paint.set_anti_alias(true);
+        for p in &self.particles {
+            let t = p.age / p.lifetime;
+            paint.set_color(from.lerp(&to, t).to_color());
+            canvas.draw_circle(p.position, size_from.lerp(&size_to, t), &paint);
+        }
+    }
+}
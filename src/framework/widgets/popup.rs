@@ -0,0 +1,197 @@
+use std::sync::{Arc, Mutex};
+
+use crate::prelude::*;
+use game::{InputEvent, State, ID};
+use skia::{Canvas, Contains, Matrix, Point, Rect, Size};
+use skulpin_renderer_sdl2::sdl2::keyboard::Keycode;
+
+enum Request {
+    Open(Point, Box<dyn Widget>),
+    Close,
+}
+
+#[derive(Default)]
+struct Shared {
+    request: Option<Request>,
+    open: bool,
+}
+
+/// Opens and closes popups on a [`PopupLayer`]. Cheap to clone and hand out
+/// to any widget, including the popup's own contents so it can close itself.
+#[derive(Clone, Default)]
+pub struct PopupHandle(Arc<Mutex<Shared>>);
+
+impl PopupHandle {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Show a popup with its top left corner at the given position in the
+    /// layer's coordinates, replacing any popup already open.
+    pub fn open(&self, at: Point, popup: impl Widget) {
+        self.0.lock().unwrap().request = Some(Request::Open(at, Box::new(popup)));
+    }
+
+    /// Show a popup at the mouse cursor. This assumes the layer is the root
+    /// widget, or at least placed at the origin of the canvas.
+    pub fn open_at_cursor(&self, popup: impl Widget) {
+        self.open(State::mouse_position(), popup);
+    }
+
+    pub fn close(&self) {
+        self.0.lock().unwrap().request = Some(Request::Close);
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.0.lock().unwrap().open
+    }
+}
+
+struct OpenPopup {
+    widget: Wrap<Box<dyn Widget>>,
+    anchor: Point,
+    position: Point,
+    size: Size,
+}
+
+/// Draws popups such as context menus above its child.
+///
+/// An open popup is shrunk to its minimum size and kept on screen, flipping
+/// to the other side of its anchor near the edges. It captures all input
+/// while open, and is dismissed by clicking outside of it or pressing Escape.
+pub struct PopupLayer<T: Widget> {
+    pub inner: Wrap<T>,
+    handle: PopupHandle,
+    popup: Option<OpenPopup>,
+    size: Size,
+}
+
+impl<T: Widget> PopupLayer<T> {
+    pub fn new(inner: impl Into<Wrap<T>>) -> Self {
+        Self {
+            inner: inner.into(),
+            handle: PopupHandle::new(),
+            popup: None,
+            size: Size::new_empty(),
+        }
+    }
+
+    pub fn handle(&self) -> PopupHandle {
+        self.handle.clone()
+    }
+
+    /// Open or close what was asked for since. A newly opened popup is laid
+    /// out straight away, as it can be drawn before the next update.
+    fn apply_requests(&mut self) {
+        let request = self.handle.0.lock().unwrap().request.take();
+        match request {
+            Some(Request::Open(anchor, widget)) => {
+                self.popup = Some(OpenPopup {
+                    widget: Wrap::new(widget),
+                    anchor,
+                    position: anchor,
+                    size: Size::new_empty(),
+                });
+                self.layout_popup();
+            }
+            Some(Request::Close) => self.popup = None,
+            None => {}
+        }
+        self.handle.0.lock().unwrap().open = self.popup.is_some();
+    }
+
+    fn layout_popup(&mut self) {
+        let bounds = self.size;
+        if let Some(popup) = &mut self.popup {
+            let (s, changed) = popup.widget.size();
+            let size = Size::new(s.width.min, s.height.min);
+            if changed || size != popup.size {
+                popup.size = size;
+                popup.widget.set_size(size);
+            }
+            popup.position = Point::new(
+                Self::place(popup.anchor.x, size.width, bounds.width),
+                Self::place(popup.anchor.y, size.height, bounds.height),
+            );
+        }
+    }
+
+    /// Place a span starting at the anchor so it stays within the bounds,
+    /// flipping it to end at the anchor instead if it would overflow.
+    fn place(anchor: f32, length: f32, bounds: f32) -> f32 {
+        if anchor + length <= bounds {
+            anchor
+        } else if anchor - length >= 0.0 {
+            anchor - length
+        } else {
+            (bounds - length).max(0.0)
+        }
+    }
+}
+
+impl<T: Widget> Widget for PopupLayer<T> {
+    // The popup is placed wherever it fits, outside of the layer too
+    fn on_mount(&mut self, wrap: &mut WrapState) {
+        wrap.set_cull_margin(None);
+    }
+
+    fn update(&mut self, _wrap: &mut WrapState) {
+        self.apply_requests();
+        self.layout_popup();
+        self.inner.update();
+        if let Some(popup) = &mut self.popup {
+            popup.widget.update();
+        }
+    }
+
+    fn input(&mut self, _wrap: &mut WrapState, event: &InputEvent) -> bool {
+        let popup = match &mut self.popup {
+            Some(popup) => popup,
+            None => return self.inner.input(event),
+        };
+        let dismiss = match event {
+            InputEvent::KeyDown(Keycode::Escape, _) => true,
+            InputEvent::MouseDown(_, p, _) => {
+                !Rect::from_point_and_size(popup.position, popup.size).contains(*p)
+            }
+            _ => false,
+        };
+        if dismiss {
+            self.handle.close();
+        } else if let Some(event) = event.reverse_map_position(Matrix::translate(popup.position)) {
+            popup.widget.input(&event);
+        }
+        self.apply_requests();
+        true
+    }
+
+    forward_widget!(inner; size, height_for_width);
+
+    fn set_size(&mut self, _wrap: &mut WrapState, size: Size) {
+        self.size = size;
+        self.inner.set_size(size);
+        self.layout_popup();
+    }
+
+    // The popup floats over the layer, so only the content takes space
+    fn draw(&mut self, _wrap: &mut WrapState, canvas: &mut Canvas) {
+        // Popups opened from the input of the content this frame
+        self.apply_requests();
+        self.inner.draw(canvas);
+        if let Some(popup) = &mut self.popup {
+            canvas.save();
+            canvas.concat(&Matrix::translate(popup.position));
+            popup.widget.draw(canvas);
+            canvas.restore();
+        }
+    }
+
+    fn get(&mut self, _wrap: &mut WrapState, id: ID) -> Option<(&mut dyn Widget, &mut WrapState)> {
+        if let Some(popup) = &mut self.popup {
+            if let Some(x) = popup.widget.get(id) {
+                return Some(x);
+            }
+        }
+        self.inner.get(id)
+    }
+}
@@ -0,0 +1,394 @@
+use std::time::Duration;
+
+use crate::framework::tween::Tween;
+use crate::prelude::*;
+use game::{InputEvent, MouseButton, State, ID};
+use skia::{scalar, Canvas, Matrix, Paint, Point, Rect, Size};
+
+/// How far the mouse may move during a long press before it counts as
+/// something else, like scrolling
+const PRESS_SLOP: scalar = 8.0;
+
+struct Item<T: Widget> {
+    inner: Wrap<T>,
+    height: scalar,
+    top: Tween,
+}
+
+struct Press {
+    index: usize,
+    point: Point,
+    at: Duration,
+}
+
+struct Drag {
+    from: usize,
+    to: usize,
+    /// Where the item was grabbed, from its top
+    grab: scalar,
+    pointer: scalar,
+}
+
+/// A vertical list whose items can be dragged to a new place, for editing
+/// playlists and the like.
+///
+/// Items are picked up with a long press, or right away when pressed on a
+/// drag handle along their left edge if [`with_handle`](Self::with_handle)
+/// is set. While an item is dragged, the others slide out of the way to
+/// open a gap where it would land, optionally filled with a placeholder.
+/// Dropping it moves it there and calls `on_reorder(from, to)` with its old
+/// and new index.
+///
+/// An item picked up with a long press has already seen the mouse going
+/// down, so it is sent a mouse up outside of its bounds to let go of the
+/// press without acting on it.
+pub struct ReorderableList<T: Widget> {
+    items: Vec<Item<T>>,
+    pub layout_size: LayoutSize,
+    pub long_press: Duration,
+    pub handle: Option<scalar>,
+    pub placeholder: Option<Paint>,
+    /// How long displaced items take to slide into their new place
+    pub animation: Duration,
+    on_reorder: Option<Box<dyn FnMut(usize, usize) + Send>>,
+    press: Option<Press>,
+    drag: Option<Drag>,
+    gap_top: scalar,
+    size: Size,
+    /// Set when items were added or removed, which needs a new layout even
+    /// if no item's size changed
+    items_changed: bool,
+}
+
+impl<T: Widget> ReorderableList<T> {
+    pub fn new<I, W>(items: I, size: LayoutSize) -> Self
+    where
+        W: Into<Wrap<T>>,
+        I: IntoIterator<Item = W>,
+    {
+        Self {
+            items: items.into_iter().map(|i| Self::item(i.into())).collect(),
+            layout_size: size,
+            long_press: Duration::from_millis(400),
+            handle: None,
+            placeholder: None,
+            animation: Duration::from_millis(150),
+            on_reorder: None,
+            press: None,
+            drag: None,
+            gap_top: 0.0,
+            size: Size::new_empty(),
+            items_changed: false,
+        }
+    }
+
+    /// Pick items up as soon as they are pressed within `width` logical
+    /// pixels of their left edge, instead of after a long press
+    pub fn with_handle(mut self, width: scalar) -> Self {
+        self.handle = Some(width);
+        self
+    }
+
+    pub fn with_long_press(mut self, duration: Duration) -> Self {
+        self.long_press = duration;
+        self
+    }
+
+    /// Fill the gap left for the dragged item
+    pub fn with_placeholder(mut self, paint: Paint) -> Self {
+        self.placeholder = Some(paint);
+        self
+    }
+
+    pub fn with_on_reorder(mut self, f: impl FnMut(usize, usize) + Send + 'static) -> Self {
+        self.on_reorder = Some(Box::new(f));
+        self
+    }
+
+    fn item(inner: Wrap<T>) -> Item<T> {
+        Item {
+            inner,
+            height: 0.0,
+            top: Tween::new(0.0),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    pub fn push(&mut self, item: impl Into<Wrap<T>>) {
+        self.cancel();
+        self.items.push(Self::item(item.into()));
+        self.items_changed = true;
+    }
+
+    pub fn remove(&mut self, index: usize) -> T {
+        self.cancel();
+        self.items_changed = true;
+        self.items.remove(index).inner.into_inner()
+    }
+
+    pub fn item_mut(&mut self, index: usize) -> Option<&mut T> {
+        self.items.get_mut(index).map(|i| &mut i.inner.inner)
+    }
+
+    pub fn is_dragging(&self) -> bool {
+        self.drag.is_some()
+    }
+
+    fn cancel(&mut self) {
+        self.press = None;
+        self.drag = None;
+    }
+
+    fn hit(&self, p: Point) -> Option<usize> {
+        if p.x < 0.0 || p.x > self.size.width {
+            return None;
+        }
+        self.items.iter().position(|i| {
+            let top = i.top.value();
+            p.y >= top && p.y < top + i.height
+        })
+    }
+
+    fn start_drag(&mut self, index: usize, p: Point) {
+        self.press = None;
+        self.drag = Some(Drag {
+            from: index,
+            to: index,
+            grab: p.y - self.items[index].top.value(),
+            pointer: p.y,
+        });
+        self.arrange(true);
+    }
+
+    fn dragged_top(&self, drag: &Drag) -> scalar {
+        let height = self.items[drag.from].height;
+        let max = (self.size.height - height).max(0.0);
+        (drag.pointer - drag.grab).max(0.0).min(max)
+    }
+
+    /// The index the dragged item would land at: the number of other items
+    /// whose center is above its own, as if it weren't in the list
+    fn target(&self, drag: &Drag) -> usize {
+        let center = self.dragged_top(drag) + self.items[drag.from].height / 2.0;
+        let mut top = 0.0;
+        let mut to = 0;
+        for (n, i) in self.items.iter().enumerate() {
+            if n == drag.from {
+                continue;
+            }
+            if top + i.height / 2.0 < center {
+                to += 1;
+            }
+            top += i.height;
+        }
+        to
+    }
+
+    /// Move every item towards its place, leaving a gap for the dragged one
+    fn arrange(&mut self, animate: bool) {
+        let (from, to, gap) = match &self.drag {
+            Some(d) => (Some(d.from), d.to, self.items[d.from].height),
+            None => (None, 0, 0.0),
+        };
+        let mut top = 0.0;
+        let mut k = 0;
+        for (n, i) in self.items.iter_mut().enumerate() {
+            if Some(n) == from {
+                continue;
+            }
+            if from.is_some() && k == to {
+                self.gap_top = top;
+                top += gap;
+            }
+            if !animate {
+                i.top.set(top);
+            } else if i.top.target() != top {
+                i.top.animate_to(top, self.animation);
+            }
+            top += i.height;
+            k += 1;
+        }
+        if from.is_some() && k == to {
+            self.gap_top = top;
+        }
+    }
+
+    fn release(&mut self) {
+        let drag = match self.drag.take() {
+            Some(d) => d,
+            None => return,
+        };
+        // Start from where the item was let go, so it slides into place
+        let top = self.dragged_top(&drag);
+        self.items[drag.from].top.set(top);
+        let item = self.items.remove(drag.from);
+        self.items.insert(drag.to, item);
+        self.arrange(true);
+        if drag.from != drag.to {
+            if let Some(f) = &mut self.on_reorder {
+                f(drag.from, drag.to);
+            }
+        }
+    }
+
+    fn forward(&mut self, event: &InputEvent) -> bool {
+        self.items.iter_mut().rev().any(|i| {
+            let m = Matrix::translate((0.0, i.top.value()));
+            event
+                .reverse_map_position(m)
+                .map_or(false, |event| i.inner.input(&event))
+        })
+    }
+}
+
+impl<T: Widget> Widget for ReorderableList<T> {
+    fn update(&mut self, _wrap: &mut WrapState) {
+        for i in &mut self.items {
+            i.inner.update();
+        }
+        let due = self
+            .press
+            .as_ref()
+            .filter(|p| State::elapsed() - p.at >= self.long_press)
+            .map(|p| (p.index, p.point));
+        if let Some((index, point)) = due {
+            let outside = Point::new(-1.0, -1.0);
+            self.items[index].inner.input(&InputEvent::MouseUp(
+                MouseButton::Left,
+                outside,
+                State::elapsed(),
+            ));
+            self.start_drag(index, point);
+            State::request_redraw();
+        }
+    }
+
+    fn input(&mut self, _wrap: &mut WrapState, event: &InputEvent) -> bool {
+        match event {
+            InputEvent::MouseDown(MouseButton::Left, p, _) if self.drag.is_none() => {
+                if let Some(index) = self.hit(*p) {
+                    match self.handle {
+                        Some(width) if p.x <= width => {
+                            self.start_drag(index, *p);
+                            return true;
+                        }
+                        Some(_) => {}
+                        None => {
+                            self.press = Some(Press {
+                                index,
+                                point: *p,
+                                at: State::elapsed(),
+                            })
+                        }
+                    }
+                }
+            }
+            InputEvent::MouseMove(p, _) => {
+                if let Some(mut drag) = self.drag.take() {
+                    drag.pointer = p.y;
+                    drag.to = self.target(&drag);
+                    self.drag = Some(drag);
+                    self.arrange(true);
+                    State::request_redraw();
+                    return true;
+                }
+                if let Some(press) = &self.press {
+                    if (*p - press.point).length() > PRESS_SLOP {
+                        self.press = None;
+                    }
+                }
+            }
+            InputEvent::MouseUp(MouseButton::Left, ..) => {
+                self.press = None;
+                if self.drag.is_some() {
+                    self.release();
+                    State::request_redraw();
+                    return true;
+                }
+            }
+            _ => {}
+        }
+        if self.drag.is_some() {
+            return true;
+        }
+        self.forward(event)
+    }
+
+    fn size(&mut self, _wrap: &mut WrapState) -> (LayoutSize, bool) {
+        let mut width_min = 0.0f32;
+        let mut height_min = 0.0f32;
+        let mut changed = std::mem::take(&mut self.items_changed);
+        for i in &mut self.items {
+            let (size, c) = i.inner.size();
+            changed |= c;
+            width_min = width_min.max(size.width.min);
+            height_min += size.height.min;
+        }
+        let mut size = self.layout_size;
+        size.width.min = size.width.min.max(width_min);
+        size.height.min = size.height.min.max(height_min);
+        (size, changed)
+    }
+
+    fn set_size(&mut self, _wrap: &mut WrapState, size: Size) {
+        let first = self.size.is_empty();
+        self.size = size;
+        for i in &mut self.items {
+            let min = i.inner.state.layout_size().height.min;
+            i.height = i
+                .inner
+                .height_for_width(size.width)
+                .map_or(min, |h| h.max(min));
+            i.inner.set_size(Size::new(size.width, i.height));
+        }
+        self.arrange(!first);
+    }
+
+    fn draw(&mut self, _wrap: &mut WrapState, canvas: &mut Canvas) {
+        let from = self.drag.as_ref().map(|d| d.from);
+        if let (Some(from), Some(paint)) = (from, &self.placeholder) {
+            let height = self.items[from].height;
+            let rect = Rect::from_xywh(0.0, self.gap_top, self.size.width, height);
+            canvas.draw_rect(rect, paint);
+        }
+        let mut animating = false;
+        for (n, i) in self.items.iter_mut().enumerate() {
+            if Some(n) == from {
+                continue;
+            }
+            animating |= !i.top.is_finished();
+            canvas.save();
+            canvas.translate((0.0, i.top.value()));
+            i.inner.draw(canvas);
+            canvas.restore();
+        }
+        // The dragged item goes over everything else
+        if let Some(drag) = &self.drag {
+            let top = self.dragged_top(drag);
+            canvas.save();
+            canvas.translate((0.0, top));
+            self.items[drag.from].inner.draw(canvas);
+            canvas.restore();
+        }
+        if animating {
+            State::request_redraw();
+        }
+    }
+
+    fn get(&mut self, _wrap: &mut WrapState, id: ID) -> Option<(&mut dyn Widget, &mut WrapState)> {
+        for i in &mut self.items {
+            let x = i.inner.get(id);
+            if x.is_some() {
+                return x;
+            }
+        }
+        None
+    }
+}
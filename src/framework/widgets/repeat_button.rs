@@ -0,0 +1,136 @@
+use std::time::Duration;
+
+use crate::prelude::*;
+use game::{InputEvent, MouseButton, State};
+use skia::{Contains, Rect, Size};
+
+/// Schedules a repeating action while something is held, in the same manner
+/// as key repeat: once on press, once more after an initial delay, then at a
+/// steady interval until released.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Repeater {
+    pub delay: Duration,
+    pub interval: Duration,
+    pressed_at: Option<Duration>,
+    fired: u32,
+}
+
+impl Repeater {
+    pub fn new(delay: Duration, interval: Duration) -> Self {
+        Self {
+            delay,
+            interval,
+            pressed_at: None,
+            fired: 0,
+        }
+    }
+
+    pub fn press(&mut self) {
+        self.pressed_at = Some(State::elapsed());
+        self.fired = 0;
+    }
+
+    pub fn release(&mut self) {
+        self.pressed_at = None;
+    }
+
+    pub fn is_pressed(&self) -> bool {
+        self.pressed_at.is_some()
+    }
+
+    /// How many times the action is due to fire since the last poll
+    pub fn poll(&mut self) -> u32 {
+        let held = match self.pressed_at {
+            Some(t) => State::elapsed() - t,
+            None => return 0,
+        };
+        let due = if held < self.delay {
+            1
+        } else if self.interval == Duration::ZERO {
+            2
+        } else {
+            2 + ((held - self.delay).as_secs_f64() / self.interval.as_secs_f64()) as u32
+        };
+        let pending = due.saturating_sub(self.fired);
+        self.fired = due;
+        pending
+    }
+}
+
+impl Default for Repeater {
+    fn default() -> Self {
+        Self::new(Duration::from_millis(400), Duration::from_millis(50))
+    }
+}
+
+/// Calls its callback repeatedly while its child is held down with the mouse.
+pub struct RepeatButton<T: Widget> {
+    pub inner: Wrap<T>,
+    pub repeater: Repeater,
+    pub button: MouseButton,
+    callback: Box<dyn FnMut() + Send>,
+    size: Size,
+}
+
+impl<T: Widget> RepeatButton<T> {
+    pub fn new(inner: impl Into<Wrap<T>>, callback: impl FnMut() + Send + 'static) -> Self {
+        Self {
+            inner: inner.into(),
+            repeater: Repeater::default(),
+            button: MouseButton::Left,
+            callback: Box::new(callback),
+            size: Size::new_empty(),
+        }
+    }
+
+    pub fn with_repeater(mut self, repeater: Repeater) -> Self {
+        self.repeater = repeater;
+        self
+    }
+
+    pub fn with_button(mut self, button: MouseButton) -> Self {
+        self.button = button;
+        self
+    }
+}
+
+impl<T: Widget> Widget for RepeatButton<T> {
+    fn update(&mut self, _wrap: &mut WrapState) {
+        for _ in 0..self.repeater.poll() {
+            (self.callback)();
+        }
+        self.inner.update();
+    }
+
+    fn input(&mut self, _wrap: &mut WrapState, event: &InputEvent) -> bool {
+        // The release stops the repeating even if the child handles it, or
+        // the button would keep firing after being let go
+        if let InputEvent::MouseUp(b, ..) = event {
+            if *b == self.button && self.repeater.is_pressed() {
+                self.repeater.release();
+                self.inner.input(event);
+                return true;
+            }
+        }
+        if self.inner.input(event) {
+            return true;
+        }
+        match event {
+            InputEvent::MouseDown(b, p, _) if *b == self.button => {
+                let hit = Rect::from_size(self.size).contains(*p);
+                if hit {
+                    self.repeater.press();
+                }
+                hit
+            }
+            _ => false,
+        }
+    }
+
+    forward_widget!(inner; size, height_for_width, draw, get);
+
+    fn set_size(&mut self, _wrap: &mut WrapState, size: Size) {
+        self.size = size;
+        self.inner.set_size(size);
+    }
+}
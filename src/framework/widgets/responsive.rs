@@ -0,0 +1,113 @@
+use crate::prelude::*;
+use game::{InputEvent, ID};
+use skia::{scalar, Canvas, Size};
+
+/// Switches between layouts depending on the width it is given.
+///
+/// Each child has a breakpoint, the minimum width at which it is used. On
+/// every layout pass the child with the largest breakpoint that still fits
+/// the allocated width is shown, falling back to the narrowest one. The
+/// reported layout size is that of the child currently shown.
+pub struct Responsive {
+    breakpoints: Vec<(scalar, Wrap<Box<dyn Widget>>)>,
+    active: usize,
+    switched: bool,
+}
+
+impl Responsive {
+    pub fn new<I, W>(breakpoints: I) -> Self
+    where
+        W: Into<Wrap<Box<dyn Widget>>>,
+        I: IntoIterator<Item = (scalar, W)>,
+    {
+        let mut breakpoints: Vec<_> = breakpoints
+            .into_iter()
+            .map(|(min_width, w)| (min_width, w.into()))
+            .collect();
+        breakpoints.sort_by(|a, b| a.0.total_cmp(&b.0));
+        Self {
+            breakpoints,
+            active: 0,
+            switched: false,
+        }
+    }
+
+    /// The index of the child currently shown, in order of breakpoint
+    pub fn active(&self) -> usize {
+        self.active
+    }
+
+    fn pick(&self, width: scalar) -> usize {
+        self.breakpoints
+            .iter()
+            .rposition(|(min_width, _)| *min_width <= width)
+            .unwrap_or(0)
+    }
+
+    fn current(&mut self) -> Option<&mut Wrap<Box<dyn Widget>>> {
+        self.breakpoints.get_mut(self.active).map(|(_, w)| w)
+    }
+}
+
+impl Widget for Responsive {
+    fn update(&mut self, _wrap: &mut WrapState) {
+        if let Some(w) = self.current() {
+            w.update();
+        }
+    }
+
+    fn input(&mut self, _wrap: &mut WrapState, event: &InputEvent) -> bool {
+        self.current().map_or(false, |w| w.input(event))
+    }
+
+    fn size(&mut self, _wrap: &mut WrapState) -> (LayoutSize, bool) {
+        // A switch made during the last layout pass changes what we report,
+        // so the parent has to lay us out again.
+        let switched = std::mem::replace(&mut self.switched, false);
+        match self.current() {
+            Some(w) => {
+                let (s, c) = w.size();
+                (s, c || switched)
+            }
+            None => (LayoutSize::ZERO, switched),
+        }
+    }
+
+    fn set_size(&mut self, _wrap: &mut WrapState, size: Size) {
+        let active = self.pick(size.width);
+        if active != self.active {
+            self.active = active;
+            self.switched = true;
+            if let Some(w) = self.current() {
+                w.size();
+            }
+        }
+        if let Some(w) = self.current() {
+            w.set_size(size);
+        }
+    }
+
+    // The child that would be shown at that width decides
+    fn height_for_width(&mut self, _wrap: &mut WrapState, width: scalar) -> Option<scalar> {
+        let index = self.pick(width);
+        self.breakpoints
+            .get_mut(index)
+            .and_then(|(_, w)| w.height_for_width(width))
+    }
+
+    fn draw(&mut self, _wrap: &mut WrapState, canvas: &mut Canvas) {
+        if let Some(w) = self.current() {
+            w.draw(canvas);
+        }
+    }
+
+    fn get(&mut self, _wrap: &mut WrapState, id: ID) -> Option<(&mut dyn Widget, &mut WrapState)> {
+        for (_, w) in &mut self.breakpoints {
+            let x = w.get(id);
+            if x.is_some() {
+                return x;
+            }
+        }
+        None
+    }
+}
@@ -0,0 +1,153 @@
+use std::time::Duration;
+
+use crate::framework::tween::{Easing, Tween};
+use crate::prelude::*;
+use game::{InputEvent, State};
+use skia::{scalar, Canvas, Color4f, Paint, Rect, Size};
+
+/// Fades its child to and from a color, black by default, for scene changes
+/// and the like. Wrapping the root widget fades the whole screen.
+///
+/// Each fade can be given a callback that gets the child once the fade is
+/// over, so that [`fade_through`](Self::fade_through) can swap the scene
+/// while the screen is covered. Input is kept from the child while a fade
+/// runs and while it is covered.
+pub struct ScreenFade<T: Widget> {
+    pub inner: Wrap<T>,
+    pub color: Color4f,
+    /// How much the color covers the child, from 0 to 1
+    opacity: Tween,
+    then: Option<Box<dyn FnOnce(&mut T) + Send>>,
+    /// How long to fade back in for once covered, for `fade_through`
+    fade_back: Option<Duration>,
+    size: Size,
+}
+
+impl<T: Widget> ScreenFade<T> {
+    /// Start out showing the child
+    pub fn new(inner: impl Into<Wrap<T>>) -> Self {
+        Self {
+            inner: inner.into(),
+            color: Color4f::new(0.0, 0.0, 0.0, 1.0),
+            opacity: Tween::new(0.0).with_easing(Easing::Linear),
+            then: None,
+            fade_back: None,
+            size: Size::new_empty(),
+        }
+    }
+
+    pub fn with_color(mut self, color: Color4f) -> Self {
+        self.color = color;
+        self
+    }
+
+    pub fn with_easing(mut self, easing: Easing) -> Self {
+        self.opacity.easing = easing;
+        self
+    }
+
+    /// Start out covered, to fade in from at startup
+    pub fn covered(mut self) -> Self {
+        self.opacity.set(1.0);
+        self
+    }
+
+    /// Cover the child, then call `then` with it
+    pub fn fade_out(&mut self, duration: Duration, then: impl FnOnce(&mut T) + Send + 'static) {
+        self.fade(1.0, duration, Some(Box::new(then)));
+    }
+
+    /// Uncover the child, then call `then` with it
+    pub fn fade_in(&mut self, duration: Duration, then: impl FnOnce(&mut T) + Send + 'static) {
+        self.fade(0.0, duration, Some(Box::new(then)));
+    }
+
+    /// Cover the child, call `midpoint` with it, and uncover it again,
+    /// taking `duration` for each half
+    pub fn fade_through(
+        &mut self,
+        duration: Duration,
+        midpoint: impl FnOnce(&mut T) + Send + 'static,
+    ) {
+        self.fade(1.0, duration, Some(Box::new(midpoint)));
+        self.fade_back = Some(duration);
+    }
+
+    /// Whether a fade is running
+    pub fn is_fading(&self) -> bool {
+        !self.opacity.is_finished() || self.then.is_some() || self.fade_back.is_some()
+    }
+
+    pub fn opacity(&self) -> scalar {
+        self.opacity.value()
+    }
+
+    /// Start a fade, calling the callback of an interrupted one right away
+    /// so that a scene change isn't lost
+    fn fade(
+        &mut self,
+        to: scalar,
+        duration: Duration,
+        then: Option<Box<dyn FnOnce(&mut T) + Send>>,
+    ) {
+        if let Some(interrupted) = self.then.take() {
+            interrupted(&mut self.inner.inner);
+        }
+        self.fade_back = None;
+        self.then = then;
+        self.opacity.animate_to(to, duration);
+        State::request_redraw();
+    }
+}
+
+impl<T: Widget> Widget for ScreenFade<T> {
+    fn update(&mut self, _wrap: &mut WrapState) {
+        if self.opacity.is_finished() {
+            if let Some(then) = self.then.take() {
+                then(&mut self.inner.inner);
+            }
+            if let Some(duration) = self.fade_back.take() {
+                self.opacity.animate_to(0.0, duration);
+                State::request_redraw();
+            }
+        }
+        self.inner.update();
+    }
+
+    fn input(&mut self, _wrap: &mut WrapState, event: &InputEvent) -> bool {
+        let covered = self.is_fading() || self.opacity.value() >= 1.0;
+        // Releases still reach the child, so nothing pressed before the fade
+        // is left held down
+        if covered && matches!(event, InputEvent::MouseUp(..)) {
+            self.inner.input(event);
+            return true;
+        }
+        if covered {
+            return event.position().is_some();
+        }
+        self.inner.input(event)
+    }
+
+    forward_widget!(inner; size, height_for_width, get);
+
+    fn set_size(&mut self, _wrap: &mut WrapState, size: Size) {
+        self.size = size;
+        self.inner.set_size(size);
+    }
+
+    fn draw(&mut self, _wrap: &mut WrapState, canvas: &mut Canvas) {
+        let opacity = self.opacity.value();
+        // Nothing of the child shows through once it is fully covered
+        if opacity < 1.0 || self.color.a < 1.0 {
+            self.inner.draw(canvas);
+        }
+        if opacity > 0.0 {
+            let mut color = self.color;
+            color.a *= opacity;
+            canvas.draw_rect(Rect::from_size(self.size), &Paint::new(color, None));
+        }
+        if self.is_fading() {
+            State::request_redraw();
+        }
+    }
+}
@@ -0,0 +1,181 @@
+use std::time::Duration;
+
+use crate::prelude::*;
+use game::{InputEvent, State};
+use skia::{scalar, Canvas, Contains, Paint, Point, Rect, Size, Vector};
+use skulpin_renderer_sdl2::sdl2::mouse::MouseButton;
+
+use super::{Orientation, ScrollHandle, ScrollState};
+
+/// A draggable bar reflecting the scroll position of a
+/// [`ScrollView`](super::ScrollView) sharing the same [`ScrollHandle`].
+///
+/// The bar hides itself when the content fits, and fades out after a while
+/// without scrolling.
+pub struct ScrollBar {
+    pub handle: ScrollHandle,
+    pub orientation: Orientation,
+    pub thickness: scalar,
+    pub thumb: Paint,
+    pub track: Paint,
+    /// How long the bar stays visible after scrolling, or `None` to always
+    /// show it while the content overflows.
+    pub fade_after: Option<Duration>,
+    size: Size,
+    grab: Option<scalar>,
+    hovered: bool,
+}
+
+impl ScrollBar {
+    const FADE_TIME: f32 = 0.3;
+
+    pub fn new(
+        handle: ScrollHandle,
+        orientation: Orientation,
+        thickness: scalar,
+        thumb: Paint,
+        track: Paint,
+    ) -> Self {
+        Self {
+            handle,
+            orientation,
+            thickness,
+            thumb,
+            track,
+            fade_after: Some(Duration::SECOND),
+            size: Size::new_empty(),
+            grab: None,
+            hovered: false,
+        }
+    }
+
+    pub fn with_fade_after(mut self, fade_after: Option<Duration>) -> Self {
+        self.fade_after = fade_after;
+        self
+    }
+
+    fn along(&self, v: Vector) -> scalar {
+        match self.orientation {
+            Orientation::Horizontal => v.x,
+            Orientation::Vertical => v.y,
+        }
+    }
+
+    fn along_size(&self, s: Size) -> scalar {
+        self.along(Vector::new(s.width, s.height))
+    }
+
+    /// Position and length of the thumb along the track
+    fn thumb_span(&self, state: &ScrollState) -> (scalar, scalar) {
+        let track = self.along_size(self.size);
+        let content = self.along_size(state.content);
+        let viewport = self.along_size(state.viewport);
+        let max_offset = self.along(state.max_offset());
+        let len = (viewport / content * track).max(self.thickness.min(track));
+        let pos = if max_offset > 0.0 {
+            self.along(state.offset) / max_offset * (track - len)
+        } else {
+            0.0
+        };
+        (pos, len)
+    }
+
+    fn thumb_rect(&self, state: &ScrollState) -> Rect {
+        let (pos, len) = self.thumb_span(state);
+        match self.orientation {
+            Orientation::Horizontal => Rect::from_xywh(pos, 0.0, len, self.size.height),
+            Orientation::Vertical => Rect::from_xywh(0.0, pos, self.size.width, len),
+        }
+    }
+
+    fn drag_to(&self, p: Point, grab: scalar) {
+        let state = self.handle.get();
+        let (_, len) = self.thumb_span(&state);
+        let free = self.along_size(self.size) - len;
+        if free <= 0.0 {
+            return;
+        }
+        let offset = (self.along(p) - grab) / free * self.along(state.max_offset());
+        self.handle.scroll_to(match self.orientation {
+            Orientation::Horizontal => Vector::new(offset, state.offset.y),
+            Orientation::Vertical => Vector::new(state.offset.x, offset),
+        });
+    }
+
+    fn opacity(&self, state: &ScrollState) -> f32 {
+        match self.fade_after {
+            Some(fade_after) if self.grab.is_none() && !self.hovered => {
+                let idle = State::elapsed()
+                    .checked_sub(state.last_scrolled + fade_after)
+                    .unwrap_or(Duration::ZERO);
+                1.0 - (idle.as_secs_f32() / Self::FADE_TIME).min(1.0)
+            }
+            _ => 1.0,
+        }
+    }
+}
+
+impl Widget for ScrollBar {
+    fn input(&mut self, _wrap: &mut WrapState, event: &InputEvent) -> bool {
+        let state = self.handle.get();
+        match event {
+            InputEvent::MouseMove(p, _) => {
+                self.hovered = Rect::from_size(self.size).contains(*p);
+                if let Some(grab) = self.grab {
+                    self.drag_to(*p, grab);
+                    return true;
+                }
+                false
+            }
+            InputEvent::MouseDown(MouseButton::Left, p, _) => {
+                if !state.overflows(self.orientation) || !Rect::from_size(self.size).contains(*p) {
+                    return false;
+                }
+                let (pos, len) = self.thumb_span(&state);
+                let grab = self.along(*p) - pos;
+                if (0.0..=len).contains(&grab) {
+                    self.grab = Some(grab);
+                } else {
+                    // Clicking the track centers the thumb on the cursor.
+                    self.grab = Some(len * 0.5);
+                    self.drag_to(*p, len * 0.5);
+                }
+                true
+            }
+            InputEvent::MouseUp(MouseButton::Left, ..) => self.grab.take().is_some(),
+            _ => false,
+        }
+    }
+
+    fn size(&mut self, _wrap: &mut WrapState) -> (LayoutSize, bool) {
+        (
+            match self.orientation {
+                Orientation::Horizontal => LayoutSize::min(0.0, self.thickness).expand_width(),
+                Orientation::Vertical => LayoutSize::min(self.thickness, 0.0).expand_height(),
+            },
+            false,
+        )
+    }
+
+    fn set_size(&mut self, _wrap: &mut WrapState, size: Size) {
+        self.size = size;
+    }
+
+    fn draw(&mut self, _wrap: &mut WrapState, canvas: &mut Canvas) {
+        let state = self.handle.get();
+        if !state.overflows(self.orientation) {
+            return;
+        }
+        let opacity = self.opacity(&state);
+        if opacity <= 0.0 {
+            return;
+        }
+        let mut track = self.track.clone();
+        track.set_alpha_f(track.alpha_f() * opacity);
+        let mut thumb = self.thumb.clone();
+        thumb.set_alpha_f(thumb.alpha_f() * opacity);
+        let radius = self.thickness * 0.5;
+        canvas.draw_rect(Rect::from_size(self.size), &track);
+        canvas.draw_round_rect(self.thumb_rect(&state), radius, radius, &thumb);
+    }
+}
@@ -0,0 +1,353 @@
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::framework::tween::Tween;
+use crate::prelude::*;
+use game::{InputEvent, State, ID};
+use skia::{scalar, Canvas, Contains, Matrix, Rect, Size, Vector};
+
+/// Scroll position and extents of a [`ScrollView`].
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ScrollState {
+    pub offset: Vector,
+    /// Size of the scrolled content
+    pub content: Size,
+    /// Size of the visible area
+    pub viewport: Size,
+    /// When the offset last changed, as per [`State::elapsed`]
+    pub last_scrolled: Duration,
+}
+
+impl ScrollState {
+    /// The largest offset that still keeps the viewport filled with content
+    pub fn max_offset(&self) -> Vector {
+        Vector::new(
+            (self.content.width - self.viewport.width).max(0.0),
+            (self.content.height - self.viewport.height).max(0.0),
+        )
+    }
+
+    /// Whether the content overflows the viewport in the given direction
+    pub fn overflows(&self, orientation: Orientation) -> bool {
+        match orientation {
+            Orientation::Horizontal => self.content.width > self.viewport.width,
+            Orientation::Vertical => self.content.height > self.viewport.height,
+        }
+    }
+
+    fn clamp(&mut self) {
+        let max = self.max_offset();
+        self.offset = Vector::new(
+            self.offset.x.max(0.0).min(max.x),
+            self.offset.y.max(0.0).min(max.y),
+        );
+    }
+}
+
+impl Default for ScrollState {
+    fn default() -> Self {
+        Self {
+            offset: Vector::default(),
+            content: Size::new_empty(),
+            viewport: Size::new_empty(),
+            last_scrolled: Duration::ZERO,
+        }
+    }
+}
+
+#[derive(Default)]
+struct Shared {
+    state: ScrollState,
+    // A widget to bring into view once its bounds are known, and whether
+    // it has already waited a frame for them
+    reveal: Option<(ID, bool)>,
+}
+
+/// A shared reference to the scroll state of a [`ScrollView`], so that other
+/// widgets like [`ScrollBar`](super::ScrollBar) can read and change it.
+#[derive(Clone, Default)]
+pub struct ScrollHandle(Arc<Mutex<Shared>>);
+
+impl ScrollHandle {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get(&self) -> ScrollState {
+        self.0.lock().unwrap().state
+    }
+
+    /// Scroll to the given offset, clamped to the content
+    pub fn scroll_to(&self, offset: Vector) {
+        self.with_mut(|s| {
+            let old = s.offset;
+            s.offset = offset;
+            s.clamp();
+            if s.offset != old {
+                s.last_scrolled = State::elapsed();
+            }
+        });
+    }
+
+    pub fn scroll_by(&self, delta: Vector) {
+        self.scroll_to(self.get().offset + delta);
+    }
+
+    /// Scroll just far enough for the widget with the given ID, somewhere
+    /// in the view's content, to be fully visible. This happens when the
+    /// view is next drawn, or a frame later if the widget hasn't been drawn
+    /// yet. The request is dropped if the widget isn't in the content, or
+    /// still hasn't been drawn by then.
+    pub fn scroll_into_view(&self, id: ID) {
+        self.0.lock().unwrap().reveal = Some((id, false));
+    }
+
+    fn take_reveal(&self) -> Option<(ID, bool)> {
+        self.0.lock().unwrap().reveal.take()
+    }
+
+    fn retry_reveal(&self, id: ID) {
+        self.0.lock().unwrap().reveal.get_or_insert((id, true));
+    }
+
+    fn with_mut<R>(&self, f: impl FnOnce(&mut ScrollState) -> R) -> R {
+        f(&mut self.0.lock().unwrap().state)
+    }
+}
+
+/// Clips its child to its own bounds and lets the user scroll through it with
+/// the mouse wheel.
+pub struct ScrollView<T: Widget> {
+    pub inner: Wrap<T>,
+    pub layout_size: LayoutSize,
+    pub handle: ScrollHandle,
+    pub horizontal: bool,
+    pub vertical: bool,
+    /// Logical pixels scrolled per mouse wheel step
+    pub scroll_step: scalar,
+    /// How long scrolling a widget into view takes, or `None` to jump
+    pub reveal_duration: Option<Duration>,
+    tween: Option<(Tween, Tween)>,
+    last_focus: Option<ID>,
+    inner_size: LayoutSize,
+    hovered: bool,
+}
+
+impl<T: Widget> ScrollView<T> {
+    pub fn new(inner: impl Into<Wrap<T>>, size: LayoutSize) -> Self {
+        Self {
+            inner: inner.into(),
+            layout_size: size,
+            handle: ScrollHandle::new(),
+            horizontal: false,
+            vertical: true,
+            scroll_step: 48.0,
+            reveal_duration: Some(Duration::from_millis(200)),
+            tween: None,
+            last_focus: None,
+            inner_size: LayoutSize::ZERO,
+            hovered: false,
+        }
+    }
+
+    /// Share the scroll state through an existing handle
+    pub fn with_handle(mut self, handle: ScrollHandle) -> Self {
+        self.handle = handle;
+        self
+    }
+
+    pub fn with_directions(mut self, horizontal: bool, vertical: bool) -> Self {
+        self.horizontal = horizontal;
+        self.vertical = vertical;
+        self
+    }
+
+    pub fn with_reveal_duration(mut self, duration: Option<Duration>) -> Self {
+        self.reveal_duration = duration;
+        self
+    }
+
+    pub fn handle(&self) -> ScrollHandle {
+        self.handle.clone()
+    }
+
+    /// Scroll the widget with the given ID into view, see
+    /// [`ScrollHandle::scroll_into_view`]
+    pub fn scroll_to(&self, id: ID) {
+        self.handle.scroll_into_view(id);
+    }
+
+    /// Work out the offset that shows the widget being revealed, given the
+    /// transform the view is drawn with. Puts the request back for one more
+    /// frame if the widget's bounds aren't known yet.
+    fn reveal(&mut self, matrix: &Matrix) {
+        let (id, retried) = match self.handle.take_reveal() {
+            Some(reveal) => reveal,
+            None => return,
+        };
+        if self.inner.get(id).is_none() {
+            return;
+        }
+        let bounds = State::widget_bounds(id).and_then(|b| Some(matrix.invert()?.map_rect(b).0));
+        let bounds = match bounds {
+            Some(bounds) => bounds,
+            None => {
+                if !retried {
+                    self.handle.retry_reveal(id);
+                    State::request_redraw();
+                }
+                return;
+            }
+        };
+        let state = self.handle.get();
+        let offset = state.offset;
+        // The bounds are relative to the viewport, so shift them into the
+        // content's coordinates first.
+        let target = Vector::new(
+            if self.horizontal {
+                Self::reveal_axis(offset.x, bounds.left, bounds.right, state.viewport.width)
+            } else {
+                offset.x
+            },
+            if self.vertical {
+                Self::reveal_axis(offset.y, bounds.top, bounds.bottom, state.viewport.height)
+            } else {
+                offset.y
+            },
+        );
+        match self.reveal_duration {
+            Some(duration) => {
+                let mut x = Tween::new(offset.x);
+                let mut y = Tween::new(offset.y);
+                x.animate_to(target.x, duration);
+                y.animate_to(target.y, duration);
+                self.tween = Some((x, y));
+            }
+            None => self.handle.scroll_to(target),
+        }
+    }
+
+    /// The offset along one axis that shows the span from `start` to `end`,
+    /// preferring the start if the span doesn't fit.
+    fn reveal_axis(offset: scalar, start: scalar, end: scalar, viewport: scalar) -> scalar {
+        let (start, end) = (start + offset, end + offset);
+        if start < offset || end - start > viewport {
+            start
+        } else if end > offset + viewport {
+            end - viewport
+        } else {
+            offset
+        }
+    }
+
+    fn offset_matrix(&self) -> Matrix {
+        Matrix::translate(-self.handle.get().offset)
+    }
+}
+
+impl<T: Widget> Widget for ScrollView<T> {
+    fn update(&mut self, _wrap: &mut WrapState) {
+        // Follow keyboard focus moving to widgets in the content.
+        let focus = State::with(|x| x.focus.focused().filter(|_| x.focus.is_visible()));
+        if let Some(id) = focus {
+            if self.last_focus != focus && self.inner.get(id).is_some() {
+                self.handle.scroll_into_view(id);
+            }
+        }
+        self.last_focus = focus;
+        if let Some((x, y)) = &self.tween {
+            self.handle.scroll_to(Vector::new(x.value(), y.value()));
+            if x.is_finished() && y.is_finished() {
+                self.tween = None;
+            }
+        }
+        self.inner.update();
+    }
+
+    fn input(&mut self, _wrap: &mut WrapState, event: &InputEvent) -> bool {
+        let viewport = Rect::from_size(self.handle.get().viewport);
+        match event {
+            InputEvent::MouseMove(p, _) => self.hovered = viewport.contains(*p),
+            InputEvent::MouseScroll(y, _) if self.hovered => {
+                if self.inner.input(event) {
+                    return true;
+                }
+                // Scrolling by hand takes over from an ongoing reveal.
+                self.tween = None;
+                let step = -*y as scalar * self.scroll_step;
+                let delta = if self.vertical {
+                    Vector::new(0.0, step)
+                } else {
+                    Vector::new(step, 0.0)
+                };
+                self.handle.scroll_by(delta);
+                return true;
+            }
+            InputEvent::MouseScroll(..) => return false,
+            _ => {}
+        }
+        // Positional events outside of the viewport would land on content
+        // that is scrolled out of view, except for releases that may end a
+        // drag started inside.
+        if let Some(p) = event.position() {
+            let release = matches!(event, InputEvent::MouseUp(..));
+            if !release && !viewport.contains(p) {
+                return false;
+            }
+        }
+        event
+            .reverse_map_position(self.offset_matrix())
+            .map_or(false, |event| self.inner.input(&event))
+    }
+
+    fn size(&mut self, _wrap: &mut WrapState) -> (LayoutSize, bool) {
+        let (s, c) = self.inner.size();
+        self.inner_size = s;
+        (self.layout_size, c)
+    }
+
+    fn set_size(&mut self, _wrap: &mut WrapState, size: Size) {
+        let content = Size::new(
+            if self.horizontal {
+                self.inner_size.width.min.max(size.width)
+            } else {
+                size.width
+            },
+            if self.vertical {
+                self.inner_size.height.min.max(size.height)
+            } else {
+                size.height
+            },
+        );
+        self.handle.with_mut(|s| {
+            s.viewport = size;
+            s.content = content;
+            s.clamp();
+        });
+        self.inner.set_size(content);
+    }
+
+    // Scrolling vertically makes the height independent of the content
+    fn height_for_width(&mut self, _wrap: &mut WrapState, width: scalar) -> Option<scalar> {
+        if self.vertical {
+            return None;
+        }
+        let width = if self.horizontal {
+            self.inner_size.width.min.max(width)
+        } else {
+            width
+        };
+        self.inner.height_for_width(width)
+    }
+
+    fn draw(&mut self, _wrap: &mut WrapState, canvas: &mut Canvas) {
+        self.reveal(&State::window_matrix(canvas));
+        canvas.save();
+        canvas.clip_rect(Rect::from_size(self.handle.get().viewport), None, None);
+        canvas.concat(&self.offset_matrix());
+        self.inner.draw(canvas);
+        canvas.restore();
+    }
+
+    forward_widget!(inner; get);
+}
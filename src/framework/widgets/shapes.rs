@@ -1,5 +1,27 @@
+mod radial_progress;
 mod rect;
 mod throbber;
 
+pub use radial_progress::RadialProgress;
 pub use rect::Rect;
 pub use throbber::Throbber;
+
+use std::borrow::Cow;
+
+use crate::game::State;
+use crate::skia::Paint;
+
+/// A shape's paint, anti-aliased unless the shape says otherwise. A paint
+/// that is anti-aliased already stays that way, so only an explicit `false`
+/// on the shape turns it off when the theme has it on.
+pub(crate) fn anti_aliased(paint: &Paint, anti_alias: Option<bool>) -> Cow<Paint> {
+    let anti_alias =
+        anti_alias.unwrap_or_else(|| paint.is_anti_alias() || State::with_theme(|t| t.anti_alias));
+    if anti_alias == paint.is_anti_alias() {
+        Cow::Borrowed(paint)
+    } else {
+        let mut paint = paint.clone();
+        paint.set_anti_alias(anti_alias);
+        Cow::Owned(paint)
+    }
+}
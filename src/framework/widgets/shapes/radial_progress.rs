@@ -0,0 +1,131 @@
+use crate::prelude::*;
+use game::InputEvent;
+use skia::{scalar, Canvas, Contains, Paint, PaintCap, PaintStyle, Rect, Size, Vector};
+
+/// A determinate circular progress indicator, filling an arc from the start
+/// angle around to `value` of a full turn, for loading circles and cooldown
+/// timers.
+///
+/// A stroked paint draws a ring, while a filled one draws a pie. The stroke
+/// width and cap are taken from the paint, and can be set with
+/// [`with_stroke_width`](Self::with_stroke_width) and
+/// [`with_cap`](Self::with_cap).
+pub struct RadialProgress {
+    pub radius: LayoutDimension,
+    /// The progress, from 0 to 1
+    pub value: scalar,
+    pub paint: Paint,
+    /// Drawn as a full circle under the progress, if set
+    pub track: Option<Paint>,
+    /// Where the arc starts, in degrees clockwise from the right. Defaults
+    /// to the top.
+    pub start_angle: scalar,
+    pub clockwise: bool,
+    pub take_input: bool,
+    /// Overrides the theme's anti-aliasing default when set
+    pub anti_alias: Option<bool>,
+    size: Size,
+}
+
+impl RadialProgress {
+    pub fn new(radius: LayoutDimension, value: scalar, paint: Paint, take_input: bool) -> Self {
+        Self {
+            radius,
+            value,
+            paint,
+            track: None,
+            start_angle: -90.0,
+            clockwise: true,
+            take_input,
+            anti_alias: None,
+            size: Size::new_empty(),
+        }
+    }
+
+    pub fn with_track(mut self, paint: Paint) -> Self {
+        self.track = Some(paint);
+        self
+    }
+
+    pub fn with_start_angle(mut self, degrees: scalar) -> Self {
+        self.start_angle = degrees;
+        self
+    }
+
+    pub fn with_clockwise(mut self, clockwise: bool) -> Self {
+        self.clockwise = clockwise;
+        self
+    }
+
+    /// Draw a ring of this width, changing the paint to stroke
+    pub fn with_stroke_width(mut self, width: scalar) -> Self {
+        self.paint.set_style(PaintStyle::Stroke);
+        self.paint.set_stroke_width(width);
+        self
+    }
+
+    pub fn with_cap(mut self, cap: PaintCap) -> Self {
+        self.paint.set_stroke_cap(cap);
+        self
+    }
+
+    pub fn with_anti_alias(mut self, anti_alias: bool) -> Self {
+        self.anti_alias = Some(anti_alias);
+        self
+    }
+
+    pub fn set_value(&mut self, value: scalar) {
+        self.value = value;
+    }
+
+    /// The square the circle is drawn in, inset so that strokes stay inside
+    fn oval(&self) -> Rect {
+        let stroke_width = if self.paint.style() == PaintStyle::Fill {
+            0.0
+        } else {
+            self.paint.stroke_width()
+        };
+        let s = self.size.width.min(self.size.height) - stroke_width;
+        Rect::from_wh(s, s).with_offset(Vector::new(stroke_width, stroke_width) * 0.5)
+    }
+}
+
+impl Widget for RadialProgress {
+    fn input(&mut self, _wrap: &mut WrapState, event: &InputEvent) -> bool {
+        self.take_input
+            && event.position().map_or(false, |p| {
+                let s = self.size.width.min(self.size.height);
+                Rect::from_wh(s, s).contains(p)
+            })
+    }
+
+    fn size(&mut self, _wrap: &mut WrapState) -> (LayoutSize, bool) {
+        (
+            LayoutSize {
+                width: self.radius,
+                height: self.radius,
+            },
+            false,
+        )
+    }
+
+    fn set_size(&mut self, _wrap: &mut WrapState, size: Size) {
+        self.size = size;
+    }
+
+    fn draw(&mut self, _wrap: &mut WrapState, canvas: &mut Canvas) {
+        let oval = self.oval();
+        if let Some(track) = &self.track {
+            let track = super::anti_aliased(track, self.anti_alias);
+            canvas.draw_oval(oval, &track);
+        }
+        let value = self.value.max(0.0).min(1.0);
+        if value <= 0.0 {
+            return;
+        }
+        let paint = super::anti_aliased(&self.paint, self.anti_alias);
+        let sweep = if self.clockwise { 360.0 } else { -360.0 } * value;
+        let pie = self.paint.style() == PaintStyle::Fill;
+        canvas.draw_arc(oval, self.start_angle, sweep, pie, &paint);
+    }
+}
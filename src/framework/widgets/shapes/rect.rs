@@ -7,6 +7,8 @@ pub struct Rect {
     size: Size,
     pub paint: Paint,
     pub take_input: bool,
+    /// Overrides the theme's anti-aliasing default when set
+    pub anti_alias: Option<bool>,
 }
 
 impl Rect {
@@ -16,8 +18,14 @@ impl Rect {
             size: Size::new_empty(),
             paint,
             take_input,
+            anti_alias: None,
         }
     }
+
+    pub fn with_anti_alias(mut self, anti_alias: bool) -> Self {
+        self.anti_alias = Some(anti_alias);
+        self
+    }
 }
 
 impl Widget for Rect {
@@ -37,6 +45,7 @@ impl Widget for Rect {
     }
 
     fn draw(&mut self, _wrap: &mut WrapState, canvas: &mut Canvas) {
-        canvas.draw_rect(SkRect::from_size(self.size), &self.paint);
+        let paint = super::anti_aliased(&self.paint, self.anti_alias);
+        canvas.draw_rect(SkRect::from_size(self.size), &paint);
     }
 }
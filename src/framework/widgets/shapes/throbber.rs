@@ -7,6 +7,8 @@ pub struct Throbber {
     size: Size,
     pub paint: Paint,
     pub take_input: bool,
+    /// Overrides the theme's anti-aliasing default when set
+    pub anti_alias: Option<bool>,
     rad: scalar,
 }
 
@@ -17,9 +19,15 @@ impl Throbber {
             size: Size::new_empty(),
             paint,
             take_input,
+            anti_alias: None,
             rad: 0.0,
         }
     }
+
+    pub fn with_anti_alias(mut self, anti_alias: bool) -> Self {
+        self.anti_alias = Some(anti_alias);
+        self
+    }
 }
 
 impl Widget for Throbber {
@@ -46,6 +54,7 @@ impl Widget for Throbber {
     }
 
     fn draw(&mut self, _wrap: &mut WrapState, canvas: &mut Canvas) {
+        let paint = super::anti_aliased(&self.paint, self.anti_alias);
         let stroke_width = self.paint.stroke_width();
         let s = self.size.width.min(self.size.height) - stroke_width;
         canvas.draw_arc(
@@ -53,7 +62,7 @@ impl Widget for Throbber {
             self.rad,
             240.0,
             false,
-            &self.paint,
+            &paint,
         );
         self.rad += State::last_update_time_draw().as_secs_f32() * 720.0;
     }
@@ -0,0 +1,155 @@
+use std::time::Duration;
+
+use crate::prelude::*;
+use game::State;
+use skia::{gradient_shader, scalar, Canvas, Color, Color4f, Paint, RRect, Rect, Size, TileMode};
+
+use super::shapes;
+
+/// A shape of a [`Skeleton`], placed in fractions of the skeleton's size so
+/// the layout scales with it
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Bone {
+    /// A rounded rectangle, with the corner radius in pixels
+    Block(Rect, scalar),
+    /// The largest circle centered in the rectangle, for avatars and icons
+    Circle(Rect),
+}
+
+/// A placeholder for content that is still loading, drawing grey shapes in
+/// its layout with a highlight sweeping across them.
+///
+/// The shimmer is a single gradient over the whole skeleton, so all of its
+/// bones light up in turn as it passes. Colors default to the theme's
+/// surface color, with a highlight a little towards its text color.
+pub struct Skeleton {
+    pub layout_size: LayoutSize,
+    pub bones: Vec<Bone>,
+    pub base: Color4f,
+    pub highlight: Color4f,
+    /// How long the highlight takes to cross the skeleton
+    pub period: Duration,
+    /// Overrides the theme's anti-aliasing default when set
+    pub anti_alias: Option<bool>,
+    size: Size,
+}
+
+impl Skeleton {
+    /// A skeleton that is a single rounded block
+    pub fn new(size: LayoutSize) -> Self {
+        let (base, text) = State::with_theme(|t| (t.palette.surface, t.palette.text));
+        let mix = |a: scalar, b: scalar| a + (b - a) * 0.15;
+        Self {
+            layout_size: size,
+            bones: vec![Bone::Block(Rect::from_wh(1.0, 1.0), 4.0)],
+            base,
+            highlight: Color4f::new(
+                mix(base.r, text.r),
+                mix(base.g, text.g),
+                mix(base.b, text.b),
+                base.a,
+            ),
+            period: Duration::from_millis(1500),
+            anti_alias: None,
+            size: Size::new_empty(),
+        }
+    }
+
+    /// A skeleton of `count` lines of text, the last one shorter like the
+    /// end of a paragraph
+    pub fn lines(size: LayoutSize, count: usize) -> Self {
+        let count = count.max(1);
+        let pitch = 1.0 / count as scalar;
+        let bones = (0..count)
+            .map(|i| {
+                let width = if i + 1 == count && count > 1 {
+                    0.6
+                } else {
+                    1.0
+                };
+                let top = i as scalar * pitch;
+                Bone::Block(
+                    Rect::new(0.0, top + pitch * 0.2, width, top + pitch * 0.8),
+                    4.0,
+                )
+            })
+            .collect();
+        Self::new(size).with_bones(bones)
+    }
+
+    pub fn with_bones(mut self, bones: Vec<Bone>) -> Self {
+        self.bones = bones;
+        self
+    }
+
+    pub fn with_colors(mut self, base: Color4f, highlight: Color4f) -> Self {
+        self.base = base;
+        self.highlight = highlight;
+        self
+    }
+
+    pub fn with_period(mut self, period: Duration) -> Self {
+        self.period = period;
+        self
+    }
+
+    pub fn with_anti_alias(mut self, anti_alias: bool) -> Self {
+        self.anti_alias = Some(anti_alias);
+        self
+    }
+
+    fn place(&self, r: Rect) -> Rect {
+        let (w, h) = (self.size.width, self.size.height);
+        Rect::new(r.left * w, r.top * h, r.right * w, r.bottom * h)
+    }
+
+    fn shimmer(&self) -> Paint {
+        let mut paint = Paint::new(self.base, None);
+        let band = self.size.width.max(self.size.height) * 0.5;
+        let period = self.period.as_secs_f32().max(0.001);
+        let t = State::elapsed().as_secs_f32() % period / period;
+        // The band travels from fully off the left edge to fully off the
+        // right one
+        let x = -band + (self.size.width + band * 2.0) * t;
+        let (base, highlight) = (self.base.to_color(), self.highlight.to_color());
+        let colors: [Color; 3] = [base, highlight, base];
+        paint.set_shader(gradient_shader::linear(
+            ((x - band, 0.0), (x, band * 0.25)),
+            &colors[..],
+            Some(&[0.0, 0.5, 1.0][..]),
+            TileMode::Clamp,
+            None,
+            None,
+        ));
+        paint
+    }
+}
+
+impl Widget for Skeleton {
+    fn size(&mut self, _wrap: &mut WrapState) -> (LayoutSize, bool) {
+        (self.layout_size, false)
+    }
+
+    fn set_size(&mut self, _wrap: &mut WrapState, size: Size) {
+        self.size = size;
+    }
+
+    fn draw(&mut self, _wrap: &mut WrapState, canvas: &mut Canvas) {
+        let paint = self.shimmer();
+        let paint = shapes::anti_aliased(&paint, self.anti_alias);
+        for bone in &self.bones {
+            match *bone {
+                Bone::Block(rect, radius) => {
+                    let rect = self.place(rect);
+                    canvas.draw_rrect(RRect::new_rect_xy(rect, radius, radius), &paint);
+                }
+                Bone::Circle(rect) => {
+                    let rect = self.place(rect);
+                    let radius = rect.width().min(rect.height()) * 0.5;
+                    canvas.draw_circle(rect.center(), radius, &paint);
+                }
+            }
+        }
+        State::request_redraw();
+    }
+}
@@ -0,0 +1,239 @@
+use crate::prelude::*;
+use game::{InputEvent, Keycode, MouseButton, State};
+use skia::{scalar, Canvas, Contains, Paint, Point, RRect, Rect, Size};
+
+/// The values a [`Slider`] or [`Stepper`](super::Stepper) can take, and
+/// how far the keyboard moves them
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct StepRange {
+    pub min: scalar,
+    pub max: scalar,
+    /// The spacing values snap to, or 0 for any value
+    pub step: scalar,
+    /// How far Page Up and Page Down move the value
+    pub page: scalar,
+}
+
+impl StepRange {
+    /// A range stepped by `step`, with pages of a tenth of the range
+    pub fn new(min: scalar, max: scalar, step: scalar) -> Self {
+        Self {
+            min,
+            max,
+            step,
+            page: (max - min) / 10.0,
+        }
+    }
+
+    pub fn with_page(mut self, page: scalar) -> Self {
+        self.page = page;
+        self
+    }
+
+    /// Keep a value within the range, on a step
+    pub fn clamp(&self, value: scalar) -> scalar {
+        let value = if self.step > 0.0 {
+            self.min + ((value - self.min) / self.step).round() * self.step
+        } else {
+            value
+        };
+        value.max(self.min).min(self.max)
+    }
+
+    /// Where a value is in the range, from 0 to 1
+    pub fn fraction(&self, value: scalar) -> scalar {
+        if self.max > self.min {
+            ((value - self.min) / (self.max - self.min))
+                .max(0.0)
+                .min(1.0)
+        } else {
+            0.0
+        }
+    }
+
+    /// The value a key moves `value` to, if the key adjusts values.
+    /// Arrow keys move by a step, Page Up and Down by a page, and Home and
+    /// End to either end.
+    pub fn key(&self, value: scalar, key: Keycode) -> Option<scalar> {
+        let step = if self.step > 0.0 {
+            self.step
+        } else {
+            (self.max - self.min) / 100.0
+        };
+        let page = self.page.max(step);
+        Some(self.clamp(match key {
+            Keycode::Right | Keycode::Up => value + step,
+            Keycode::Left | Keycode::Down => value - step,
+            Keycode::PageUp => value + page,
+            Keycode::PageDown => value - page,
+            Keycode::Home => self.min,
+            Keycode::End => self.max,
+            _ => return None,
+        }))
+    }
+}
+
+/// A horizontal track with a thumb that can be dragged to pick a value, or
+/// moved with the keyboard once the slider has focus: see
+/// [`StepRange::key`] for the keys.
+///
+/// Clicking the slider gives it focus. The focus ring is drawn when focus
+/// was moved there with the keyboard, like for
+/// [`Focusable`](super::Focusable).
+pub struct Slider {
+    pub layout_size: LayoutSize,
+    pub range: StepRange,
+    /// The whole track, the theme's muted color by default
+    pub track: Option<Paint>,
+    /// The track up to the value and the thumb, the theme's primary color by
+    /// default
+    pub fill: Option<Paint>,
+    pub thumb_radius: scalar,
+    value: scalar,
+    on_change: Option<Box<dyn FnMut(scalar) + Send>>,
+    dragging: bool,
+    size: Size,
+}
+
+impl Slider {
+    pub fn new(size: LayoutSize, range: StepRange, value: scalar) -> Self {
+        Self {
+            layout_size: size,
+            range,
+            track: None,
+            fill: None,
+            thumb_radius: 8.0,
+            value: range.clamp(value),
+            on_change: None,
+            dragging: false,
+            size: Size::new_empty(),
+        }
+    }
+
+    pub fn with_paints(mut self, track: Paint, fill: Paint) -> Self {
+        self.track = Some(track);
+        self.fill = Some(fill);
+        self
+    }
+
+    pub fn with_thumb_radius(mut self, radius: scalar) -> Self {
+        self.thumb_radius = radius;
+        self
+    }
+
+    /// Called with the new value whenever the user changes it
+    pub fn with_on_change(mut self, f: impl FnMut(scalar) + Send + 'static) -> Self {
+        self.on_change = Some(Box::new(f));
+        self
+    }
+
+    pub fn value(&self) -> scalar {
+        self.value
+    }
+
+    /// Set the value without calling `on_change`
+    pub fn set_value(&mut self, value: scalar) {
+        self.value = self.range.clamp(value);
+    }
+
+    fn change(&mut self, value: scalar) {
+        let value = self.range.clamp(value);
+        if value != self.value {
+            self.value = value;
+            if let Some(f) = &mut self.on_change {
+                f(value);
+            }
+            State::request_redraw();
+        }
+    }
+
+    /// The track runs between the thumb's centers at either end
+    fn track_span(&self) -> (scalar, scalar) {
+        let r = self.thumb_radius;
+        (r, (self.size.width - r).max(r))
+    }
+
+    fn value_at(&self, x: scalar) -> scalar {
+        let (start, end) = self.track_span();
+        let t = if end > start {
+            (x - start) / (end - start)
+        } else {
+            0.0
+        };
+        self.range.min + (self.range.max - self.range.min) * t.max(0.0).min(1.0)
+    }
+}
+
+impl Widget for Slider {
+    fn input(&mut self, wrap: &mut WrapState, event: &InputEvent) -> bool {
+        match event {
+            InputEvent::MouseDown(MouseButton::Left, p, _)
+                if Rect::from_size(self.size).contains(*p) =>
+            {
+                State::set_focus(Some(wrap.id()));
+                self.dragging = true;
+                self.change(self.value_at(p.x));
+                true
+            }
+            InputEvent::MouseMove(p, _) if self.dragging => {
+                self.change(self.value_at(p.x));
+                true
+            }
+            InputEvent::MouseUp(MouseButton::Left, ..) if self.dragging => {
+                self.dragging = false;
+                true
+            }
+            InputEvent::KeyDown(key, _) if State::focused() == Some(wrap.id()) => {
+                match self.range.key(self.value, *key) {
+                    Some(value) => {
+                        self.change(value);
+                        true
+                    }
+                    None => false,
+                }
+            }
+            _ => false,
+        }
+    }
+
+    fn size(&mut self, _wrap: &mut WrapState) -> (LayoutSize, bool) {
+        let mut size = self.layout_size;
+        size.height.min = size.height.min.max(self.thumb_radius * 2.0);
+        (size, false)
+    }
+
+    fn set_size(&mut self, _wrap: &mut WrapState, size: Size) {
+        self.size = size;
+    }
+
+    fn draw(&mut self, wrap: &mut WrapState, canvas: &mut Canvas) {
+        let id = wrap.id();
+        let show_ring = State::with_mut(|x| {
+            x.focus.register(id, wrap.bounds());
+            x.focus.focused() == Some(id) && x.focus.is_visible()
+        });
+        let (track, fill) = State::with_theme(|t| {
+            (
+                self.track.clone().unwrap_or_else(|| t.muted()),
+                self.fill.clone().unwrap_or_else(|| t.primary()),
+            )
+        });
+        let (track, fill) = (track.anti_alias(), fill.anti_alias());
+
+        let (start, end) = self.track_span();
+        let y = self.size.height / 2.0;
+        let thickness = (self.thumb_radius / 2.0).max(1.0);
+        let x = start + (end - start) * self.range.fraction(self.value);
+        let bar = |from: scalar, to: scalar| {
+            let rect = Rect::new(from, y - thickness / 2.0, to, y + thickness / 2.0);
+            RRect::new_rect_xy(rect, thickness / 2.0, thickness / 2.0)
+        };
+        canvas.draw_rrect(bar(start, end), &track);
+        canvas.draw_rrect(bar(start, x), &fill);
+        canvas.draw_circle(Point::new(x, y), self.thumb_radius, &fill);
+        if show_ring {
+            let ring = fill.clone().stroke_style().with_stroke_width(2.0);
+            canvas.draw_circle(Point::new(x, y), self.thumb_radius + 3.0, &ring);
+        }
+    }
+}
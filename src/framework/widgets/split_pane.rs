@@ -0,0 +1,220 @@
+use crate::prelude::*;
+use game::{InputEvent, MouseButton, State, SystemCursor, ID};
+use skia::{scalar, Canvas, Contains, Matrix, Paint, Point, Rect, Size, Vector};
+
+use super::Orientation;
+
+/// Two panes separated by a gutter that can be dragged to share the space
+/// between them differently, for editor-style layouts.
+///
+/// With [`Orientation::Horizontal`] the panes sit side by side, and with
+/// [`Orientation::Vertical`] one above the other. The share of the space
+/// given to the first pane is kept in the [`WrapState`] as its
+/// [split ratio](WrapState::split_ratio), so it can be read and restored
+/// from outside with [`Wrap::get`] to persist it. Dragging never shrinks a
+/// pane below its minimum size.
+pub struct SplitPane<A: Widget, B: Widget> {
+    pub first: Wrap<A>,
+    pub second: Wrap<B>,
+    pub orientation: Orientation,
+    pub layout_size: LayoutSize,
+    /// The thickness of the gutter between the panes
+    pub gutter: scalar,
+    /// How the gutter is drawn, the theme's muted color by default
+    pub paint: Option<Paint>,
+    /// Where the gutter was grabbed, from its start, while it is dragged
+    grab: Option<scalar>,
+    first_length: scalar,
+    size: Size,
+}
+
+impl<A: Widget, B: Widget> SplitPane<A, B> {
+    pub fn new(
+        first: impl Into<Wrap<A>>,
+        second: impl Into<Wrap<B>>,
+        orientation: Orientation,
+        size: LayoutSize,
+    ) -> Self {
+        Self {
+            first: first.into(),
+            second: second.into(),
+            orientation,
+            layout_size: size,
+            gutter: 6.0,
+            paint: None,
+            grab: None,
+            first_length: 0.0,
+            size: Size::new_empty(),
+        }
+    }
+
+    pub fn with_gutter(mut self, gutter: scalar) -> Self {
+        self.gutter = gutter;
+        self
+    }
+
+    pub fn with_paint(mut self, paint: Paint) -> Self {
+        self.paint = Some(paint);
+        self
+    }
+
+    fn main(&self, size: Size) -> scalar {
+        match self.orientation {
+            Orientation::Horizontal => size.width,
+            Orientation::Vertical => size.height,
+        }
+    }
+
+    fn main_min(&self, size: LayoutSize) -> scalar {
+        match self.orientation {
+            Orientation::Horizontal => size.width.min,
+            Orientation::Vertical => size.height.min,
+        }
+    }
+
+    fn along(&self, main: scalar, cross: scalar) -> Vector {
+        match self.orientation {
+            Orientation::Horizontal => Vector::new(main, cross),
+            Orientation::Vertical => Vector::new(cross, main),
+        }
+    }
+
+    fn pane_size(&self, main: scalar) -> Size {
+        let cross = match self.orientation {
+            Orientation::Horizontal => self.size.height,
+            Orientation::Vertical => self.size.width,
+        };
+        let v = self.along(main, cross);
+        Size::new(v.x, v.y)
+    }
+
+    /// The space to share between the panes
+    fn available(&self) -> scalar {
+        (self.main(self.size) - self.gutter).max(0.0)
+    }
+
+    /// Keep a length for the first pane within both panes'
+    /// minimum sizes where there is room for that
+    fn clamp(&self, length: scalar) -> scalar {
+        let available = self.available();
+        let first_min = self.main_min(self.first.state.layout_size());
+        let second_min = self.main_min(self.second.state.layout_size());
+        length
+            .min(available - second_min)
+            .max(first_min)
+            .min(available)
+            .max(0.0)
+    }
+
+    fn second_offset(&self) -> Vector {
+        self.along(self.first_length + self.gutter, 0.0)
+    }
+
+    fn gutter_rect(&self) -> Rect {
+        let origin = self.along(self.first_length, 0.0);
+        let size = self.pane_size(self.gutter);
+        Rect::from_point_and_size(Point::new(origin.x, origin.y), size)
+    }
+
+    fn layout(&mut self, wrap: &WrapState) {
+        self.first_length = self.clamp(self.available() * wrap.split_ratio());
+        let second_length = (self.available() - self.first_length).max(0.0);
+        self.first.set_size(self.pane_size(self.first_length));
+        self.second.set_size(self.pane_size(second_length));
+    }
+
+    fn pointer(&self, p: Point) -> scalar {
+        match self.orientation {
+            Orientation::Horizontal => p.x,
+            Orientation::Vertical => p.y,
+        }
+    }
+}
+
+impl<A: Widget, B: Widget> Widget for SplitPane<A, B> {
+    fn update(&mut self, _wrap: &mut WrapState) {
+        self.first.update();
+        self.second.update();
+    }
+
+    fn input(&mut self, wrap: &mut WrapState, event: &InputEvent) -> bool {
+        match event {
+            InputEvent::MouseDown(MouseButton::Left, p, _) if self.gutter_rect().contains(*p) => {
+                self.grab = Some(self.pointer(*p) - self.first_length);
+                return true;
+            }
+            InputEvent::MouseMove(p, _) => {
+                if self.grab.is_some() || self.gutter_rect().contains(*p) {
+                    State::set_hover_cursor(match self.orientation {
+                        Orientation::Horizontal => SystemCursor::SizeWE,
+                        Orientation::Vertical => SystemCursor::SizeNS,
+                    });
+                }
+                if let Some(grab) = self.grab {
+                    let length = self.clamp(self.pointer(*p) - grab);
+                    let available = self.available();
+                    if available > 0.0 {
+                        wrap.set_split_ratio(length / available);
+                    }
+                    self.layout(wrap);
+                    State::request_redraw();
+                    return true;
+                }
+            }
+            InputEvent::MouseUp(MouseButton::Left, ..) if self.grab.is_some() => {
+                self.grab = None;
+                return true;
+            }
+            _ => {}
+        }
+        if self.first.input(event) {
+            return true;
+        }
+        event
+            .reverse_map_position(Matrix::translate(self.second_offset()))
+            .map_or(false, |event| self.second.input(&event))
+    }
+
+    fn size(&mut self, _wrap: &mut WrapState) -> (LayoutSize, bool) {
+        let (first, a) = self.first.size();
+        let (second, b) = self.second.size();
+        let mut size = self.layout_size;
+        let (main, cross) = match self.orientation {
+            Orientation::Horizontal => (&mut size.width, &mut size.height),
+            Orientation::Vertical => (&mut size.height, &mut size.width),
+        };
+        let (first_main, first_cross, second_main, second_cross) = match self.orientation {
+            Orientation::Horizontal => (first.width, first.height, second.width, second.height),
+            Orientation::Vertical => (first.height, first.width, second.height, second.width),
+        };
+        main.min = main.min.max(first_main.min + self.gutter + second_main.min);
+        cross.min = cross.min.max(first_cross.min).max(second_cross.min);
+        (size, a || b)
+    }
+
+    fn set_size(&mut self, wrap: &mut WrapState, size: Size) {
+        self.size = size;
+        self.layout(wrap);
+    }
+
+    fn draw(&mut self, _wrap: &mut WrapState, canvas: &mut Canvas) {
+        self.first.draw(canvas);
+        let paint = self
+            .paint
+            .clone()
+            .unwrap_or_else(|| State::with_theme(|t| t.muted()));
+        canvas.draw_rect(self.gutter_rect(), &paint);
+        canvas.save();
+        canvas.translate(self.second_offset());
+        self.second.draw(canvas);
+        canvas.restore();
+    }
+
+    fn get(&mut self, _wrap: &mut WrapState, id: ID) -> Option<(&mut dyn Widget, &mut WrapState)> {
+        let x = self.first.get(id);
+        if x.is_some() {
+            return x;
+        }
+        self.second.get(id)
+    }
+}
@@ -0,0 +1,273 @@
+use std::time::Duration;
+
+use crate::prelude::*;
+use game::{InputEvent, Keycode, MouseButton, State};
+use skia::{scalar, Canvas, Contains, Font as SkFont, Paint, PaintStyle, Point, RRect, Rect, Size};
+
+use super::{Font, FontStyle, Repeater, StepRange};
+
+/// A number between a minus and a plus button, for picking values precisely.
+///
+/// Holding a button keeps stepping, like key repeat. Once the stepper has
+/// focus, the same keys as for a [`Slider`](super::Slider) adjust the value,
+/// and typing digits replaces it: Enter confirms the typed number, clamped
+/// to the range, and Escape goes back to the old value.
+pub struct Stepper {
+    pub layout_size: LayoutSize,
+    pub range: StepRange,
+    /// Digits shown after the decimal point
+    pub decimals: usize,
+    pub font: SkFont,
+    pub text_paint: Paint,
+    /// The buttons and the outline of the field, the theme's primary color
+    /// by default
+    pub paint: Option<Paint>,
+    value: scalar,
+    /// The number being typed, while there is one
+    typed: Option<String>,
+    on_change: Option<Box<dyn FnMut(scalar) + Send>>,
+    repeater: Repeater,
+    /// -1 while the minus button is held, 1 for the plus button
+    held: i32,
+    size: Size,
+}
+
+impl Stepper {
+    pub fn new(size: LayoutSize, range: StepRange, value: scalar) -> Self {
+        let (text_paint, font_size) = State::with_theme(|t| (t.text(), t.font_sizes.regular));
+        let mut font = Font::Default.resolve(&FontStyle::Regular);
+        font.set_size(font_size);
+        let decimals = if range.step.fract() == 0.0 { 0 } else { 2 };
+        Self {
+            layout_size: size,
+            range,
+            decimals,
+            font,
+            text_paint,
+            paint: None,
+            value: range.clamp(value),
+            typed: None,
+            on_change: None,
+            repeater: Repeater::new(Duration::from_millis(400), Duration::from_millis(60)),
+            held: 0,
+            size: Size::new_empty(),
+        }
+    }
+
+    pub fn with_decimals(mut self, decimals: usize) -> Self {
+        self.decimals = decimals;
+        self
+    }
+
+    pub fn with_paint(mut self, paint: Paint) -> Self {
+        self.paint = Some(paint);
+        self
+    }
+
+    /// Called with the new value whenever the user changes it
+    pub fn with_on_change(mut self, f: impl FnMut(scalar) + Send + 'static) -> Self {
+        self.on_change = Some(Box::new(f));
+        self
+    }
+
+    pub fn value(&self) -> scalar {
+        self.value
+    }
+
+    /// Set the value without calling `on_change`
+    pub fn set_value(&mut self, value: scalar) {
+        self.value = self.range.clamp(value);
+        self.typed = None;
+    }
+
+    fn change(&mut self, value: scalar) {
+        let value = self.range.clamp(value);
+        if value != self.value {
+            self.value = value;
+            if let Some(f) = &mut self.on_change {
+                f(value);
+            }
+        }
+        State::request_redraw();
+    }
+
+    fn step(&mut self, direction: i32) {
+        let key = if direction < 0 {
+            Keycode::Down
+        } else {
+            Keycode::Up
+        };
+        if let Some(value) = self.range.key(self.value, key) {
+            self.change(value);
+        }
+    }
+
+    /// The buttons are squares at either end
+    fn button_width(&self) -> scalar {
+        self.size.height.min(self.size.width / 3.0)
+    }
+
+    fn minus_rect(&self) -> Rect {
+        Rect::from_wh(self.button_width(), self.size.height)
+    }
+
+    fn plus_rect(&self) -> Rect {
+        let w = self.button_width();
+        Rect::from_xywh(self.size.width - w, 0.0, w, self.size.height)
+    }
+
+    fn label(&self) -> String {
+        match &self.typed {
+            Some(typed) => typed.clone(),
+            None => format!("{:.*}", self.decimals, self.value),
+        }
+    }
+
+    /// The character a key types into the number, if any
+    fn typed_char(key: Keycode) -> Option<char> {
+        Some(match key {
+            Keycode::Num0 | Keycode::Kp0 => '0',
+            Keycode::Num1 | Keycode::Kp1 => '1',
+            Keycode::Num2 | Keycode::Kp2 => '2',
+            Keycode::Num3 | Keycode::Kp3 => '3',
+            Keycode::Num4 | Keycode::Kp4 => '4',
+            Keycode::Num5 | Keycode::Kp5 => '5',
+            Keycode::Num6 | Keycode::Kp6 => '6',
+            Keycode::Num7 | Keycode::Kp7 => '7',
+            Keycode::Num8 | Keycode::Kp8 => '8',
+            Keycode::Num9 | Keycode::Kp9 => '9',
+            Keycode::Minus | Keycode::KpMinus => '-',
+            Keycode::Period | Keycode::KpPeriod => '.',
+            _ => return None,
+        })
+    }
+
+    /// Handle a key while focused, returning whether it was used
+    fn key(&mut self, key: Keycode) -> bool {
+        if let Some(c) = Self::typed_char(key) {
+            self.typed.get_or_insert_with(String::new).push(c);
+            State::request_redraw();
+            return true;
+        }
+        match (key, &mut self.typed) {
+            (Keycode::Backspace, Some(typed)) => {
+                typed.pop();
+            }
+            (Keycode::Return, Some(_)) | (Keycode::KpEnter, Some(_)) => {
+                let typed = self.typed.take().unwrap_or_default();
+                // Something that isn't a number leaves the value as it was
+                if let Ok(value) = typed.parse::<scalar>() {
+                    self.change(value);
+                }
+            }
+            (Keycode::Escape, Some(_)) => self.typed = None,
+            (key, _) => {
+                return match self.range.key(self.value, key) {
+                    Some(value) => {
+                        self.typed = None;
+                        self.change(value);
+                        true
+                    }
+                    None => false,
+                };
+            }
+        }
+        State::request_redraw();
+        true
+    }
+}
+
+impl Widget for Stepper {
+    fn update(&mut self, _wrap: &mut WrapState) {
+        for _ in 0..self.repeater.poll() {
+            self.step(self.held);
+        }
+    }
+
+    fn input(&mut self, wrap: &mut WrapState, event: &InputEvent) -> bool {
+        match event {
+            InputEvent::MouseDown(MouseButton::Left, p, _)
+                if Rect::from_size(self.size).contains(*p) =>
+            {
+                State::set_focus(Some(wrap.id()));
+                self.held = if self.minus_rect().contains(*p) {
+                    -1
+                } else if self.plus_rect().contains(*p) {
+                    1
+                } else {
+                    0
+                };
+                if self.held != 0 {
+                    self.typed = None;
+                    self.repeater.press();
+                }
+                true
+            }
+            InputEvent::MouseUp(MouseButton::Left, ..) if self.repeater.is_pressed() => {
+                self.repeater.release();
+                self.held = 0;
+                true
+            }
+            InputEvent::KeyDown(key, _) if State::focused() == Some(wrap.id()) => self.key(*key),
+            _ => false,
+        }
+    }
+
+    fn size(&mut self, _wrap: &mut WrapState) -> (LayoutSize, bool) {
+        let mut size = self.layout_size;
+        size.height.min = size.height.min.max(self.font.spacing());
+        (size, false)
+    }
+
+    fn set_size(&mut self, _wrap: &mut WrapState, size: Size) {
+        self.size = size;
+    }
+
+    fn draw(&mut self, wrap: &mut WrapState, canvas: &mut Canvas) {
+        let id = wrap.id();
+        let focused = State::with_mut(|x| {
+            x.focus.register(id, wrap.bounds());
+            x.focus.focused() == Some(id)
+        });
+        let paint = self
+            .paint
+            .clone()
+            .unwrap_or_else(|| State::with_theme(|t| t.primary()))
+            .anti_alias();
+        let radius = 4.0;
+        let (minus, plus) = (self.minus_rect(), self.plus_rect());
+        canvas.draw_rrect(RRect::new_rect_xy(minus, radius, radius), &paint);
+        canvas.draw_rrect(RRect::new_rect_xy(plus, radius, radius), &paint);
+
+        // The signs are drawn as lines, so they don't depend on the font
+        let sign = self
+            .text_paint
+            .clone()
+            .stroke_style()
+            .with_stroke_width(2.0)
+            .anti_alias();
+        let arm = self.button_width() * 0.2;
+        let (mc, pc) = (minus.center(), plus.center());
+        canvas.draw_line((mc.x - arm, mc.y), (mc.x + arm, mc.y), &sign);
+        canvas.draw_line((pc.x - arm, pc.y), (pc.x + arm, pc.y), &sign);
+        canvas.draw_line((pc.x, pc.y - arm), (pc.x, pc.y + arm), &sign);
+
+        let field = Rect::new(minus.right, 0.0, plus.left, self.size.height).with_inset((2.0, 1.0));
+        if focused {
+            let mut outline = paint.clone();
+            outline.set_style(PaintStyle::Stroke);
+            outline.set_stroke_width(if self.typed.is_some() { 2.0 } else { 1.0 });
+            canvas.draw_rrect(RRect::new_rect_xy(field, radius, radius), &outline);
+        }
+        let label = self.label();
+        let (width, bounds) = self.font.measure_str(&label, Some(&self.text_paint));
+        let origin = Point::new(
+            field.center_x() - width / 2.0,
+            field.center_y() - bounds.height() / 2.0 - bounds.top,
+        );
+        canvas.save();
+        canvas.clip_rect(field, None, None);
+        canvas.draw_str(&label, origin, &self.font, &self.text_paint);
+        canvas.restore();
+    }
+}
@@ -0,0 +1,236 @@
+use std::fs;
+use std::io::Error as IoError;
+use std::path::Path as FsPath;
+
+use usvg::NodeExt;
+
+use crate::prelude::*;
+use skia::{
+    paint::{Cap, Join},
+    scalar, Canvas, Matrix, Paint, PaintStyle, Path, PathFillType, Picture, PictureRecorder, Rect,
+    Size,
+};
+
+/// A vector image parsed from an SVG document, drawn scaled to fill its
+/// bounds.
+///
+/// Only paths filled and stroked with solid colors are drawn, which covers
+/// most icons. Gradients, patterns, text and embedded images are skipped.
+/// The layout size defaults to the size given by the document.
+pub struct Svg {
+    picture: Picture,
+    view_box: Rect,
+    pub layout_size: LayoutSize,
+    /// Whether to keep the aspect ratio of the view box, centering the image
+    /// in its bounds
+    pub keep_aspect: bool,
+    size: Size,
+}
+
+impl Svg {
+    pub fn open(path: impl AsRef<FsPath>) -> Result<Self, SvgError> {
+        Self::from_data(&fs::read(path)?)
+    }
+
+    pub fn from_data(data: &[u8]) -> Result<Self, SvgError> {
+        let tree = usvg::Tree::from_data(data, &usvg::Options::default())?;
+        let svg = tree.svg_node();
+        let r = svg.view_box.rect;
+        let view_box = Rect::from_xywh(
+            r.x() as scalar,
+            r.y() as scalar,
+            r.width() as scalar,
+            r.height() as scalar,
+        );
+
+        let mut recorder = PictureRecorder::new();
+        let canvas = recorder.begin_recording(view_box, None);
+        for node in tree.root().descendants() {
+            if let usvg::NodeKind::Path(ref path) = *node.borrow() {
+                Self::draw_path(canvas, path, node.abs_transform());
+            }
+        }
+        let picture = recorder
+            .finish_recording_as_picture(None)
+            .ok_or(SvgError::Empty)?;
+
+        Ok(Self {
+            picture,
+            view_box,
+            layout_size: LayoutSize::min(svg.size.width() as scalar, svg.size.height() as scalar),
+            keep_aspect: true,
+            size: Size::new_empty(),
+        })
+    }
+
+    pub fn with_layout_size(mut self, size: LayoutSize) -> Self {
+        self.layout_size = size;
+        self
+    }
+
+    pub fn with_keep_aspect(mut self, keep_aspect: bool) -> Self {
+        self.keep_aspect = keep_aspect;
+        self
+    }
+
+    /// The intrinsic size of the document, from its view box
+    pub fn view_box(&self) -> Rect {
+        self.view_box
+    }
+
+    fn draw_path(canvas: &mut Canvas, path: &usvg::Path, transform: usvg::Transform) {
+        if path.visibility != usvg::Visibility::Visible {
+            return;
+        }
+        let mut sk_path = Path::new();
+        for segment in path.data.iter() {
+            match *segment {
+                usvg::PathSegment::MoveTo { x, y } => {
+                    sk_path.move_to((x as scalar, y as scalar));
+                }
+                usvg::PathSegment::LineTo { x, y } => {
+                    sk_path.line_to((x as scalar, y as scalar));
+                }
+                usvg::PathSegment::CurveTo {
+                    x1,
+                    y1,
+                    x2,
+                    y2,
+                    x,
+                    y,
+                } => {
+                    sk_path.cubic_to(
+                        (x1 as scalar, y1 as scalar),
+                        (x2 as scalar, y2 as scalar),
+                        (x as scalar, y as scalar),
+                    );
+                }
+                usvg::PathSegment::ClosePath => {
+                    sk_path.close();
+                }
+            }
+        }
+
+        let t = transform;
+        let matrix = Matrix::new_all(
+            t.a as scalar,
+            t.c as scalar,
+            t.e as scalar,
+            t.b as scalar,
+            t.d as scalar,
+            t.f as scalar,
+            0.0,
+            0.0,
+            1.0,
+        );
+        canvas.save();
+        canvas.concat(&matrix);
+
+        if let Some(fill) = &path.fill {
+            if let Some(mut paint) = Self::paint(&fill.paint, fill.opacity.value()) {
+                sk_path.set_fill_type(match fill.rule {
+                    usvg::FillRule::NonZero => PathFillType::Winding,
+                    usvg::FillRule::EvenOdd => PathFillType::EvenOdd,
+                });
+                paint.set_style(PaintStyle::Fill);
+                canvas.draw_path(&sk_path, &paint);
+            }
+        }
+        if let Some(stroke) = &path.stroke {
+            if let Some(mut paint) = Self::paint(&stroke.paint, stroke.opacity.value()) {
+                paint.set_style(PaintStyle::Stroke);
+                paint.set_stroke_width(stroke.width.value() as scalar);
+                paint.set_stroke_miter(stroke.miterlimit.value() as scalar);
+                paint.set_stroke_cap(match stroke.linecap {
+                    usvg::LineCap::Butt => Cap::Butt,
+                    usvg::LineCap::Round => Cap::Round,
+                    usvg::LineCap::Square => Cap::Square,
+                });
+                paint.set_stroke_join(match stroke.linejoin {
+                    usvg::LineJoin::Miter => Join::Miter,
+                    usvg::LineJoin::Round => Join::Round,
+                    usvg::LineJoin::Bevel => Join::Bevel,
+                });
+                canvas.draw_path(&sk_path, &paint);
+            }
+        }
+
+        canvas.restore();
+    }
+
+    fn paint(paint: &usvg::Paint, opacity: f64) -> Option<Paint> {
+        match paint {
+            usvg::Paint::Color(c) => {
+                let mut paint = Paint::new_color4f(
+                    c.red as f32 / 255.0,
+                    c.green as f32 / 255.0,
+                    c.blue as f32 / 255.0,
+                    opacity as f32,
+                );
+                paint.set_anti_alias(true);
+                Some(paint)
+            }
+            _ => None,
+        }
+    }
+
+    fn fit_matrix(&self) -> Matrix {
+        let vb = self.view_box;
+        if vb.is_empty() {
+            return Matrix::default();
+        }
+        let mut sx = self.size.width / vb.width();
+        let mut sy = self.size.height / vb.height();
+        let mut offset = (0.0, 0.0);
+        if self.keep_aspect {
+            let s = sx.min(sy);
+            offset = (
+                (self.size.width - vb.width() * s) * 0.5,
+                (self.size.height - vb.height() * s) * 0.5,
+            );
+            sx = s;
+            sy = s;
+        }
+        Matrix::concat(
+            &Matrix::translate(offset),
+            &Matrix::concat(
+                &Matrix::scale((sx, sy)),
+                &Matrix::translate((-vb.left, -vb.top)),
+            ),
+        )
+    }
+}
+
+impl Widget for Svg {
+    fn size(&mut self, _wrap: &mut WrapState) -> (LayoutSize, bool) {
+        (self.layout_size, false)
+    }
+
+    fn set_size(&mut self, _wrap: &mut WrapState, size: Size) {
+        self.size = size;
+    }
+
+    fn draw(&mut self, _wrap: &mut WrapState, canvas: &mut Canvas) {
+        canvas.draw_picture(&self.picture, Some(&self.fit_matrix()), None);
+    }
+}
+
+#[derive(Debug)]
+pub enum SvgError {
+    IoError(IoError),
+    ParseError(usvg::Error),
+    /// The document could not be recorded into a picture
+    Empty,
+}
+
+impl From<IoError> for SvgError {
+    fn from(err: IoError) -> Self {
+        Self::IoError(err)
+    }
+}
+
+impl From<usvg::Error> for SvgError {
+    fn from(err: usvg::Error) -> Self {
+        Self::ParseError(err)
+    }
+}
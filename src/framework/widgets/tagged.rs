@@ -0,0 +1,113 @@
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use crate::prelude::*;
+use game::{InputEvent, State, ID};
+use skia::{scalar, Canvas, Size};
+
+/// Registers its child under an [`ID`], so that game logic can reach it with
+/// [`State::with_widget`] without holding on to it.
+///
+/// The child is shared behind a lock, which means [`Wrap::get`] can't hand
+/// out references into it; lookups through the tree stop here.
+pub struct Tagged<T: Widget> {
+    inner: Wrap<Shared<T>>,
+    id: ID,
+}
+
+impl<T: Widget> Tagged<T> {
+    /// Register the child under a new ID
+    pub fn new(inner: T) -> Self {
+        Self::with_id(ID::next(), inner)
+    }
+
+    /// Register the child under an existing ID, replacing whatever was
+    /// registered under it before
+    pub fn with_id(id: ID, inner: T) -> Self {
+        let inner = Arc::new(Mutex::new(inner));
+        State::with_mut(|x| x.registry.register(id, &inner));
+        Self {
+            inner: Wrap::new(Shared(inner)),
+            id,
+        }
+    }
+
+    pub fn id(&self) -> ID {
+        self.id
+    }
+}
+
+impl<T: Widget> Widget for Tagged<T> {
+    forward_widget!(
+        inner;
+        update,
+        input,
+        size,
+        set_size,
+        height_for_width,
+        min_intrinsic_width,
+        max_intrinsic_width,
+        min_intrinsic_height,
+        max_intrinsic_height,
+        draw,
+    );
+}
+
+/// The registered child, which passes everything on through the lock as
+/// `Box<dyn Widget>` does through the box
+struct Shared<T: Widget>(Arc<Mutex<T>>);
+
+impl<T: Widget> Shared<T> {
+    fn lock(&self) -> MutexGuard<T> {
+        self.0.lock().unwrap()
+    }
+}
+
+impl<T: Widget> Widget for Shared<T> {
+    fn on_mount(&mut self, wrap: &mut WrapState) {
+        self.lock().on_mount(wrap);
+    }
+
+    fn on_unmount(&mut self, wrap: &mut WrapState) {
+        self.lock().on_unmount(wrap);
+    }
+
+    fn update(&mut self, wrap: &mut WrapState) {
+        self.lock().update(wrap);
+    }
+
+    fn input(&mut self, wrap: &mut WrapState, event: &InputEvent) -> bool {
+        self.lock().input(wrap, event)
+    }
+
+    fn size(&mut self, wrap: &mut WrapState) -> (LayoutSize, bool) {
+        self.lock().size(wrap)
+    }
+
+    fn set_size(&mut self, wrap: &mut WrapState, size: Size) {
+        self.lock().set_size(wrap, size);
+    }
+
+    fn height_for_width(&mut self, wrap: &mut WrapState, width: scalar) -> Option<scalar> {
+        self.lock().height_for_width(wrap, width)
+    }
+
+    fn min_intrinsic_width(&mut self, wrap: &mut WrapState) -> scalar {
+        self.lock().min_intrinsic_width(wrap)
+    }
+
+    fn max_intrinsic_width(&mut self, wrap: &mut WrapState) -> scalar {
+        self.lock().max_intrinsic_width(wrap)
+    }
+
+    fn min_intrinsic_height(&mut self, wrap: &mut WrapState) -> scalar {
+        self.lock().min_intrinsic_height(wrap)
+    }
+
+    fn max_intrinsic_height(&mut self, wrap: &mut WrapState) -> scalar {
+        self.lock().max_intrinsic_height(wrap)
+    }
+
+    fn draw(&mut self, wrap: &mut WrapState, canvas: &mut Canvas) {
+        self.lock().draw(wrap, canvas);
+    }
+}
@@ -1,7 +1,11 @@
+use std::ops::Range;
+
+use crate::framework::shortcuts::KeyCombo;
 use crate::prelude::*;
-use game::{InputEvent, State};
+use game::{FontRendering, InputEvent, Keycode, MouseButton, State};
 use skia::{
-    shaper::TextBlobBuilderRunHandler, Canvas, Font as SkFont, Paint, Shaper, Size, TextBlob,
+    scalar, shaper::TextBlobBuilderRunHandler, Canvas, ClipOp, Contains, Font as SkFont, Paint,
+    Point, Rect, Shaper, Size, TextBlob,
 };
 
 #[derive(Debug, Copy, Clone, Hash, Eq, PartialEq)]
@@ -10,8 +14,13 @@ pub enum Font {
 }
 
 impl Font {
+    /// The Skia font for a style, rendered with [`State::font_rendering`]
     pub fn resolve(&self, style: &FontStyle) -> SkFont {
-        State::with(|x| x.font_set.get(self, style))
+        State::with(|x| {
+            let mut font = x.font_set.get(self, style);
+            x.font_rendering.apply(&mut font);
+            font
+        })
     }
 }
 
@@ -23,13 +32,42 @@ pub enum FontStyle {
     BoldItalic,
 }
 
+#[derive(Debug, Copy, Clone, Hash, Eq, PartialEq)]
+pub enum TextAlign {
+    Left,
+    Center,
+    Right,
+    /// Stretches the gaps between words so that every line but the last one
+    /// of each paragraph fills the block width.
+    Justify,
+}
+
 pub struct Text {
     pub layout_size: LayoutSize,
     pub font: SkFont,
     pub paint: Paint,
+    pub align: TextAlign,
+    pub wrap: bool,
     size: Size,
     text: String,
-    blob: Option<TextBlob>,
+    text_width: scalar,
+    text_height: scalar,
+    blobs: Vec<TextBlob>,
+    /// Where each word of the text ended up, for hiding what isn't revealed
+    words: Vec<PlacedWord>,
+    /// Only the text before this byte offset is drawn
+    revealed: Option<usize>,
+    /// What the blobs were shaped for, to only shape again when it changes
+    shaped: Option<ShapeKey>,
+    /// Lets the text be selected with the mouse and copied, drawing the
+    /// selection with this paint
+    pub selection_paint: Option<Paint>,
+    /// Where each shaped line is, for hit testing the selection
+    placed: Vec<PlacedLine>,
+    /// The character the selection started at and the one it extends to,
+    /// counted over the lines as shown, joined by line breaks
+    selection: Option<(usize, usize)>,
+    selecting: bool,
 }
 
 impl Text {
@@ -46,40 +84,498 @@ impl Text {
             layout_size: size,
             font,
             paint,
+            align: TextAlign::Left,
+            wrap: false,
             size: Size::new_empty(),
             text: text.to_owned(),
-            blob: None,
+            text_width: 0.0,
+            text_height: 0.0,
+            blobs: Vec::new(),
+            words: Vec::new(),
+            revealed: None,
+            shaped: None,
+            selection_paint: None,
+            placed: Vec::new(),
+            selection: None,
+            selecting: false,
+        }
+    }
+
+    pub fn with_align(mut self, align: TextAlign) -> Self {
+        self.align = align;
+        self
+    }
+
+    /// Breaks lines on word boundaries to fit the allocated width.
+    /// Words longer than a whole line are broken between characters.
+    pub fn with_wrap(mut self, wrap: bool) -> Self {
+        self.wrap = wrap;
+        self
+    }
+
+    /// Render this text differently from the default set with
+    /// [`Builder::font_rendering`](crate::game::Builder::font_rendering)
+    pub fn with_rendering(mut self, rendering: FontRendering) -> Self {
+        rendering.apply(&mut self.font);
+        self.reshape();
+        self
+    }
+
+    /// Let the text be selected by dragging the mouse across it and copied
+    /// with Ctrl+C, or Command+C on macOS, drawing the selection behind the
+    /// text with `paint`. What gets copied is the text as shown, with lines
+    /// broken by wrapping joined back together.
+    pub fn with_selectable(mut self, paint: Paint) -> Self {
+        self.selection_paint = Some(paint);
+        self
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// The selected text, if there is any
+    pub fn selected_text(&self) -> Option<String> {
+        let range = self.selection_range()?;
+        let shown: String = self
+            .placed
+            .iter()
+            .flat_map(|l| {
+                let end = if l.last { '\n' } else { ' ' };
+                l.text.chars().chain(std::iter::once(end))
+            })
+            .skip(range.start)
+            .take(range.len())
+            .collect();
+        Some(shown)
+    }
+
+    pub fn clear_selection(&mut self) {
+        self.selection = None;
+        self.selecting = false;
+    }
+
+    fn selection_range(&self) -> Option<Range<usize>> {
+        match self.selection {
+            Some((a, b)) if a != b => Some(a.min(b)..a.max(b)),
+            _ => None,
+        }
+    }
+
+    /// The character boundary closest to a point
+    fn hit(&self, p: Point) -> usize {
+        let row = (p.y / self.font.spacing()).floor().max(0.0) as usize;
+        let line = match self
+            .placed
+            .get(row.min(self.placed.len().saturating_sub(1)))
+        {
+            Some(line) => line,
+            None => return 0,
+        };
+        let distance = |x: scalar| (x - p.x).abs();
+        let column = (0..line.carets.len())
+            .min_by(|&a, &b| {
+                distance(line.carets[a])
+                    .partial_cmp(&distance(line.carets[b]))
+                    .unwrap()
+            })
+            .unwrap_or(0);
+        line.start + column
+    }
+
+    /// Replace the text, shaping it again for the current size
+    pub fn set_text(&mut self, text: impl AsRef<str>) {
+        let text = text.as_ref();
+        if self.text != text {
+            self.text = text.to_owned();
+            self.shaped = None;
+            self.reshape();
+        }
+    }
+
+    /// Only draw the text up to the byte offset `end`, which has to be on a
+    /// character boundary, or all of it with `None`. The text is laid out in
+    /// full either way, so revealing more of it never moves what is already
+    /// shown.
+    pub fn set_revealed(&mut self, end: Option<usize>) {
+        if self.revealed != end {
+            self.revealed = end;
+            State::request_redraw();
+        }
+    }
+
+    pub fn revealed(&self) -> Option<usize> {
+        self.revealed
+    }
+
+    fn measure(&self, text: &str) -> scalar {
+        self.font.measure_str(text, Some(&self.paint)).0
+    }
+
+    /// The width of the longest paragraph without any wrapping
+    fn max_line_width(&self) -> scalar {
+        self.text
+            .split('\n')
+            .map(|l| self.measure(l))
+            .fold(0.0, scalar::max)
+    }
+
+    fn shape_key(&self) -> ShapeKey {
+        ShapeKey {
+            font: self.font.clone(),
+            align: self.align,
+            wrap: self.wrap,
+            width: self.size.width,
+            selectable: self.selection_paint.is_some(),
+        }
+    }
+
+    /// Shape the text if anything it was shaped for changed since, including
+    /// the public fields being changed directly
+    fn reshape(&mut self) {
+        if !self.shaped.as_ref().map_or(false, |key| key.matches(self)) {
+            self.shape();
+            self.shaped = Some(self.shape_key());
         }
     }
 
     fn shape(&mut self) {
-        let mut handler = TextBlobBuilderRunHandler::new(&self.text, (0.0, 0.0));
+        let lines = self.break_lines(self.size.width);
+        let line_height = self.font.spacing();
+        let block_width = lines
+            .iter()
+            .map(|l| l.width)
+            .fold(self.size.width, scalar::max);
+
         let shaper = Shaper::new(None);
-        shaper.shape(&self.text, &self.font, true, self.size.width, &mut handler);
-        self.blob = handler.make_blob();
+        let mut blobs = Vec::new();
+        let mut words = Vec::new();
+        let mut placed = Vec::new();
+        let mut start = 0;
+        for (i, line) in lines.iter().enumerate() {
+            let top = i as scalar * line_height;
+            let space_left = block_width - line.width;
+            let (mut x, gap) = match self.align {
+                TextAlign::Left => (0.0, 0.0),
+                TextAlign::Center => (space_left * 0.5, 0.0),
+                TextAlign::Right => (space_left, 0.0),
+                TextAlign::Justify if !line.last && line.words.len() > 1 => {
+                    (0.0, space_left / (line.words.len() - 1) as scalar)
+                }
+                TextAlign::Justify => (0.0, 0.0),
+            };
+            let mut word_x = x;
+            for (word, &offset) in line.words.iter().zip(&line.offsets) {
+                words.push(PlacedWord {
+                    start: offset,
+                    end: offset + word.len(),
+                    x: word_x,
+                    top,
+                });
+                word_x += self.measure(word) + self.measure(" ") + gap;
+            }
+            // Justified lines are shaped word by word, so that the gaps
+            // between them can be stretched freely.
+            let runs = if gap > 0.0 {
+                line.words.clone()
+            } else {
+                vec![line.words.join(" ")]
+            };
+            // The x of every character boundary, only needed for selecting
+            let mut carets = vec![x];
+            for (j, run) in runs.iter().enumerate() {
+                let mut handler = TextBlobBuilderRunHandler::new(run, Point::new(x, top));
+                shaper.shape(run, &self.font, true, scalar::MAX, &mut handler);
+                if let Some(blob) = handler.make_blob() {
+                    blobs.push(blob);
+                }
+                if self.selection_paint.is_some() {
+                    for (b, c) in run.char_indices() {
+                        carets.push(x + self.measure(&run[..b + c.len_utf8()]));
+                    }
+                    // The space after the word, which is stretched when
+                    // justifying
+                    if j + 1 < runs.len() {
+                        carets.push(x + self.measure(run) + self.measure(" ") + gap);
+                    }
+                }
+                x += self.measure(run) + self.measure(" ") + gap;
+            }
+            let text = runs.join(" ");
+            let chars = text.chars().count();
+            placed.push(PlacedLine {
+                text,
+                start,
+                top,
+                carets,
+                last: line.last,
+            });
+            start += chars + 1;
+        }
+        self.text_width = block_width;
+        self.text_height = lines.len() as scalar * line_height;
+        self.blobs = blobs;
+        self.words = words;
+        self.placed = placed;
+        // Character positions are meaningless after reshaping
+        self.selection = None;
+    }
+
+    /// Clip away the text from the revealed offset on, which is the rest of
+    /// its line and every line below
+    fn clip_unrevealed(&self, canvas: &mut Canvas) {
+        let end = match self.revealed {
+            Some(end) => end,
+            None => return,
+        };
+        let word = match self.words.iter().find(|w| w.end > end) {
+            Some(word) => word,
+            None => return,
+        };
+        let cut = if end > word.start {
+            word.x + self.measure(&self.text[word.start..end])
+        } else {
+            word.x
+        };
+        // Glyphs may reach a little past the text's box
+        let line_height = self.font.spacing();
+        let (left, right) = (-line_height, self.text_width + line_height);
+        let bottom = word.top + line_height;
+        let rest_of_line = Rect::new(cut, word.top, right, bottom);
+        let below = Rect::new(left, bottom, right, self.text_height + line_height);
+        canvas.clip_rect(rest_of_line, ClipOp::Difference, None);
+        canvas.clip_rect(below, ClipOp::Difference, None);
+    }
+
+    fn break_lines(&self, max_width: scalar) -> Vec<Line> {
+        let space = self.measure(" ");
+        let mut lines = Vec::new();
+        for paragraph in self.text.split('\n') {
+            let mut line = Line::default();
+            for word in paragraph.split(' ').filter(|w| !w.is_empty()) {
+                // Words are slices of the text, so this is where they start
+                let offset = word.as_ptr() as usize - self.text.as_ptr() as usize;
+                let width = self.measure(word);
+                let joined = if line.words.is_empty() {
+                    width
+                } else {
+                    line.width + space + width
+                };
+                if !self.wrap || joined <= max_width {
+                    line.words.push(word.to_owned());
+                    line.offsets.push(offset);
+                    line.width = joined;
+                    continue;
+                }
+                if !line.words.is_empty() {
+                    lines.push(std::mem::take(&mut line));
+                }
+                if width <= max_width {
+                    line.words.push(word.to_owned());
+                    line.offsets.push(offset);
+                    line.width = width;
+                    continue;
+                }
+                // The word does not fit on a line of its own, fall back to
+                // breaking it between characters.
+                let mut part = String::new();
+                let mut part_offset = offset;
+                for c in word.chars() {
+                    part.push(c);
+                    if self.measure(&part) > max_width && part.chars().count() > 1 {
+                        part.pop();
+                        let next = part_offset + part.len();
+                        lines.push(Line {
+                            width: self.measure(&part),
+                            words: vec![std::mem::take(&mut part)],
+                            offsets: vec![part_offset],
+                            last: false,
+                        });
+                        part_offset = next;
+                        part.push(c);
+                    }
+                }
+                line.width = self.measure(&part);
+                line.words.push(part);
+                line.offsets.push(part_offset);
+            }
+            line.last = true;
+            lines.push(line);
+        }
+        lines
     }
 }
 
 impl Widget for Text {
-    fn input(&mut self, _wrap: &mut WrapState, _event: &InputEvent) -> bool {
-        // TODO: this is mostly a placeholder value.
-        // I'm pretty sure somebody will have a use for some text to handle click events, that sort of stuff.
+    fn input(&mut self, _wrap: &mut WrapState, event: &InputEvent) -> bool {
+        if self.selection_paint.is_none() {
+            return false;
+        }
+        match event {
+            InputEvent::MouseDown(MouseButton::Left, p, _) => {
+                if Rect::from_size(self.size).contains(*p) {
+                    let at = self.hit(*p);
+                    self.selection = Some((at, at));
+                    self.selecting = true;
+                    State::request_redraw();
+                    return true;
+                }
+                // Clicking elsewhere lets go of the selection, so that only
+                // one text has one at a time
+                if self.selection.take().is_some() {
+                    State::request_redraw();
+                }
+            }
+            InputEvent::MouseMove(p, _) if self.selecting => {
+                let at = self.hit(*p);
+                if let Some((_, to)) = &mut self.selection {
+                    if *to != at {
+                        *to = at;
+                        State::request_redraw();
+                    }
+                }
+                return true;
+            }
+            InputEvent::MouseUp(MouseButton::Left, ..) if self.selecting => {
+                self.selecting = false;
+                return true;
+            }
+            InputEvent::KeyDown(key, _)
+                if KeyCombo::new(Keycode::C)
+                    .primary()
+                    .matches(*key, State::modifiers()) =>
+            {
+                if let Some(text) = self.selected_text() {
+                    State::set_clipboard_text(text);
+                    return true;
+                }
+            }
+            _ => {}
+        }
         false
     }
 
     fn size(&mut self, _wrap: &mut WrapState) -> (LayoutSize, bool) {
-        (self.layout_size, false)
+        let mut size = self.layout_size;
+        size.height.min = size.height.min.max(self.text_height);
+        (size, false)
     }
 
     fn set_size(&mut self, _wrap: &mut WrapState, size: Size) {
         self.size = size;
-        self.shape();
+        self.reshape();
+    }
+
+    fn height_for_width(&mut self, _wrap: &mut WrapState, width: scalar) -> Option<scalar> {
+        if self.wrap {
+            let lines = self.break_lines(width).len();
+            Some(lines as scalar * self.font.spacing())
+        } else {
+            None
+        }
+    }
+
+    fn min_intrinsic_width(&mut self, _wrap: &mut WrapState) -> scalar {
+        let words = self.text.split(|c| c == ' ' || c == '\n');
+        let widest = if self.wrap {
+            words.map(|w| self.measure(w)).fold(0.0, scalar::max)
+        } else {
+            self.max_line_width()
+        };
+        widest.max(self.layout_size.width.min)
+    }
+
+    fn max_intrinsic_width(&mut self, _wrap: &mut WrapState) -> scalar {
+        self.max_line_width().max(self.layout_size.width.min)
+    }
+
+    fn min_intrinsic_height(&mut self, _wrap: &mut WrapState) -> scalar {
+        let lines = self.text.split('\n').count();
+        (lines as scalar * self.font.spacing()).max(self.layout_size.height.min)
+    }
+
+    fn max_intrinsic_height(&mut self, wrap: &mut WrapState) -> scalar {
+        let width = self.min_intrinsic_width(wrap);
+        let lines = self.break_lines(width).len();
+        (lines as scalar * self.font.spacing()).max(self.layout_size.height.min)
     }
 
     fn draw(&mut self, _wrap: &mut WrapState, canvas: &mut Canvas) {
-        if let Some(blob) = &self.blob {
-            let bounds = blob.bounds();
-            canvas.draw_text_blob(blob, (-bounds.left, -bounds.top), &self.paint);
+        self.reshape();
+        if let (Some(paint), Some(range)) = (&self.selection_paint, self.selection_range()) {
+            let line_height = self.font.spacing();
+            for line in &self.placed {
+                if range.start >= line.start + line.carets.len() || range.end <= line.start {
+                    continue;
+                }
+                let from = range.start.max(line.start) - line.start;
+                let to = range.end.min(line.start + line.carets.len()) - line.start;
+                // The selection may run past the end of a line, over the
+                // line break
+                let right = match line.carets.get(to) {
+                    Some(&x) => x,
+                    None => line.carets[to - 1] + self.measure(" "),
+                };
+                let rect = Rect::new(line.carets[from], line.top, right, line.top + line_height);
+                canvas.draw_rect(rect, paint);
+            }
         }
+        canvas.save();
+        self.clip_unrevealed(canvas);
+        for blob in &self.blobs {
+            canvas.draw_text_blob(blob, (0.0, 0.0), &self.paint);
+        }
+        canvas.restore();
+    }
+}
+
+/// Everything shaping depends on besides the text, which only changes
+/// through [`Text::set_text`]
+struct ShapeKey {
+    font: SkFont,
+    align: TextAlign,
+    wrap: bool,
+    width: scalar,
+    selectable: bool,
+}
+
+impl ShapeKey {
+    /// Whether the text is still shaped for what it is drawn with, compared
+    /// in place so that checking every draw doesn't copy the font
+    fn matches(&self, text: &Text) -> bool {
+        self.font == text.font
+            && self.align == text.align
+            && self.wrap == text.wrap
+            && self.width == text.size.width
+            && self.selectable == text.selection_paint.is_some()
     }
 }
+
+/// A line as it was laid out, for selecting text
+struct PlacedLine {
+    text: String,
+    /// The index of the line's first character among all shown characters
+    start: usize,
+    top: scalar,
+    carets: Vec<scalar>,
+    last: bool,
+}
+
+/// A word as it was laid out, for revealing text
+struct PlacedWord {
+    /// The byte range of the word in the text
+    start: usize,
+    end: usize,
+    x: scalar,
+    top: scalar,
+}
+
+#[derive(Default)]
+struct Line {
+    words: Vec<String>,
+    /// The byte offset of each word in the text
+    offsets: Vec<usize>,
+    width: scalar,
+    /// Whether this is the last line of a paragraph.
+    last: bool,
+}
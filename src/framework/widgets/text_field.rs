@@ -0,0 +1,136 @@
+use crate::prelude::*;
+use game::{InputEvent, Keycode, MouseButton, State};
+use skia::{scalar, Canvas, Contains, Rect, Size};
+
+use super::Text;
+
+/// A single line of text that can be typed into, shown in a [`Text`].
+///
+/// A click takes the focus, and while focused every [`InputEvent::Char`]
+/// is added to the end of the text and Backspace takes the last character
+/// away, so it works the same with a physical keyboard and a
+/// [`VirtualKeyboard`](super::VirtualKeyboard). Enter calls the submit
+/// callback. A caret is drawn after the text while focused, which assumes
+/// the `Text` is aligned to the left.
+pub struct TextField {
+    pub inner: Wrap<Text>,
+    /// The most characters the text may have
+    pub max_chars: Option<usize>,
+    on_change: Option<Box<dyn FnMut(&str) + Send>>,
+    on_submit: Option<Box<dyn FnMut(&str) + Send>>,
+    size: Size,
+}
+
+impl TextField {
+    pub fn new(text: impl Into<Wrap<Text>>) -> Self {
+        Self {
+            inner: text.into(),
+            max_chars: None,
+            on_change: None,
+            on_submit: None,
+            size: Size::new_empty(),
+        }
+    }
+
+    pub fn with_max_chars(mut self, max: usize) -> Self {
+        self.max_chars = Some(max);
+        self
+    }
+
+    /// Called with the whole text whenever it is typed into
+    pub fn with_on_change(mut self, f: impl FnMut(&str) + Send + 'static) -> Self {
+        self.on_change = Some(Box::new(f));
+        self
+    }
+
+    /// Called with the whole text when Enter is pressed
+    pub fn with_on_submit(mut self, f: impl FnMut(&str) + Send + 'static) -> Self {
+        self.on_submit = Some(Box::new(f));
+        self
+    }
+
+    pub fn text(&self) -> &str {
+        self.inner.inner.text()
+    }
+
+    /// Replace the text, without calling the change callback
+    pub fn set_text(&mut self, text: impl AsRef<str>) {
+        self.inner.inner.set_text(text);
+        State::request_redraw();
+    }
+
+    fn edit(&mut self, f: impl FnOnce(&mut String)) {
+        let mut text = self.text().to_owned();
+        f(&mut text);
+        if text != self.text() {
+            self.inner.inner.set_text(&text);
+            State::request_redraw();
+            if let Some(f) = &mut self.on_change {
+                f(&text);
+            }
+        }
+    }
+}
+
+impl Widget for TextField {
+    fn input(&mut self, wrap: &mut WrapState, event: &InputEvent) -> bool {
+        let focused = State::focused() == Some(wrap.id());
+        match event {
+            InputEvent::MouseDown(MouseButton::Left, p, _)
+                if Rect::from_size(self.size).contains(*p) =>
+            {
+                State::set_focus(Some(wrap.id()));
+                true
+            }
+            // Control characters come as key presses too, and are handled
+            // as those
+            InputEvent::Char(c, _) if focused && !c.is_control() => {
+                let max = self.max_chars;
+                let c = *c;
+                self.edit(|text| {
+                    if max.map_or(true, |max| text.chars().count() < max) {
+                        text.push(c);
+                    }
+                });
+                true
+            }
+            InputEvent::KeyDown(Keycode::Backspace, _) if focused => {
+                self.edit(|text| {
+                    text.pop();
+                });
+                true
+            }
+            InputEvent::KeyDown(Keycode::Return, _) if focused => {
+                if let Some(f) = &mut self.on_submit {
+                    f(self.inner.inner.text());
+                }
+                true
+            }
+            _ => self.inner.input(event),
+        }
+    }
+
+    forward_widget!(inner; update, size, height_for_width, get);
+
+    fn set_size(&mut self, _wrap: &mut WrapState, size: Size) {
+        self.size = size;
+        self.inner.set_size(size);
+    }
+
+    fn draw(&mut self, wrap: &mut WrapState, canvas: &mut Canvas) {
+        let id = wrap.id();
+        let focused = State::with_mut(|x| {
+            x.focus.register(id, wrap.bounds());
+            x.focus.focused() == Some(id)
+        });
+        self.inner.draw(canvas);
+        if focused {
+            let text = &self.inner.inner;
+            let line = text.text().rsplit('\n').next().unwrap_or("");
+            let x = text.font.measure_str(line, Some(&text.paint)).0;
+            let height: scalar = text.font.spacing();
+            let caret = Rect::from_xywh(x.round(), 0.0, 1.0, height.min(self.size.height));
+            canvas.draw_rect(caret, &text.paint);
+        }
+    }
+}
@@ -0,0 +1,101 @@
+use crate::prelude::*;
+use game::State;
+use skia::{scalar, Canvas, Image, Matrix, Paint, Rect, Size, TileMode, Vector};
+
+/// Fills its bounds with an image repeated in both directions, for patterned
+/// and textured backgrounds.
+///
+/// The pattern is scaled by `scale` and shifted by `offset`. With a
+/// `velocity`, the offset moves on its own at that many logical pixels per
+/// second, timed by [`State::elapsed`], for scrolling backgrounds.
+pub struct TiledImage {
+    pub image: Image,
+    pub layout_size: LayoutSize,
+    pub scale: scalar,
+    pub offset: Vector,
+    pub velocity: Vector,
+    pub paint: Paint,
+    size: Size,
+}
+
+impl TiledImage {
+    pub fn new(image: Image, size: LayoutSize) -> Self {
+        Self {
+            image,
+            layout_size: size,
+            scale: 1.0,
+            offset: Vector::default(),
+            velocity: Vector::default(),
+            paint: Paint::default(),
+            size: Size::new_empty(),
+        }
+    }
+
+    pub fn with_scale(mut self, scale: scalar) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    pub fn with_offset(mut self, offset: impl Into<Vector>) -> Self {
+        self.offset = offset.into();
+        self
+    }
+
+    pub fn with_velocity(mut self, velocity: impl Into<Vector>) -> Self {
+        self.velocity = velocity.into();
+        self
+    }
+
+    pub fn with_paint(mut self, paint: Paint) -> Self {
+        self.paint = paint;
+        self
+    }
+
+    /// The offset at the current time. It is wrapped to a single tile so it
+    /// doesn't lose precision as it keeps growing.
+    fn current_offset(&self) -> Vector {
+        let t = State::elapsed().as_secs_f32();
+        let offset = self.offset + self.velocity * t;
+        let tile_width = self.image.width() as scalar * self.scale;
+        let tile_height = self.image.height() as scalar * self.scale;
+        Vector::new(
+            if tile_width > 0.0 {
+                offset.x % tile_width
+            } else {
+                0.0
+            },
+            if tile_height > 0.0 {
+                offset.y % tile_height
+            } else {
+                0.0
+            },
+        )
+    }
+}
+
+impl Widget for TiledImage {
+    fn size(&mut self, _wrap: &mut WrapState) -> (LayoutSize, bool) {
+        (self.layout_size, false)
+    }
+
+    fn set_size(&mut self, _wrap: &mut WrapState, size: Size) {
+        self.size = size;
+    }
+
+    fn draw(&mut self, _wrap: &mut WrapState, canvas: &mut Canvas) {
+        let offset = self.current_offset();
+        let matrix = Matrix::concat(
+            &Matrix::translate(offset),
+            &Matrix::scale((self.scale, self.scale)),
+        );
+        let shader = self
+            .image
+            .to_shader(Some((TileMode::Repeat, TileMode::Repeat)), Some(&matrix));
+        let mut paint = self.paint.clone();
+        paint.set_shader(shader);
+        canvas.draw_rect(Rect::from_size(self.size), &paint);
+        if self.velocity != Vector::default() {
+            State::request_redraw();
+        }
+    }
+}
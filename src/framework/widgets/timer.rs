@@ -0,0 +1,221 @@
+use std::time::Duration;
+
+use crate::prelude::*;
+use game::State;
+use skia::{Canvas, Size};
+
+use super::Text;
+
+/// How a [`Timer`] writes out its time
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum TimerFormat {
+    /// `75`
+    Seconds,
+    /// `1:15`
+    MinutesSeconds,
+    /// `1:15.250`
+    MinutesSecondsMillis,
+    /// `0:01:15`
+    HoursMinutesSeconds,
+}
+
+impl TimerFormat {
+    pub fn format(&self, time: Duration) -> String {
+        let secs = time.as_secs();
+        match self {
+            Self::Seconds => format!("{}", secs),
+            Self::MinutesSeconds => format!("{}:{:02}", secs / 60, secs % 60),
+            Self::MinutesSecondsMillis => {
+                format!("{}:{:02}.{:03}", secs / 60, secs % 60, time.subsec_millis())
+            }
+            Self::HoursMinutesSeconds => {
+                format!("{}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60)
+            }
+        }
+    }
+
+    fn shows_millis(&self) -> bool {
+        *self == Self::MinutesSecondsMillis
+    }
+}
+
+/// Shows a running time as text, either counting down to zero or counting
+/// up like a stopwatch, timed by [`State::elapsed`].
+///
+/// A countdown shows whole seconds rounded up, so it reads `0:01` for the
+/// whole of its last second and only `0:00` once it is over. When it
+/// reaches zero it stops and calls its `on_finished` callback once.
+pub struct Timer {
+    pub format: TimerFormat,
+    /// How long to count down from, or `None` to count up
+    countdown: Option<Duration>,
+    text: Text,
+    running: bool,
+    /// When the current run started, once the timer has been updated
+    since: Option<Duration>,
+    /// Time counted in earlier runs, before the last pause
+    banked: Duration,
+    finished: bool,
+    on_finished: Option<Box<dyn FnMut() + Send>>,
+}
+
+impl Timer {
+    /// Count down from `duration`
+    pub fn countdown(duration: Duration, text: Text) -> Self {
+        Self::new(Some(duration), text)
+    }
+
+    /// Count up from zero
+    pub fn stopwatch(text: Text) -> Self {
+        Self::new(None, text)
+    }
+
+    fn new(countdown: Option<Duration>, text: Text) -> Self {
+        let mut timer = Self {
+            format: TimerFormat::MinutesSeconds,
+            countdown,
+            text,
+            running: true,
+            since: None,
+            banked: Duration::ZERO,
+            finished: false,
+            on_finished: None,
+        };
+        timer.refresh();
+        timer
+    }
+
+    pub fn with_format(mut self, format: TimerFormat) -> Self {
+        self.format = format;
+        self.refresh();
+        self
+    }
+
+    /// Create the timer paused, to be started with [`resume`](Self::resume)
+    pub fn with_paused(mut self, paused: bool) -> Self {
+        self.running = !paused;
+        self
+    }
+
+    pub fn with_on_finished(mut self, f: impl FnMut() + Send + 'static) -> Self {
+        self.on_finished = Some(Box::new(f));
+        self
+    }
+
+    pub fn pause(&mut self) {
+        self.banked = self.elapsed();
+        self.since = None;
+        self.running = false;
+    }
+
+    pub fn resume(&mut self) {
+        if !self.finished {
+            self.running = true;
+        }
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.running
+    }
+
+    /// Stop and go back to the start, keeping the paused state
+    pub fn reset(&mut self) {
+        self.banked = Duration::ZERO;
+        self.since = None;
+        self.finished = false;
+        self.refresh();
+    }
+
+    /// Start over with a different countdown
+    pub fn restart(&mut self, duration: Duration) {
+        self.countdown = Some(duration);
+        self.reset();
+        self.running = true;
+    }
+
+    /// How long the timer has been running, not counting pauses
+    pub fn elapsed(&self) -> Duration {
+        let run = self
+            .since
+            .map_or(Duration::ZERO, |s| State::elapsed().saturating_sub(s));
+        let elapsed = self.banked + run;
+        match self.countdown {
+            Some(d) => elapsed.min(d),
+            None => elapsed,
+        }
+    }
+
+    /// The time left on a countdown
+    pub fn remaining(&self) -> Option<Duration> {
+        self.countdown.map(|d| d.saturating_sub(self.elapsed()))
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    /// The text the time is shown with, to change its paint or alignment
+    pub fn text_mut(&mut self) -> &mut Text {
+        &mut self.text
+    }
+
+    fn display(&self) -> Duration {
+        match self.remaining() {
+            Some(left) if !self.format.shows_millis() && left.subsec_nanos() > 0 => {
+                Duration::from_secs(left.as_secs() + 1)
+            }
+            Some(left) => left,
+            None => self.elapsed(),
+        }
+    }
+
+    /// How long until the shown time next changes, for the formats without
+    /// milliseconds
+    fn next_change(&self) -> Duration {
+        let nanos = match self.remaining() {
+            Some(left) if left.subsec_nanos() > 0 => left.subsec_nanos(),
+            Some(_) => 1_000_000_000,
+            None => 1_000_000_000 - self.elapsed().subsec_nanos(),
+        };
+        Duration::from_nanos(nanos.into())
+    }
+
+    fn refresh(&mut self) {
+        let text = self.format.format(self.display());
+        self.text.set_text(text);
+    }
+}
+
+impl Widget for Timer {
+    fn update(&mut self, wrap: &mut WrapState) {
+        if self.running && self.since.is_none() {
+            self.since = Some(State::elapsed());
+        }
+        if self.running && !self.finished && self.remaining() == Some(Duration::ZERO) {
+            self.pause();
+            self.finished = true;
+            if let Some(f) = &mut self.on_finished {
+                f();
+            }
+        }
+        self.refresh();
+        self.text.update(wrap);
+    }
+
+    fn size(&mut self, wrap: &mut WrapState) -> (LayoutSize, bool) {
+        self.text.size(wrap)
+    }
+
+    fn set_size(&mut self, wrap: &mut WrapState, size: Size) {
+        self.text.set_size(wrap, size);
+    }
+
+    fn draw(&mut self, wrap: &mut WrapState, canvas: &mut Canvas) {
+        self.text.draw(wrap, canvas);
+        if self.running && self.format.shows_millis() {
+            State::request_redraw();
+        } else if self.running {
+            State::request_redraw_in(self.next_change());
+        }
+    }
+}
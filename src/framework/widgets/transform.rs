@@ -1,6 +1,6 @@
 use crate::prelude::*;
-use game::{InputEvent, ID};
-use skia::{Canvas, Matrix, Rect, Size};
+use game::InputEvent;
+use skia::{scalar, Canvas, Matrix, Rect, Size};
 
 pub struct Transform<T: Widget> {
     pub inner: Wrap<T>,
@@ -17,10 +17,13 @@ impl<T: Widget> Transform<T> {
 }
 
 impl<T: Widget> Widget for Transform<T> {
-    fn update(&mut self, _wrap: &mut WrapState) {
-        self.inner.update();
+    // The matrix can move the child anywhere
+    fn on_mount(&mut self, wrap: &mut WrapState) {
+        wrap.set_cull_margin(None);
     }
 
+    forward_widget!(inner; update, get);
+
     fn input(&mut self, _wrap: &mut WrapState, event: &InputEvent) -> bool {
         event
             .reverse_map_position(self.matrix)
@@ -39,14 +42,22 @@ impl<T: Widget> Widget for Transform<T> {
         }
     }
 
+    // Only a scale keeps the width and height apart, under a rotation or
+    // skew each depends on both
+    fn height_for_width(&mut self, _wrap: &mut WrapState, width: scalar) -> Option<scalar> {
+        let m = self.matrix;
+        if !m.is_scale_translate() || m.scale_x() == 0.0 {
+            return None;
+        }
+        self.inner
+            .height_for_width(width / m.scale_x().abs())
+            .map(|h| h * m.scale_y().abs())
+    }
+
     fn draw(&mut self, _wrap: &mut WrapState, canvas: &mut Canvas) {
         canvas.save();
         canvas.concat(&self.matrix);
         self.inner.draw(canvas);
         canvas.restore();
     }
-
-    fn get(&mut self, _wrap: &mut WrapState, id: ID) -> Option<(&mut dyn Widget, &mut WrapState)> {
-        self.inner.get(id)
-    }
 }
@@ -0,0 +1,109 @@
+use crate::prelude::*;
+use game::InputEvent;
+use skia::{scalar, Canvas, Matrix, Size, M44, V3};
+
+/// Transforms its child in 3D with perspective, for effects like flipping a
+/// card over.
+///
+/// The child is first transformed by `matrix`, then rotated around its
+/// center by `rotation_x` and `rotation_y` in degrees, and finally projected
+/// as seen from a camera `camera_distance` logical pixels in front of it.
+/// Closer cameras give stronger foreshortening.
+pub struct Transform3D<T: Widget> {
+    pub inner: Wrap<T>,
+    pub matrix: M44,
+    pub rotation_x: scalar,
+    pub rotation_y: scalar,
+    pub camera_distance: scalar,
+    size: Size,
+}
+
+impl<T: Widget> Transform3D<T> {
+    pub fn new(inner: impl Into<Wrap<T>>, matrix: M44) -> Self {
+        Self {
+            inner: inner.into(),
+            matrix,
+            rotation_x: 0.0,
+            rotation_y: 0.0,
+            camera_distance: 800.0,
+            size: Size::new_empty(),
+        }
+    }
+
+    /// Tip the child forwards or backwards around its horizontal axis
+    pub fn flip_x(inner: impl Into<Wrap<T>>, degrees: scalar, camera_distance: scalar) -> Self {
+        let mut t = Self::new(inner, M44::new_identity());
+        t.rotation_x = degrees;
+        t.camera_distance = camera_distance;
+        t
+    }
+
+    /// Turn the child left or right around its vertical axis
+    pub fn flip_y(inner: impl Into<Wrap<T>>, degrees: scalar, camera_distance: scalar) -> Self {
+        let mut t = Self::new(inner, M44::new_identity());
+        t.rotation_y = degrees;
+        t.camera_distance = camera_distance;
+        t
+    }
+
+    /// Whether the child is turned away from the camera, showing its back.
+    /// Use this to swap in the other side of a card halfway through a flip.
+    pub fn is_back_facing(&self) -> bool {
+        let facing = self.rotation_x.to_radians().cos() * self.rotation_y.to_radians().cos();
+        facing < 0.0
+    }
+
+    /// The full transform, as drawn
+    pub fn transform(&self) -> M44 {
+        let (cx, cy) = (self.size.width / 2.0, self.size.height / 2.0);
+        let rotate_x = M44::rotate(V3::new(1.0, 0.0, 0.0), self.rotation_x.to_radians());
+        let rotate_y = M44::rotate(V3::new(0.0, 1.0, 0.0), self.rotation_y.to_radians());
+        // With the camera in front at z = -d, points further away are
+        // scaled down by d / (d + z).
+        let mut perspective = M44::new_identity();
+        if self.camera_distance > 0.0 {
+            perspective.set_rc(3, 2, 1.0 / self.camera_distance);
+        }
+        let mut m = M44::translate(cx, cy, 0.0);
+        m.pre_concat(&perspective)
+            .pre_concat(&rotate_x)
+            .pre_concat(&rotate_y)
+            .pre_concat(&self.matrix)
+            .pre_concat(&M44::translate(-cx, -cy, 0.0));
+        m
+    }
+
+    /// The full transform flattened for the 2D canvas. This drops the z row
+    /// and column, which is exact for content lying flat in the z = 0
+    /// plane, as all widgets do, so it maps input the same as it is drawn.
+    pub fn flattened(&self) -> Matrix {
+        self.transform().to_m33()
+    }
+}
+
+impl<T: Widget> Widget for Transform3D<T> {
+    // The projection can move the child anywhere
+    fn on_mount(&mut self, wrap: &mut WrapState) {
+        wrap.set_cull_margin(None);
+    }
+
+    forward_widget!(inner; update, size, get);
+
+    fn input(&mut self, _wrap: &mut WrapState, event: &InputEvent) -> bool {
+        event
+            .reverse_map_position(self.flattened())
+            .map_or(false, |event| self.inner.input(&event))
+    }
+
+    fn set_size(&mut self, _wrap: &mut WrapState, size: Size) {
+        self.size = size;
+        self.inner.set_size(size);
+    }
+
+    fn draw(&mut self, _wrap: &mut WrapState, canvas: &mut Canvas) {
+        canvas.save();
+        canvas.concat_44(&self.transform());
+        self.inner.draw(canvas);
+        canvas.restore();
+    }
+}
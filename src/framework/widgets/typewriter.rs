@@ -0,0 +1,180 @@
+use std::time::Duration;
+
+use crate::prelude::*;
+use game::{InputEvent, MouseButton, State};
+use skia::{Canvas, Contains, Rect, Size};
+
+use super::Text;
+
+/// Reveals the text of a [`Text`] a character at a time, like dialogue in a
+/// narrative game.
+///
+/// Characters appear at a steady rate from when the text is set, timed by
+/// [`State::elapsed`]. Clicking can skip to the end, and a callback gets each
+/// character as it appears, for playing a blip per letter. Whole characters
+/// are revealed, never parts of one. The full text is laid out from the
+/// start and the rest hidden with [`Text::set_revealed`], so words don't
+/// jump to the next line as they are typed out.
+pub struct Typewriter {
+    pub inner: Wrap<Text>,
+    /// Characters revealed per second
+    pub rate: f64,
+    /// Show the whole text when clicked while it is still being revealed
+    pub skip_on_click: bool,
+    text: String,
+    /// How many characters of the text are shown
+    shown: usize,
+    /// Byte offsets of the characters, and the end of the text
+    boundaries: Vec<usize>,
+    start: Duration,
+    on_char: Option<Box<dyn FnMut(char) + Send>>,
+    on_complete: Option<Box<dyn FnMut() + Send>>,
+    /// Whether `on_complete` was called for the current text
+    completed: bool,
+    size: Size,
+}
+
+impl Typewriter {
+    /// Start revealing the text of `text` at `rate` characters per second
+    pub fn new(text: impl Into<Wrap<Text>>, rate: f64) -> Self {
+        let inner: Wrap<Text> = text.into();
+        let full = inner.inner.text().to_owned();
+        let mut typewriter = Self {
+            inner,
+            rate,
+            skip_on_click: true,
+            text: String::new(),
+            shown: 0,
+            boundaries: vec![0],
+            start: Duration::ZERO,
+            on_char: None,
+            on_complete: None,
+            completed: false,
+            size: Size::new_empty(),
+        };
+        typewriter.set_text(full);
+        typewriter
+    }
+
+    pub fn with_skip_on_click(mut self, skip: bool) -> Self {
+        self.skip_on_click = skip;
+        self
+    }
+
+    /// Called with every character as it's revealed, except those shown at
+    /// once by skipping
+    pub fn with_on_char(mut self, f: impl FnMut(char) + Send + 'static) -> Self {
+        self.on_char = Some(Box::new(f));
+        self
+    }
+
+    /// Called once the whole text is shown, whether by revealing or skipping.
+    /// For an empty text, that is on the first update.
+    pub fn with_on_complete(mut self, f: impl FnMut() + Send + 'static) -> Self {
+        self.on_complete = Some(Box::new(f));
+        self
+    }
+
+    /// Replace the text, revealing it from the start
+    pub fn set_text(&mut self, text: impl Into<String>) {
+        self.text = text.into();
+        self.boundaries = self
+            .text
+            .char_indices()
+            .map(|(i, _)| i)
+            .chain(std::iter::once(self.text.len()))
+            .collect();
+        self.start = State::elapsed();
+        self.shown = 0;
+        self.completed = false;
+        self.inner.inner.set_text(&self.text);
+        self.inner.inner.set_revealed(Some(0));
+        State::request_redraw();
+    }
+
+    /// The whole text, including what isn't shown yet
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn char_count(&self) -> usize {
+        self.boundaries.len() - 1
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.shown >= self.char_count()
+    }
+
+    /// Show the whole text straight away
+    pub fn skip(&mut self) {
+        self.reveal(self.char_count(), false);
+    }
+
+    fn reveal(&mut self, count: usize, announce: bool) {
+        let count = count.min(self.char_count());
+        if count <= self.shown {
+            return;
+        }
+        if announce {
+            if let Some(f) = &mut self.on_char {
+                let from = self.boundaries[self.shown];
+                let to = self.boundaries[count];
+                self.text[from..to].chars().for_each(|c| f(c));
+            }
+        }
+        self.shown = count;
+        self.inner.inner.set_revealed(Some(self.boundaries[count]));
+        self.complete();
+    }
+
+    /// Call `on_complete` once the whole text is shown
+    fn complete(&mut self) {
+        if self.is_complete() && !self.completed {
+            self.completed = true;
+            self.inner.inner.set_revealed(None);
+            if let Some(f) = &mut self.on_complete {
+                f();
+            }
+        }
+    }
+}
+
+impl Widget for Typewriter {
+    fn update(&mut self, _wrap: &mut WrapState) {
+        if !self.is_complete() {
+            let elapsed = State::elapsed()
+                .checked_sub(self.start)
+                .unwrap_or(Duration::ZERO);
+            let count = (elapsed.as_secs_f64() * self.rate.max(0.0)) as usize;
+            self.reveal(count, true);
+        }
+        // An empty text is complete right away, without revealing anything
+        self.complete();
+        self.inner.update();
+    }
+
+    fn input(&mut self, _wrap: &mut WrapState, event: &InputEvent) -> bool {
+        if let InputEvent::MouseDown(MouseButton::Left, p, _) = event {
+            if self.skip_on_click && !self.is_complete() && Rect::from_size(self.size).contains(*p)
+            {
+                self.skip();
+                return true;
+            }
+        }
+        self.inner.input(event)
+    }
+
+    forward_widget!(inner; size, height_for_width, get);
+
+    fn set_size(&mut self, _wrap: &mut WrapState, size: Size) {
+        self.size = size;
+        self.inner.set_size(size);
+    }
+
+    fn draw(&mut self, _wrap: &mut WrapState, canvas: &mut Canvas) {
+        self.inner.draw(canvas);
+        if !self.is_complete() {
+            State::request_redraw();
+        }
+    }
+}
@@ -0,0 +1,477 @@
+use std::time::Duration;
+
+use crate::framework::tween::{Easing, Tween};
+use crate::prelude::*;
+use game::{InputEvent, Keycode, MouseButton, State};
+use skia::{scalar, Canvas, Contains, Font as SkFont, Paint, Point, RRect, Rect, Size};
+
+use super::{Font, FontStyle, Repeater};
+
+/// What a key of a [`VirtualKeyboard`] does
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VirtualKeyAction {
+    /// Type a character, in upper case while shifted
+    Char(char),
+    Backspace,
+    Enter,
+    Space,
+    /// Shift the next character, or every one after a double tap
+    Shift,
+    /// Switch between the letters and the symbols
+    Symbols,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct VirtualKey {
+    pub action: VirtualKeyAction,
+    /// Shown instead of the character or the usual name of the key
+    pub label: Option<String>,
+    /// Relative to a character key
+    pub width: scalar,
+}
+
+impl VirtualKey {
+    pub fn new(action: VirtualKeyAction) -> Self {
+        Self {
+            action,
+            label: None,
+            width: 1.0,
+        }
+    }
+
+    pub fn with_label(mut self, label: impl Into<String>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+
+    pub fn with_width(mut self, width: scalar) -> Self {
+        self.width = width;
+        self
+    }
+
+    fn label(&self, shifted: bool, symbols: bool) -> String {
+        if let Some(label) = &self.label {
+            return label.clone();
+        }
+        match self.action {
+            VirtualKeyAction::Char(c) if shifted => c.to_uppercase().collect(),
+            VirtualKeyAction::Char(c) => c.to_string(),
+            VirtualKeyAction::Backspace => "⌫".to_owned(),
+            VirtualKeyAction::Enter => "⏎".to_owned(),
+            VirtualKeyAction::Space => String::new(),
+            VirtualKeyAction::Shift => "⇧".to_owned(),
+            VirtualKeyAction::Symbols if symbols => "ABC".to_owned(),
+            VirtualKeyAction::Symbols => "?123".to_owned(),
+        }
+    }
+}
+
+/// The rows of keys of a [`VirtualKeyboard`], for the letters and the
+/// symbols. Shifting only changes the case of the letters, so it needs no
+/// rows of its own.
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeyboardLayout {
+    pub letters: Vec<Vec<VirtualKey>>,
+    pub symbols: Vec<Vec<VirtualKey>>,
+}
+
+impl KeyboardLayout {
+    pub fn qwerty() -> Self {
+        Self::from_rows(
+            &["qwertyuiop", "asdfghjkl", "zxcvbnm"],
+            &["1234567890", "-/:;()$&@\"", ".,?!'#%*+"],
+        )
+    }
+
+    /// A layout from rows of characters, with shift and backspace added
+    /// around the last row, and a row with the symbol switch, space and
+    /// enter below
+    pub fn from_rows(letters: &[&str], symbols: &[&str]) -> Self {
+        Self {
+            letters: Self::layer(letters),
+            symbols: Self::layer(symbols),
+        }
+    }
+
+    fn layer(rows: &[&str]) -> Vec<Vec<VirtualKey>> {
+        let mut keys: Vec<Vec<VirtualKey>> = rows
+            .iter()
+            .map(|row| {
+                row.chars()
+                    .map(|c| VirtualKey::new(VirtualKeyAction::Char(c)))
+                    .collect()
+            })
+            .collect();
+        if let Some(last) = keys.last_mut() {
+            last.insert(0, VirtualKey::new(VirtualKeyAction::Shift).with_width(1.5));
+            last.push(VirtualKey::new(VirtualKeyAction::Backspace).with_width(1.5));
+        }
+        keys.push(vec![
+            VirtualKey::new(VirtualKeyAction::Symbols).with_width(1.5),
+            VirtualKey::new(VirtualKeyAction::Space).with_width(5.0),
+            VirtualKey::new(VirtualKeyAction::Enter).with_width(1.5),
+        ]);
+        keys
+    }
+}
+
+impl Default for KeyboardLayout {
+    fn default() -> Self {
+        Self::qwerty()
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Shift {
+    Off,
+    Once,
+    Locked,
+}
+
+/// An on-screen keyboard for touch screens and kiosks, which types into
+/// whatever widget has focus.
+///
+/// Keys are sent with [`State::send_input`] as the same events a physical
+/// keyboard makes: a `KeyDown`, an [`InputEvent::Char`] for keys that type
+/// a character, and a `KeyUp`. Widgets that take typing, like a
+/// [`TextField`](super::TextField), don't need to know the keyboard exists.
+/// Pressing keys doesn't take the focus away, so the focused field keeps
+/// it. Backspace repeats while held.
+pub struct VirtualKeyboard {
+    pub layout: KeyboardLayout,
+    pub layout_size: LayoutSize,
+    pub font: SkFont,
+    pub text_paint: Paint,
+    /// The keys, the theme's surface color by default
+    pub key_paint: Option<Paint>,
+    /// Flashed over a key when it's pressed, and shift while it's on, the
+    /// theme's primary color by default
+    pub pressed_paint: Option<Paint>,
+    /// The gap between keys
+    pub spacing: scalar,
+    visible: bool,
+    /// Whether the size changed by showing or hiding since it was last laid out
+    visibility_changed: bool,
+    shift: Shift,
+    /// When shift was last pressed, to tell a double tap
+    shift_pressed: Option<Duration>,
+    symbols: bool,
+    /// The row and column of the key under the pointer
+    pressed: Option<(usize, usize)>,
+    /// The key that was pressed last, and how far into its press animation
+    /// it is, from 1 when pressed to 0
+    flash: Option<((usize, usize), Tween)>,
+    repeater: Repeater,
+    size: Size,
+}
+
+impl VirtualKeyboard {
+    pub const DOUBLE_TAP: Duration = Duration::from_millis(400);
+
+    pub fn new(size: LayoutSize) -> Self {
+        let (text_paint, font_size) = State::with_theme(|t| (t.text(), t.font_sizes.regular));
+        let mut font = Font::Default.resolve(&FontStyle::Regular);
+        font.set_size(font_size);
+        Self {
+            layout: KeyboardLayout::default(),
+            layout_size: size,
+            font,
+            text_paint,
+            key_paint: None,
+            pressed_paint: None,
+            spacing: 4.0,
+            visible: true,
+            visibility_changed: false,
+            shift: Shift::Off,
+            shift_pressed: None,
+            symbols: false,
+            pressed: None,
+            flash: None,
+            repeater: Repeater::new(Duration::from_millis(400), Duration::from_millis(60)),
+            size: Size::new_empty(),
+        }
+    }
+
+    pub fn with_layout(mut self, layout: KeyboardLayout) -> Self {
+        self.layout = layout;
+        self
+    }
+
+    pub fn with_key_paint(mut self, paint: Paint) -> Self {
+        self.key_paint = Some(paint);
+        self
+    }
+
+    pub fn with_pressed_paint(mut self, paint: Paint) -> Self {
+        self.pressed_paint = Some(paint);
+        self
+    }
+
+    pub fn with_spacing(mut self, spacing: scalar) -> Self {
+        self.spacing = spacing;
+        self
+    }
+
+    /// Start out hidden, to be shown once a field takes focus
+    pub fn hidden(mut self) -> Self {
+        self.visible = false;
+        self
+    }
+
+    pub fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    /// Show or hide the keyboard. Hidden, it takes no space and no input.
+    pub fn set_visible(&mut self, visible: bool) {
+        if visible != self.visible {
+            self.visible = visible;
+            self.visibility_changed = true;
+            self.pressed = None;
+            self.repeater.release();
+            State::request_redraw();
+        }
+    }
+
+    pub fn is_shifted(&self) -> bool {
+        self.shift != Shift::Off
+    }
+
+    fn rows(&self) -> &[Vec<VirtualKey>] {
+        if self.symbols {
+            &self.layout.symbols
+        } else {
+            &self.layout.letters
+        }
+    }
+
+    /// Each key with where it is. Rows are as tall as the keyboard allows,
+    /// and keys are as wide as the widest row allows, with shorter rows
+    /// centered.
+    fn key_rects(&self) -> Vec<((usize, usize), Rect)> {
+        let rows = self.rows();
+        if rows.is_empty() {
+            return Vec::new();
+        }
+        let row_height = self.size.height / rows.len() as scalar;
+        let widest = rows
+            .iter()
+            .map(|row| row.iter().map(|k| k.width).sum::<scalar>())
+            .fold(0.0, scalar::max)
+            .max(1.0);
+        let unit = self.size.width / widest;
+        let inset = self.spacing / 2.0;
+        let mut rects = Vec::new();
+        for (r, row) in rows.iter().enumerate() {
+            let row_width = row.iter().map(|k| k.width).sum::<scalar>() * unit;
+            let mut x = (self.size.width - row_width) / 2.0;
+            let y = r as scalar * row_height;
+            for (c, key) in row.iter().enumerate() {
+                let rect =
+                    Rect::from_xywh(x, y, key.width * unit, row_height).with_inset((inset, inset));
+                rects.push(((r, c), rect));
+                x += key.width * unit;
+            }
+        }
+        rects
+    }
+
+    fn key_at(&self, p: Point) -> Option<(usize, usize)> {
+        self.key_rects()
+            .into_iter()
+            .find(|(_, rect)| rect.contains(p))
+            .map(|(key, _)| key)
+    }
+
+    fn action(&self, (r, c): (usize, usize)) -> Option<VirtualKeyAction> {
+        self.rows().get(r)?.get(c).map(|k| k.action)
+    }
+
+    fn press(&mut self, key: (usize, usize)) {
+        let action = match self.action(key) {
+            Some(action) => action,
+            None => return,
+        };
+        let mut flash = Tween::new(1.0).with_easing(Easing::EaseOut);
+        flash.animate_to(0.0, Duration::from_millis(150));
+        self.flash = Some((key, flash));
+        match action {
+            VirtualKeyAction::Char(c) => {
+                let c = if self.is_shifted() {
+                    c.to_uppercase().next().unwrap_or(c)
+                } else {
+                    c
+                };
+                // The keycodes of printable keys are their unshifted ASCII
+                // characters
+                let keycode = Some(c)
+                    .filter(char::is_ascii)
+                    .and_then(|c| Keycode::from_i32(c.to_ascii_lowercase() as i32));
+                Self::send(keycode, Some(c));
+                if self.shift == Shift::Once {
+                    self.shift = Shift::Off;
+                }
+            }
+            VirtualKeyAction::Space => Self::send(Some(Keycode::Space), Some(' ')),
+            VirtualKeyAction::Enter => Self::send(Some(Keycode::Return), None),
+            VirtualKeyAction::Backspace => {
+                Self::send(Some(Keycode::Backspace), None);
+                self.repeater.press();
+            }
+            VirtualKeyAction::Shift => {
+                let now = State::elapsed();
+                let double_tap = self
+                    .shift_pressed
+                    .map_or(false, |t| now - t <= Self::DOUBLE_TAP);
+                self.shift = match self.shift {
+                    Shift::Off => Shift::Once,
+                    Shift::Once if double_tap => Shift::Locked,
+                    _ => Shift::Off,
+                };
+                self.shift_pressed = Some(now);
+            }
+            VirtualKeyAction::Symbols => {
+                self.symbols = !self.symbols;
+                // The keys under the pointer are different ones now
+                self.flash = None;
+            }
+        }
+        State::request_redraw();
+    }
+
+    fn send(keycode: Option<Keycode>, c: Option<char>) {
+        let now = State::elapsed();
+        if let Some(k) = keycode {
+            State::send_input(InputEvent::KeyDown(k, now));
+        }
+        if let Some(c) = c {
+            State::send_input(InputEvent::Char(c, now));
+        }
+        if let Some(k) = keycode {
+            State::send_input(InputEvent::KeyUp(k, now));
+        }
+    }
+
+    fn draw_key(&self, canvas: &mut Canvas, key: &VirtualKey, rect: Rect, flash: scalar) {
+        let (mut key_paint, pressed_paint) = State::with_theme(|t| {
+            (
+                self.key_paint.clone().unwrap_or_else(|| t.surface()),
+                self.pressed_paint.clone().unwrap_or_else(|| t.primary()),
+            )
+        });
+        key_paint.set_anti_alias(true);
+        // Pressed keys sink in a little before springing back
+        let scale = 1.0 - 0.08 * flash;
+        let rect = rect.with_inset((
+            rect.width() * (1.0 - scale) / 2.0,
+            rect.height() * (1.0 - scale) / 2.0,
+        ));
+        let radius = 4.0;
+        let rrect = RRect::new_rect_xy(rect, radius, radius);
+        canvas.draw_rrect(rrect, &key_paint);
+        let lit = key.action == VirtualKeyAction::Shift && self.is_shifted();
+        let highlight = if lit { 1.0 } else { flash };
+        if highlight > 0.0 {
+            let mut paint = pressed_paint.anti_alias();
+            paint.set_alpha_f(paint.alpha_f() * highlight);
+            canvas.draw_rrect(rrect, &paint);
+        }
+        if self.shift == Shift::Locked && key.action == VirtualKeyAction::Shift {
+            let underline = self.text_paint.clone().with_stroke_width(2.0).anti_alias();
+            let y = rect.bottom - rect.height() * 0.2;
+            let arm = rect.width() * 0.15;
+            canvas.draw_line(
+                (rect.center_x() - arm, y),
+                (rect.center_x() + arm, y),
+                &underline,
+            );
+        }
+        let label = key.label(self.is_shifted(), self.symbols);
+        if label.is_empty() {
+            return;
+        }
+        let (width, bounds) = self.font.measure_str(&label, Some(&self.text_paint));
+        let origin = Point::new(
+            rect.center_x() - width / 2.0,
+            rect.center_y() - bounds.height() / 2.0 - bounds.top,
+        );
+        canvas.draw_str(&label, origin, &self.font, &self.text_paint);
+    }
+}
+
+impl Widget for VirtualKeyboard {
+    fn update(&mut self, _wrap: &mut WrapState) {
+        for _ in 0..self.repeater.poll() {
+            if let Some(VirtualKeyAction::Backspace) = self.pressed.and_then(|k| self.action(k)) {
+                Self::send(Some(Keycode::Backspace), None);
+            }
+        }
+    }
+
+    fn input(&mut self, _wrap: &mut WrapState, event: &InputEvent) -> bool {
+        if !self.visible {
+            return false;
+        }
+        match event {
+            InputEvent::MouseDown(MouseButton::Left, p, _)
+                if Rect::from_size(self.size).contains(*p) =>
+            {
+                self.pressed = self.key_at(*p);
+                if let Some(key) = self.pressed {
+                    self.press(key);
+                }
+                // Even the gaps between keys are part of the keyboard, so
+                // taps there don't fall through to what's behind it
+                true
+            }
+            InputEvent::MouseMove(p, _) if self.pressed.is_some() => {
+                // Sliding off a held backspace stops it repeating
+                if self.key_at(*p) != self.pressed {
+                    self.pressed = None;
+                    self.repeater.release();
+                }
+                true
+            }
+            InputEvent::MouseUp(MouseButton::Left, ..) if self.pressed.is_some() => {
+                self.pressed = None;
+                self.repeater.release();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn size(&mut self, _wrap: &mut WrapState) -> (LayoutSize, bool) {
+        let changed = std::mem::take(&mut self.visibility_changed);
+        if self.visible {
+            (self.layout_size, changed)
+        } else {
+            (LayoutSize::ZERO, changed)
+        }
+    }
+
+    fn set_size(&mut self, _wrap: &mut WrapState, size: Size) {
+        self.size = size;
+    }
+
+    fn draw(&mut self, _wrap: &mut WrapState, canvas: &mut Canvas) {
+        if !self.visible {
+            return;
+        }
+        let flash = self.flash.as_ref().map(|(key, t)| (*key, t.value()));
+        for (key, rect) in self.key_rects() {
+            let amount = match flash {
+                Some((k, amount)) if k == key => amount,
+                _ => 0.0,
+            };
+            if let Some(virtual_key) = self.rows().get(key.0).and_then(|row| row.get(key.1)) {
+                self.draw_key(canvas, virtual_key, rect, amount);
+            }
+        }
+        match &self.flash {
+            Some((_, t)) if !t.is_finished() => State::request_redraw(),
+            Some(_) => self.flash = None,
+            None => {}
+        }
+    }
+}
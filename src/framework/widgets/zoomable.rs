@@ -0,0 +1,143 @@
+use crate::prelude::*;
+use game::{InputEvent, MouseButton};
+use skia::{scalar, Canvas, Contains, Matrix, Point, Rect, Size};
+
+/// A view onto its child that zooms towards the mouse with the scroll wheel
+/// and pans by dragging, for maps, images and editors.
+///
+/// The point under the mouse stays where it is while zooming. The child
+/// gets the first chance at every event, so it can still be clicked, and
+/// the view is only panned by drags the child didn't take. The zoom and pan
+/// are kept in the [`WrapState`], see [`WrapState::zoom`] and
+/// [`WrapState::pan`], so they can be reset or saved from outside.
+pub struct Zoomable<T: Widget> {
+    pub inner: Wrap<T>,
+    pub min_zoom: scalar,
+    pub max_zoom: scalar,
+    /// How much one notch of the scroll wheel zooms by
+    pub zoom_step: scalar,
+    pub pan_button: MouseButton,
+    /// The last mouse position over the view, which scrolling zooms towards
+    hover: Option<Point>,
+    drag: Option<Point>,
+    size: Size,
+}
+
+impl<T: Widget> Zoomable<T> {
+    pub fn new(inner: impl Into<Wrap<T>>) -> Self {
+        Self {
+            inner: inner.into(),
+            min_zoom: 0.1,
+            max_zoom: 10.0,
+            zoom_step: 1.1,
+            pan_button: MouseButton::Left,
+            hover: None,
+            drag: None,
+            size: Size::new_empty(),
+        }
+    }
+
+    pub fn with_zoom_range(mut self, min: scalar, max: scalar) -> Self {
+        self.min_zoom = min;
+        self.max_zoom = max;
+        self
+    }
+
+    pub fn with_zoom_step(mut self, step: scalar) -> Self {
+        self.zoom_step = step;
+        self
+    }
+
+    pub fn with_pan_button(mut self, button: MouseButton) -> Self {
+        self.pan_button = button;
+        self
+    }
+
+    fn matrix(wrap: &WrapState) -> Matrix {
+        let zoom = wrap.zoom();
+        let mut matrix = Matrix::translate(wrap.pan());
+        matrix.pre_scale((zoom, zoom), None);
+        matrix
+    }
+
+    /// Zoom by `factor`, keeping `anchor` in place
+    fn zoom_at(&self, wrap: &mut WrapState, anchor: Point, factor: scalar) {
+        let zoom = wrap.zoom();
+        let new_zoom = (zoom * factor).max(self.min_zoom).min(self.max_zoom);
+        let pan = wrap.pan();
+        wrap.set_pan(anchor - (anchor - pan) * (new_zoom / zoom));
+        wrap.set_zoom(new_zoom);
+    }
+}
+
+impl<T: Widget> Widget for Zoomable<T> {
+    forward_widget!(inner; update, get);
+
+    fn input(&mut self, wrap: &mut WrapState, event: &InputEvent) -> bool {
+        let bounds = Rect::from_size(self.size);
+        if let InputEvent::MouseMove(p, _) = event {
+            self.hover = Some(*p).filter(|p| bounds.contains(*p));
+        }
+        // Positions outside of the view would land on parts of the child
+        // that aren't shown, except for releases ending a drag
+        let outside = event.position().map_or(false, |p| !bounds.contains(p));
+        let release = matches!(event, InputEvent::MouseUp(..));
+        let handled = if outside && !release {
+            false
+        } else {
+            event
+                .reverse_map_position(Self::matrix(wrap))
+                .map_or(false, |event| self.inner.input(&event))
+        };
+        match event {
+            InputEvent::MouseMove(p, _) => {
+                if let Some(last) = self.drag {
+                    wrap.set_pan(wrap.pan() + (*p - last));
+                    self.drag = Some(*p);
+                    return true;
+                }
+            }
+            InputEvent::MouseDown(b, p, _) if !handled && *b == self.pan_button && !outside => {
+                self.drag = Some(*p);
+                return true;
+            }
+            InputEvent::MouseUp(b, ..) if *b == self.pan_button && self.drag.is_some() => {
+                self.drag = None;
+                return true;
+            }
+            InputEvent::MouseScroll(y, _) if !handled => {
+                if let Some(p) = self.hover {
+                    self.zoom_at(wrap, p, self.zoom_step.powi(*y));
+                    return true;
+                }
+            }
+            _ => {}
+        }
+        handled
+    }
+
+    fn size(&mut self, _wrap: &mut WrapState) -> (LayoutSize, bool) {
+        let (mut size, changed) = self.inner.size();
+        // The view can be any size, as the child can be panned into view
+        size.width.min = 0.0;
+        size.height.min = 0.0;
+        (size, changed)
+    }
+
+    fn set_size(&mut self, _wrap: &mut WrapState, size: Size) {
+        self.size = size;
+        let layout = self.inner.state.layout_size();
+        self.inner.set_size(Size::new(
+            layout.width.min.max(size.width),
+            layout.height.min.max(size.height),
+        ));
+    }
+
+    fn draw(&mut self, wrap: &mut WrapState, canvas: &mut Canvas) {
+        canvas.save();
+        canvas.clip_rect(Rect::from_size(self.size), None, None);
+        canvas.concat(&Self::matrix(wrap));
+        self.inner.draw(canvas);
+        canvas.restore();
+    }
+}
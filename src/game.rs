@@ -1,21 +1,138 @@
+mod axis;
+mod batch;
+mod bounds;
 mod builder;
 mod canvas;
+mod debug;
 mod default_font_set;
+mod focus;
 mod input;
+mod input_map;
+mod profile;
+mod registry;
+mod replay;
 mod runner;
+mod task;
 mod time;
+mod warm_up;
 
+use std::time::Duration;
+
+pub use axis::{Axes, AxisSettings, DeadZone, Stick};
+pub use batch::render_batch;
+pub use bounds::Bounds;
 pub use builder::Builder;
-pub use canvas::FontSet;
+pub use canvas::{FontRendering, FontSet};
+pub use debug::{DebugOptions, HitTest, PointerTrail, PointerTrailOptions};
+pub use focus::{Focus, FocusDirection};
 pub use input::{EventHandleResult, InputEvent, InputState};
-pub use runner::{Error, State, ID};
-pub use time::TimeState;
+pub use input_map::{Binding, InputMap};
+pub use profile::{Profiler, WidgetCost};
+pub use registry::Registry;
+pub use runner::{ClearMode, Error, FramePolicy, State, WindowPosition, ID};
+pub use task::{TaskError, TaskHandle, TaskPool};
+pub use time::{FrameStats, TimeState};
+
+pub use skulpin_renderer::CoordinateSystem;
+pub use skulpin_renderer_sdl2::sdl2::{
+    controller::Axis,
+    keyboard::{Keycode, Mod},
+    mouse::{MouseButton, SystemCursor},
+};
+
+/// A frame's worth of drawable state, see [`Game::snapshot`]. Closures
+/// that draw onto a canvas are snapshots.
+pub trait DrawSnapshot: 'static + Send {
+    fn draw(self: Box<Self>, canvas: &mut crate::skia::Canvas);
+}
+
+impl<F: 'static + Send + FnOnce(&mut crate::skia::Canvas)> DrawSnapshot for F {
+    fn draw(self: Box<Self>, canvas: &mut crate::skia::Canvas) {
+        (*self)(canvas)
+    }
+}
 
+/// The game driven by the [`Runner`](runner::Runner) on the game thread.
+///
+/// Each iteration of the game loop calls, in order:
+///
+/// 1. With a fixed update rate set, [`fixed_update`](Self::fixed_update)
+///    once for every step that has passed since the last iteration, which
+///    may be none
+/// 2. [`update`](Self::update)
+/// 3. [`input`](Self::input) for every replayed event, then every event
+///    sent with [`State::send_input`] before this step, like the
+///    characters typed on a
+///    [`VirtualKeyboard`](crate::framework::widgets::VirtualKeyboard), then
+///    every polled event, along with [`set_size`](Self::set_size) and
+///    [`on_resize`](Self::on_resize) for resizes and
+///    [`on_close_requested`](Self::on_close_requested) for close requests
+/// 4. [`late_update`](Self::late_update)
+/// 5. When a frame is due, [`pre_draw`](Self::pre_draw),
+///    [`post_process`](Self::post_process) and then
+///    [`snapshot`](Self::snapshot), falling back to [`draw`](Self::draw),
+///    and then [`draw_overlay`](Self::draw_overlay)
 pub trait Game: 'static + Send {
+    /// Called at the rate set with [`Builder::fixed_update_rate`], with the
+    /// same `dt` every time, for stepping physics and other
+    /// simulations deterministically. When the game falls behind, this is
+    /// called several times in a row to catch up. How far the next step has
+    /// come along is [`State::fixed_update_alpha`], for interpolating
+    /// between the last two steps when drawing.
+    #[allow(unused_variables)]
+    fn fixed_update(&mut self, dt: Duration) {}
+    /// Called once per iteration of the game loop, at a varying rate, for
+    /// work that should keep up with input and drawing, like the UI.
     fn update(&mut self);
+    /// Called after all input of this iteration has been handled, for work
+    /// that needs to see its effects, like a camera following the player.
+    fn late_update(&mut self) {}
+    /// Called right before [`draw`](Self::draw), only when a frame is about
+    /// to be drawn.
+    fn pre_draw(&mut self) {}
     fn draw(&mut self, canvas: &mut crate::skia::Canvas);
+    /// Draw on top of everything [`draw`](Self::draw) or the snapshot drew,
+    /// into the same frame, for things like frame rate counters and debug
+    /// readouts. Always called on the game thread, also when the frame
+    /// itself is drawn from a snapshot.
+    ///
+    /// The [`Framework`](crate::framework::Framework) draws its widgets in
+    /// `draw`, and its overlay and then the cursor here.
+    #[allow(unused_variables)]
+    fn draw_overlay(&mut self, canvas: &mut crate::skia::Canvas) {}
+    /// A filter to draw the whole frame through, for effects like color
+    /// grading, bloom or a CRT look. It applies to what
+    /// [`draw`](Self::draw) or the snapshot drew, but not to the overlay.
+    /// The [`Framework`](crate::framework::Framework) returns the filter
+    /// set with [`State::set_frame_filter`].
+    fn post_process(&mut self) -> Option<crate::skia::ImageFilter> {
+        None
+    }
+    /// Take what is needed to draw the current frame, so that it can be
+    /// drawn on a separate thread while the next update already runs.
+    /// Return `None` to draw on the game thread with [`draw`](Self::draw)
+    /// instead, which is the default.
+    ///
+    /// The snapshot is moved to the drawing thread as a whole, so it can't
+    /// see any changes the following updates make. That also means it has
+    /// no access to [`State`], which only lives on the game thread.
+    fn snapshot(&mut self) -> Option<Box<dyn DrawSnapshot>> {
+        None
+    }
     fn set_size(&mut self, size: crate::skia::Size);
-    fn input(&mut self, event: InputEvent);
+    /// Called after [`set_size`](Self::set_size) when the window is resized,
+    /// but not for the initial sizing on startup.
+    #[allow(unused_variables)]
+    fn on_resize(&mut self, old: crate::skia::Size, new: crate::skia::Size) {}
+    /// Return whether the event was used, which keeps the runner from
+    /// acting on it as well, like closing on the exit key.
+    fn input(&mut self, event: InputEvent) -> bool;
+    /// Called when the window's close button or the exit key is pressed.
+    /// Return `false` to keep running, for example to ask for confirmation
+    /// first, then call [`State::exit`] to close for real.
+    fn on_close_requested(&mut self) -> bool {
+        true
+    }
     fn close(&mut self);
     fn crash(&mut self, err: runner::Error);
 }
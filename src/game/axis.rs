@@ -0,0 +1,168 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use crate::skia::{scalar, Vector};
+use skulpin_renderer_sdl2::sdl2::controller::Axis;
+
+/// One of the two analog sticks of a controller
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Stick {
+    Left,
+    Right,
+}
+
+impl Stick {
+    fn axes(self) -> (Axis, Axis) {
+        match self {
+            Self::Left => (Axis::LeftX, Axis::LeftY),
+            Self::Right => (Axis::RightX, Axis::RightY),
+        }
+    }
+}
+
+/// A dead zone for a stick, by how far it is pushed in any direction.
+/// Unlike a dead zone per axis, this doesn't pull diagonal movement towards
+/// the axes.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct DeadZone {
+    /// How far the stick has to be pushed to register at all, from 0 to 1
+    pub inner: scalar,
+    /// How far counts as pushed all the way, as worn sticks may not reach 1
+    pub outer: scalar,
+}
+
+impl DeadZone {
+    pub fn new(inner: scalar, outer: scalar) -> Self {
+        Self { inner, outer }
+    }
+
+    /// Rescale a stick position so that movement starts at 0 past the inner
+    /// edge and reaches 1 at the outer edge
+    pub fn apply(&self, v: Vector) -> Vector {
+        let length = v.length();
+        if length <= self.inner || length == 0.0 {
+            return Vector::new(0.0, 0.0);
+        }
+        let range = (self.outer - self.inner).max(scalar::EPSILON);
+        let scaled = ((length - self.inner) / range).min(1.0);
+        v * (scaled / length)
+    }
+}
+
+/// How raw controller axes are cleaned up before
+/// [`State::axis`](super::State::axis) and
+/// [`State::stick`](super::State::stick) report them
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct AxisSettings {
+    /// The dead zone of single axes like the triggers, from 0 to 1
+    pub axis_dead_zone: scalar,
+    pub stick_dead_zone: DeadZone,
+    /// Follow the raw values with this time constant, trading a little lag
+    /// for less jitter. Off by default.
+    pub smoothing: Option<Duration>,
+}
+
+impl Default for AxisSettings {
+    fn default() -> Self {
+        Self {
+            axis_dead_zone: 0.1,
+            stick_dead_zone: DeadZone::new(0.15, 0.95),
+            smoothing: None,
+        }
+    }
+}
+
+/// The latest position of every controller axis.
+///
+/// Axes are kept for each controller, and with several connected the one
+/// pushed furthest is reported, so a controller lying at rest doesn't hide
+/// one in use.
+#[derive(Debug, Default, Clone)]
+pub struct Axes {
+    pub settings: AxisSettings,
+    /// As reported by SDL, scaled to -1..1, by controller instance ID
+    raw: HashMap<(u32, Axis), scalar>,
+    smoothed: HashMap<(u32, Axis), scalar>,
+}
+
+impl Axes {
+    pub fn new(settings: AxisSettings) -> Self {
+        Self {
+            settings,
+            ..Self::default()
+        }
+    }
+
+    pub(crate) fn set_raw(&mut self, controller: u32, axis: Axis, value: i16) {
+        let value = (value as scalar / i16::MAX as scalar).max(-1.0);
+        self.raw.insert((controller, axis), value);
+        if self.settings.smoothing.is_none() {
+            self.smoothed.insert((controller, axis), value);
+        }
+    }
+
+    /// Move the smoothed values towards the raw ones, once per update
+    pub(crate) fn smooth(&mut self, dt: Duration) {
+        let tau = match self.settings.smoothing {
+            Some(tau) if tau > Duration::ZERO => tau.as_secs_f32(),
+            _ => return,
+        };
+        let t = 1.0 - (-dt.as_secs_f32() / tau).exp();
+        for (axis, raw) in &self.raw {
+            let smoothed = self.smoothed.entry(*axis).or_insert(0.0);
+            *smoothed += (raw - *smoothed) * t;
+        }
+    }
+
+    /// Forget the positions of a controller, for when it is disconnected
+    pub(crate) fn remove(&mut self, controller: u32) {
+        self.raw.retain(|(c, _), _| *c != controller);
+        self.smoothed.retain(|(c, _), _| *c != controller);
+    }
+
+    /// An axis with its own dead zone applied. Sticks range from -1 to 1,
+    /// with down and right positive, and triggers from 0 to 1.
+    pub fn axis(&self, axis: Axis) -> scalar {
+        let value = self.value(axis);
+        let dead_zone = self.settings.axis_dead_zone;
+        if value.abs() <= dead_zone {
+            0.0
+        } else {
+            value.signum() * (value.abs() - dead_zone) / (1.0 - dead_zone).max(scalar::EPSILON)
+        }
+    }
+
+    /// A stick's position with the radial dead zone applied, no longer than 1
+    pub fn stick(&self, stick: Stick) -> Vector {
+        let (x, y) = stick.axes();
+        // Both axes have to come from the same controller
+        let v = self
+            .smoothed
+            .keys()
+            .filter(|&&(_, axis)| axis == x || axis == y)
+            .map(|&(c, _)| Vector::new(self.get(c, x), self.get(c, y)))
+            .fold(Vector::new(0.0, 0.0), |a, b| {
+                if b.length() > a.length() {
+                    b
+                } else {
+                    a
+                }
+            });
+        self.settings.stick_dead_zone.apply(v)
+    }
+
+    fn value(&self, axis: Axis) -> scalar {
+        self.smoothed
+            .iter()
+            .filter(|((_, a), _)| *a == axis)
+            .map(|(_, &v)| v)
+            .fold(0.0, |a, b| if b.abs() > a.abs() { b } else { a })
+    }
+
+    fn get(&self, controller: u32, axis: Axis) -> scalar {
+        self.smoothed
+            .get(&(controller, axis))
+            .copied()
+            .unwrap_or(0.0)
+    }
+}
@@ -0,0 +1,114 @@
+use std::sync::mpsc::channel;
+use std::sync::{Arc, Mutex};
+use std::thread::Builder as ThreadBuilder;
+
+use skulpin_renderer::LogicalSize;
+
+use super::input::InputState;
+use super::runner::Config;
+use super::State;
+use crate::framework::widgets::{Widget, Wrap};
+use crate::skia::{Color, Data, Image, ImageInfo, Size, Surface};
+
+/// Draw a widget for each item into an image, without opening a window, for
+/// asset pipelines that generate thumbnails and the like.
+///
+/// The items are shared out between `threads` worker threads. Each worker
+/// has a [`State`] of its own, so the widgets can use it like they would in
+/// a game, but it isn't shared between items of the same worker either:
+/// every widget is built, laid out to `size`, updated once and drawn. The
+/// images are returned in the order of the items, with `None` for those
+/// that couldn't be drawn, like when `size` is empty.
+pub fn render_batch<I, W, F>(
+    items: Vec<I>,
+    size: Size,
+    threads: usize,
+    build: F,
+) -> Vec<Option<Image>>
+where
+    I: 'static + Send,
+    W: Widget,
+    F: 'static + Send + Sync + Fn(I) -> W,
+{
+    let count = items.len();
+    let queue = Arc::new(Mutex::new(items.into_iter().enumerate()));
+    let build = Arc::new(build);
+    let (tx, rx) = channel();
+    let workers: Vec<_> = (0..threads.max(1).min(count.max(1)))
+        .map(|i| {
+            let queue = queue.clone();
+            let build = build.clone();
+            let tx = tx.clone();
+            let worker = ThreadBuilder::new().name(format!("tachibana-batch-{}", i));
+            let spawned = worker.spawn(move || {
+                let config = Config {
+                    task_threads: 1,
+                    ..Config::default()
+                };
+                let window_size = LogicalSize {
+                    width: size.width.ceil() as _,
+                    height: size.height.ceil() as _,
+                };
+                State::init(InputState::new(window_size, None), &config);
+                loop {
+                    // The lock is let go of before drawing
+                    let next = queue.lock().map(|mut q| q.next());
+                    let (index, item) = match next {
+                        Ok(Some(next)) => next,
+                        _ => break,
+                    };
+                    let pixels = render(build(item), size);
+                    if tx.send((index, pixels)).is_err() {
+                        break;
+                    }
+                }
+                State::with_mut(|x| x.task_pool.shutdown());
+            });
+            spawned.expect("Failed to spawn batch rendering thread")
+        })
+        .collect();
+    drop(tx);
+
+    let mut images: Vec<Option<Image>> = (0..count).map(|_| None).collect();
+    for (index, pixels) in rx {
+        // Images can't be sent between threads, so workers send their
+        // pixels instead.
+        images[index] = pixels.and_then(|(width, height, pixels)| {
+            let info = ImageInfo::new_n32_premul((width, height), None);
+            let row_bytes = info.min_row_bytes();
+            Image::from_raster_data(&info, Data::new_copy(&pixels), row_bytes)
+        });
+    }
+    for worker in workers {
+        if worker.join().is_err() {
+            eprintln!("A batch rendering thread panicked");
+        }
+    }
+    images
+}
+
+/// Draw a widget, returning the size and pixels of the image
+fn render<W: Widget>(widget: W, size: Size) -> Option<(i32, i32, Vec<u8>)> {
+    let (width, height) = (size.width.ceil() as i32, size.height.ceil() as i32);
+    if width <= 0 || height <= 0 {
+        return None;
+    }
+    let mut widget = Wrap::new(widget);
+    widget.size();
+    widget.set_size(size);
+    widget.update();
+
+    let mut surface = Surface::new_raster_n32_premul((width, height))?;
+    let canvas = surface.canvas();
+    canvas.clear(Color::TRANSPARENT);
+    widget.draw(canvas);
+
+    let info = ImageInfo::new_n32_premul((width, height), None);
+    let row_bytes = info.min_row_bytes();
+    let mut pixels = vec![0; row_bytes * height as usize];
+    if canvas.read_pixels(&info, &mut pixels, row_bytes, (0, 0)) {
+        Some((width, height, pixels))
+    } else {
+        None
+    }
+}
@@ -0,0 +1,41 @@
+use std::collections::HashMap;
+
+use super::ID;
+use crate::skia::Rect;
+
+/// Where widgets were drawn, in canvas coordinates, by [`ID`].
+///
+/// Bounds are recorded as widgets are drawn, which makes those drawn later
+/// in a frame unknown to those drawn earlier. To bridge that, the bounds of
+/// the previous frame are kept around until a widget is drawn again.
+#[derive(Default)]
+pub struct Bounds {
+    current: HashMap<ID, Rect>,
+    previous: HashMap<ID, Rect>,
+}
+
+impl Bounds {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&mut self, id: ID, bounds: Rect) {
+        self.current.insert(id, bounds);
+    }
+
+    /// The bounds from this frame if the widget was drawn already, otherwise
+    /// from the last one. Widgets that weren't drawn in either are unknown.
+    pub fn get(&self, id: ID) -> Option<Rect> {
+        self.current
+            .get(&id)
+            .or_else(|| self.previous.get(&id))
+            .copied()
+    }
+
+    /// Start recording a new frame, forgetting widgets that weren't drawn in
+    /// the last one.
+    pub(crate) fn next_frame(&mut self) {
+        std::mem::swap(&mut self.current, &mut self.previous);
+        self.current.clear();
+    }
+}
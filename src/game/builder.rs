@@ -1,11 +1,20 @@
-use skulpin_renderer::{CoordinateSystem, LogicalSize, PresentMode, RendererBuilder};
+use std::path::PathBuf;
+use std::time::Duration;
 
-use super::{runner::Runner, Game};
+use skulpin_renderer::{CoordinateSystem, LogicalSize};
+
+use super::{
+    runner::{ClearMode, Config, FramePolicy, Runner, WindowPosition},
+    AxisSettings, Error, FontRendering, Game, InputMap, Keycode,
+};
+use crate::framework::theme::Theme;
+use crate::framework::widgets::Insets;
+use crate::skia::Size;
 
 pub struct Builder<'a> {
     inner_size: LogicalSize,
     window_title: &'a str,
-    renderer_builder: RendererBuilder,
+    config: Config,
 }
 
 impl<'a> Default for Builder<'a> {
@@ -20,7 +29,7 @@ impl<'a> Builder<'a> {
         Self {
             inner_size: LogicalSize::new(1280, 720).into(),
             window_title: "Tachibana",
-            renderer_builder: RendererBuilder::new().use_vulkan_debug_layer(false),
+            config: Config::default(),
         }
     }
 
@@ -36,33 +45,261 @@ impl<'a> Builder<'a> {
         self
     }
 
+    /// Open the window with its top left corner at the given position on the display, in pixels.
+    /// The position is relative to the display chosen with [`on_monitor`](Self::on_monitor), or
+    /// to the primary display, and kept within it so the window can't open off screen.
+    pub fn window_position(mut self, x: i32, y: i32) -> Self {
+        self.config.window_position = WindowPosition::At(x, y);
+        self
+    }
+
+    /// Open the window in the middle of the display
+    pub fn window_centered(mut self) -> Self {
+        self.config.window_position = WindowPosition::Centered;
+        self
+    }
+
+    /// Open the window on the display with the given index, centered unless a position is given
+    /// with [`window_position`](Self::window_position). The primary display is used if there is
+    /// no display with that index.
+    pub fn on_monitor(mut self, index: usize) -> Self {
+        self.config.monitor = Some(index);
+        self
+    }
+
     /// Name of the app. This is passed into the vulkan layer. I believe it can hint things to the
     /// vulkan driver, but it's unlikely this makes a real difference. Still a good idea to set this
     /// to something meaningful though.
     pub fn app_name(mut self, app_name: std::ffi::CString) -> Self {
-        self.renderer_builder = self.renderer_builder.app_name(app_name);
+        self.config.renderer.app_name = Some(app_name);
         self
     }
 
-    /// Determine the coordinate system to use for the canvas. This can be overridden by using the
-    /// canvas sizer passed into the draw callback
+    /// Determine the coordinate system to use for the canvas. This can be changed later with
+    /// [`State::set_coordinate_system`](super::State::set_coordinate_system).
     pub fn coordinate_system(mut self, coordinate_system: CoordinateSystem) -> Self {
-        self.renderer_builder = self.renderer_builder.coordinate_system(coordinate_system);
+        self.config.renderer.coordinate_system = coordinate_system;
+        self
+    }
+
+    /// Multi-sample anti-aliasing with the given number of samples per pixel, smoothing the edges
+    /// of everything drawn without enabling anti-aliasing on each paint. Counts are rounded down
+    /// to 1, 2, 4 or 8, and 1 disables MSAA, which is the default. Frames are then drawn to an
+    /// offscreen surface with that many samples and resolved onto the window, which costs a
+    /// copy per frame. Without a GPU surface that supports the count, frames are drawn without
+    /// MSAA.
+    pub fn msaa(mut self, samples: u32) -> Self {
+        self.config.msaa = match samples {
+            0..=1 => 1,
+            2..=3 => 2,
+            4..=7 => 4,
+            _ => 8,
+        };
+        self
+    }
+
+    /// Whether to wait for the display's vertical blank before presenting frames. Off by default.
+    /// This can be changed later with [`State::set_vsync`](super::State::set_vsync).
+    pub fn vsync(mut self, vsync: bool) -> Self {
+        self.config.renderer.vsync = vsync;
+        self
+    }
+
+    /// The theme to start with, which can be changed later with
+    /// [`State::set_theme`](super::State::set_theme).
+    pub fn default_theme(mut self, theme: Theme) -> Self {
+        self.config.theme = theme;
+        self
+    }
+
+    /// Pick between a light and a dark theme to match the operating system's color scheme, and
+    /// keep switching between them while the app runs when the OS setting changes. Where the
+    /// color scheme can't be detected, the dark theme is used.
+    pub fn follow_color_scheme(mut self, light: Theme, dark: Theme) -> Self {
+        self.config.follow_color_scheme = Some((light, dark));
+        self
+    }
+
+    /// Request closing the app when the given key is pressed, as if the window's close button
+    /// was clicked. The game sees the key press first, and the app only closes if the game
+    /// doesn't handle it.
+    pub fn exit_on_key(mut self, key: Keycode) -> Self {
+        self.config.exit_key = Some(key);
+        self
+    }
+
+    /// Draw the game at a fixed resolution, scaled to fit the window while keeping its aspect
+    /// ratio. The remaining space is filled with black bars, and mouse positions are reported in
+    /// design resolution coordinates.
+    pub fn design_resolution(mut self, size: Size) -> Self {
+        self.config.design_resolution = Some(size);
+        self
+    }
+
+    /// How text is anti-aliased and hinted by default. Grayscale anti-aliasing with normal hinting
+    /// unless set, see [`FontRendering`] for the options.
+    pub fn font_rendering(mut self, rendering: FontRendering) -> Self {
+        self.config.font_rendering = rendering;
+        self
+    }
+
+    /// Keep the user interface this far from the edges of the window, for TVs that crop the
+    /// picture with overscan or screens with a notch. [`Framework`](crate::framework::Framework)
+    /// lays its widgets out within the remaining area. The insets are in the same units as the
+    /// size given to [`Game::set_size`], and can be changed later with
+    /// [`State::set_safe_area`](super::State::set_safe_area). They aren't detected from the
+    /// platform, as SDL doesn't report them.
+    pub fn safe_area_insets(mut self, insets: Insets) -> Self {
+        self.config.safe_area = insets;
+        self
+    }
+
+    /// The most a late frame is allowed to borrow from the next one. Lower values recover from
+    /// stalls without a burst of frames, higher values keep the average frame rate steadier.
+    pub fn max_frame_lag(mut self, lag: Duration) -> Self {
+        self.config.max_frame_lag = lag;
+        self
+    }
+
+    /// What to do when the next frame is due while the renderer is still busy with the last one.
+    /// Frames are normally drawn once the last one was presented. Drawing the new frame anyway
+    /// and dropping it if the renderer is still busy, the default, keeps latency low but shows as
+    /// stutter when the renderer can't keep up. Blocking for a while until the last frame was
+    /// presented gives smoother output, but input is handled later while waiting.
+    pub fn frame_policy(mut self, policy: FramePolicy) -> Self {
+        self.config.frame_policy = policy;
+        self
+    }
+
+    /// The action bindings [`State::action_pressed`](super::State::action_pressed) starts with,
+    /// usually the defaults or loaded from settings. They can be changed later with
+    /// [`State::set_input_map`](super::State::set_input_map).
+    pub fn input_map(mut self, map: InputMap) -> Self {
+        self.config.input_map = map;
         self
     }
 
-    /// Start the app.
-    pub fn run<F, T>(self, game: F)
+    /// The dead zones and smoothing applied to controller axes before
+    /// [`State::axis`](super::State::axis) and [`State::stick`](super::State::stick) report them.
+    pub fn axis_settings(mut self, settings: AxisSettings) -> Self {
+        self.config.axis_settings = settings;
+        self
+    }
+
+    /// Record every input event the game receives to the given file, to be replayed later with
+    /// [`replay_input`](Self::replay_input).
+    pub fn record_input(mut self, path: impl Into<PathBuf>) -> Self {
+        self.config.record_input = Some(path.into());
+        self
+    }
+
+    /// Feed input events from a file written by [`record_input`](Self::record_input) instead of
+    /// the mouse and keyboard. Events are delivered at the same time since startup they were
+    /// recorded at.
+    pub fn replay_input(mut self, path: impl Into<PathBuf>) -> Self {
+        self.config.replay_input = Some(path.into());
+        self
+    }
+
+    /// Call [`Game::fixed_update`] this many times per second, independently of the frame rate.
+    /// It isn't called at all unless this is set. Panics unless `rate` is above 0.
+    pub fn fixed_update_rate(mut self, rate: f64) -> Self {
+        assert!(
+            rate > 0.0,
+            "The fixed update rate must be above 0, not {}",
+            rate
+        );
+        self.config.fixed_update_rate = Some(rate);
+        self
+    }
+
+    /// Draw at the given frame rate instead of following the refresh rate of the display the
+    /// window is on, which is the default. Frames are still never drawn faster than they are
+    /// presented. Panics unless `fps` is above 0.
+    pub fn frame_rate(mut self, fps: f64) -> Self {
+        assert!(fps > 0.0, "The frame rate must be above 0, not {}", fps);
+        self.config.frame_rate = Some(fps);
+        self
+    }
+
+    /// Drop to the given frame rate after receiving no input for the given duration, to save
+    /// power on always-on displays. The normal frame rate is restored on the next input event.
+    /// Panics unless `fps` is above 0.
+    pub fn idle_fps(mut self, fps: f64, after: Duration) -> Self {
+        assert!(
+            fps > 0.0,
+            "The idle frame rate must be above 0, not {}",
+            fps
+        );
+        self.config.idle_frame_rate = Some((fps, after));
+        self
+    }
+
+    /// Whether widgets only get the last of several mouse moves that arrive together, which is
+    /// the default. The whole path is still available from
+    /// [`State::mouse_path`](super::State::mouse_path).
+    pub fn coalesce_mouse_motion(mut self, coalesce: bool) -> Self {
+        self.config.coalesce_mouse_motion = coalesce;
+        self
+    }
+
+    /// The number of worker threads used for tasks spawned with
+    /// [`State::spawn_task`](super::State::spawn_task). Defaults to 4.
+    pub fn task_threads(mut self, threads: usize) -> Self {
+        self.config.task_threads = threads;
+        self
+    }
+
+    /// Draw a range of common paints, shapes and effects offscreen before the first frame, so the
+    /// GPU pipelines they need are compiled by then instead of causing a hitch the first time each
+    /// is used. Makes startup a little slower. Off by default.
+    pub fn warm_up(mut self, warm_up: bool) -> Self {
+        self.config.warm_up = warm_up;
+        self
+    }
+
+    /// Skip drawing widgets that are entirely outside of what is being drawn, like the rows of
+    /// a long list that are scrolled out of view, along with everything inside them. Widgets
+    /// that draw past their bounds can widen or turn this off for themselves with
+    /// [`WrapState::set_cull_margin`](crate::framework::widgets::WrapState::set_cull_margin).
+    /// Skipped widgets still take part in focus, accessibility and hit testing. On by default.
+    pub fn cull_offscreen(mut self, cull: bool) -> Self {
+        self.config.cull_offscreen = cull;
+        self
+    }
+
+    /// What every frame is cleared to before the game draws it. The window is presented as opaque
+    /// on every platform, so clearing to transparent doesn't let the desktop show through, see
+    /// [`ClearMode::Transparent`]. Clears to a dark grey by default.
+    pub fn clear_mode(mut self, mode: ClearMode) -> Self {
+        self.config.clear = mode;
+        self
+    }
+
+    /// Ask the OS to run the game and draw threads at a high priority, for systems where other
+    /// work starves them and causes stutter. This can need privileges the app doesn't have, and
+    /// then only prints a warning. Off by default.
+    pub fn high_priority_threads(mut self, high_priority: bool) -> Self {
+        self.config.high_priority_threads = high_priority;
+        self
+    }
+
+    /// Start the app, returning once it exits. Failing to start, like when there's no GPU that
+    /// can run the renderer, is returned as an error instead of panicking.
+    pub fn run<F, T>(self, game: F) -> Result<(), Error>
+    where
+        F: 'static + Send + FnOnce() -> T,
+        T: Game,
+    {
+        Runner::run(game, self.inner_size, self.window_title, self.config)
+    }
+
+    /// Like [`run`](Self::run), but panics if the app can't be started.
+    pub fn run_or_panic<F, T>(self, game: F)
     where
         F: 'static + Send + FnOnce() -> T,
         T: Game,
     {
-        Runner::run(
-            game,
-            self.inner_size,
-            self.window_title,
-            self.renderer_builder
-                .present_mode_priority(vec![PresentMode::Immediate]),
-        )
+        Runner::run_or_panic(game, self.inner_size, self.window_title, self.config)
     }
 }
@@ -1,5 +1,5 @@
 use crate::framework::widgets::{Font, FontStyle};
-use crate::skia::Font as SkFont;
+use crate::skia::{font::Edging, Font as SkFont, FontHinting};
 
 pub trait FontSet {
     fn get(&self, font: &Font, style: &FontStyle) -> SkFont {
@@ -10,3 +10,69 @@ pub trait FontSet {
 
     fn get_default(&self, style: &FontStyle) -> SkFont;
 }
+
+/// How glyphs are rasterized. What looks best depends on the display's
+/// density and on what the text is drawn over: subpixel anti-aliasing is
+/// sharper on low density displays, but fringes on transparent or moving
+/// backgrounds.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct FontRendering {
+    pub edging: Edging,
+    pub hinting: FontHinting,
+    /// Place glyphs at fractional positions rather than on whole pixels,
+    /// for more even spacing
+    pub subpixel: bool,
+    /// Snap the baseline to the pixel grid, keeping horizontal text crisp
+    pub baseline_snap: bool,
+}
+
+impl FontRendering {
+    /// Sharp, unsmoothed glyphs on whole pixels, for pixel art fonts
+    pub fn pixel() -> Self {
+        Self {
+            edging: Edging::Alias,
+            hinting: FontHinting::Full,
+            subpixel: false,
+            baseline_snap: true,
+        }
+    }
+
+    pub fn with_edging(mut self, edging: Edging) -> Self {
+        self.edging = edging;
+        self
+    }
+
+    pub fn with_hinting(mut self, hinting: FontHinting) -> Self {
+        self.hinting = hinting;
+        self
+    }
+
+    pub fn with_subpixel(mut self, subpixel: bool) -> Self {
+        self.subpixel = subpixel;
+        self
+    }
+
+    pub fn with_baseline_snap(mut self, baseline_snap: bool) -> Self {
+        self.baseline_snap = baseline_snap;
+        self
+    }
+
+    pub fn apply(&self, font: &mut SkFont) {
+        font.set_edging(self.edging);
+        font.set_hinting(self.hinting);
+        font.set_subpixel(self.subpixel);
+        font.set_baseline_snap(self.baseline_snap);
+    }
+}
+
+/// Skia's defaults: grayscale anti-aliasing with normal hinting
+impl Default for FontRendering {
+    fn default() -> Self {
+        Self {
+            edging: Edging::AntiAlias,
+            hinting: FontHinting::Normal,
+            subpixel: false,
+            baseline_snap: true,
+        }
+    }
+}
@@ -0,0 +1,190 @@
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use super::{InputEvent, ID};
+use crate::framework::widgets::shapes;
+use crate::skia::{scalar, Canvas, Contains, Paint, Point, Rect};
+use crate::utils::PaintUtils;
+
+/// Debugging aids drawn by the framework, all disabled by default
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct DebugOptions {
+    /// Outline the allocated bounds and minimum size of every widget
+    pub layout_bounds: bool,
+    /// Label the outlines drawn by [`layout_bounds`](Self::layout_bounds) with their sizes
+    pub layout_labels: bool,
+    /// Time every widget's drawing and layout into the
+    /// [`Profiler`](super::Profiler). While off, this costs no more than
+    /// checking the flag.
+    pub profile_widgets: bool,
+    /// Outline the top-most widget under the mouse and label it with its
+    /// type and [`ID`], also printing them when they change. For finding
+    /// out which widget a click goes to.
+    pub hit_test: bool,
+    /// Draw a grid with lines this far apart over everything, for lining
+    /// things up by eye. See also [`Snapping::with_debug_grid`].
+    ///
+    /// [`Snapping::with_debug_grid`]: crate::framework::snapping::Snapping::with_debug_grid
+    pub grid: Option<scalar>,
+    /// Draw rulers along the top and left edges, marked in pixels
+    pub rulers: bool,
+    /// Draw the path the mouse took as a fading trail and a ripple for
+    /// every click, for recording demos and seeing where touches land. See
+    /// [`PointerTrail`].
+    pub pointer_trail: Option<PointerTrailOptions>,
+}
+
+/// How [`DebugOptions::pointer_trail`] looks
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct PointerTrailOptions {
+    /// How many of the latest mouse positions make up the trail
+    pub length: usize,
+    /// How long it takes for a part of the trail or a ripple to fade out
+    pub fade: Duration,
+    /// How far a click's ripple grows before it's gone
+    pub ripple_radius: scalar,
+    /// Overrides the theme's anti-aliasing default when set
+    pub anti_alias: Option<bool>,
+}
+
+impl Default for PointerTrailOptions {
+    fn default() -> Self {
+        Self {
+            length: 64,
+            fade: Duration::from_millis(600),
+            ripple_radius: 32.0,
+            anti_alias: None,
+        }
+    }
+}
+
+/// The recent mouse positions and clicks for
+/// [`DebugOptions::pointer_trail`], with when they happened by
+/// [`State::elapsed`](super::State::elapsed).
+///
+/// Nothing is recorded while the option is off, and what was recorded
+/// before fades out on its own.
+#[derive(Debug, Default)]
+pub struct PointerTrail {
+    points: VecDeque<(Point, Duration)>,
+    ripples: VecDeque<(Point, Duration)>,
+}
+
+impl PointerTrail {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The positions making up the trail, oldest first
+    pub fn points(&self) -> impl Iterator<Item = &(Point, Duration)> {
+        self.points.iter()
+    }
+
+    /// Where the mouse was pressed, oldest first
+    pub fn clicks(&self) -> impl Iterator<Item = &(Point, Duration)> {
+        self.ripples.iter()
+    }
+
+    pub(crate) fn record(
+        &mut self,
+        event: &InputEvent,
+        now: Duration,
+        options: &PointerTrailOptions,
+    ) {
+        match event {
+            InputEvent::MouseMove(p, _) => {
+                self.points.push_back((*p, now));
+                while self.points.len() > options.length {
+                    self.points.pop_front();
+                }
+            }
+            InputEvent::MouseDown(_, p, _) => self.ripples.push_back((*p, now)),
+            _ => {}
+        }
+    }
+
+    /// Draw the trail and ripples as of `now`, returning whether anything
+    /// is still fading out
+    pub(crate) fn draw(
+        &mut self,
+        canvas: &mut Canvas,
+        now: Duration,
+        options: &PointerTrailOptions,
+    ) -> bool {
+        let fade = options.fade.as_secs_f32().max(scalar::EPSILON);
+        let age = |t: Duration| now.checked_sub(t).unwrap_or(Duration::ZERO).as_secs_f32() / fade;
+        while self.points.front().map_or(false, |(_, t)| age(*t) >= 1.0) {
+            self.points.pop_front();
+        }
+        while self.ripples.front().map_or(false, |(_, t)| age(*t) >= 1.0) {
+            self.ripples.pop_front();
+        }
+
+        let paint = Paint::new_color4f(0.0, 1.0, 1.0, 1.0).stroke_style();
+        let mut paint = shapes::anti_aliased(&paint, options.anti_alias).into_owned();
+        paint.set_stroke_width(3.0);
+        // Each segment fades with the newer of its ends, so the trail
+        // thins out towards where the mouse was longest ago
+        for (a, b) in self.points.iter().zip(self.points.iter().skip(1)) {
+            paint.set_alpha_f(1.0 - age(b.1));
+            canvas.draw_line(a.0, b.0, &paint);
+        }
+        paint.set_stroke_width(2.0);
+        for (p, t) in &self.ripples {
+            let t = age(*t);
+            paint.set_alpha_f(1.0 - t);
+            canvas.draw_circle(*p, options.ripple_radius * t, &paint);
+        }
+        !self.points.is_empty() || !self.ripples.is_empty()
+    }
+}
+
+/// Finds the widget under the mouse for
+/// [`DebugOptions::hit_test`](DebugOptions::hit_test).
+///
+/// Widgets are drawn back to front, so the last one drawn with the mouse in
+/// its bounds is the one on top. As that's only known once the frame is
+/// done, the highlight is drawn a frame late.
+#[derive(Debug, Default)]
+pub struct HitTest {
+    candidate: Option<(ID, &'static str, Rect)>,
+    hovered: Option<(ID, &'static str, Rect)>,
+}
+
+impl HitTest {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The widget found under the mouse in the last frame, with its type
+    /// name and bounds in window coordinates
+    pub fn hovered(&self) -> Option<(ID, &'static str, Rect)> {
+        self.hovered
+    }
+
+    pub(crate) fn is_hovered(&self, id: ID) -> bool {
+        self.hovered.map_or(false, |(hovered, _, _)| hovered == id)
+    }
+
+    /// Note a widget being drawn, with the mouse at `mouse` in window
+    /// coordinates
+    pub(crate) fn visit(&mut self, id: ID, name: &'static str, bounds: Rect, mouse: Point) {
+        if bounds.contains(mouse) {
+            self.candidate = Some((id, name, bounds));
+        }
+    }
+
+    /// Finish a frame, returning whether a different widget is under the
+    /// mouse now
+    pub(crate) fn next_frame(&mut self) -> bool {
+        let found = self.candidate.take();
+        let changed = found.map(|(id, ..)| id) != self.hovered.map(|(id, ..)| id);
+        self.hovered = found;
+        if changed {
+            if let Some((id, name, bounds)) = found {
+                println!("Under the mouse: {} {:?} at {:?}", name, id, bounds);
+            }
+        }
+        changed
+    }
+}
@@ -0,0 +1,136 @@
+use super::ID;
+use crate::skia::{Point, Rect};
+
+/// A direction to move the focus in with the arrow keys
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum FocusDirection {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+/// Which widget has keyboard focus, and which widgets can take it.
+///
+/// Focusable widgets register themselves with their bounds as they are
+/// drawn, so the order they are drawn in is the Tab order, and arrow keys
+/// move between them by where they are on screen. Like [`Bounds`], the
+/// widgets of the previous frame are used until the current one is done.
+///
+/// [`Bounds`]: super::Bounds
+#[derive(Default)]
+pub struct Focus {
+    focused: Option<ID>,
+    visible: bool,
+    current: Vec<(ID, Rect)>,
+    previous: Vec<(ID, Rect)>,
+}
+
+impl Focus {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn focused(&self) -> Option<ID> {
+        self.focused
+    }
+
+    /// Whether the focus should be shown, which is only after it was moved
+    /// with the keyboard
+    pub fn is_visible(&self) -> bool {
+        self.visible && self.focused.is_some()
+    }
+
+    /// Focus a widget. `visible` decides whether a focus ring is drawn,
+    /// which should only be the case for moves made with the keyboard.
+    pub fn set(&mut self, id: Option<ID>, visible: bool) {
+        self.focused = id;
+        self.visible = visible;
+    }
+
+    /// Whether any widget can take the focus
+    pub fn has_widgets(&self) -> bool {
+        !self.current.is_empty() || !self.previous.is_empty()
+    }
+
+    pub fn register(&mut self, id: ID, bounds: Rect) {
+        self.current.push((id, bounds));
+    }
+
+    /// Move to the next widget in Tab order, or the previous one if
+    /// `reverse`, wrapping around at the ends
+    pub fn next(&mut self, reverse: bool) {
+        let widgets = self.widgets();
+        if widgets.is_empty() {
+            return;
+        }
+        let index = self
+            .focused
+            .and_then(|id| widgets.iter().position(|(i, _)| *i == id));
+        let n = widgets.len();
+        let next = match (index, reverse) {
+            (Some(i), false) => (i + 1) % n,
+            (Some(i), true) => (i + n - 1) % n,
+            (None, false) => 0,
+            (None, true) => n - 1,
+        };
+        let id = widgets[next].0;
+        self.set(Some(id), true);
+    }
+
+    /// Move to the nearest widget in the given direction. Widgets straight
+    /// ahead are preferred over ones that are closer but off to the side.
+    pub fn towards(&mut self, direction: FocusDirection) {
+        let widgets = self.widgets();
+        let from = match self
+            .focused
+            .and_then(|id| widgets.iter().find(|(i, _)| *i == id))
+        {
+            Some((_, bounds)) => *bounds,
+            None => return self.next(false),
+        };
+        let from_center = from.center();
+        let best = widgets
+            .iter()
+            .filter(|(id, _)| Some(*id) != self.focused)
+            .filter_map(|(id, bounds)| {
+                let to = bounds.center();
+                let (ahead, side) = Self::split(direction, from_center, to);
+                if ahead <= 0.0 {
+                    None
+                } else {
+                    Some((*id, ahead + side.abs() * 2.0))
+                }
+            })
+            .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal));
+        if let Some((id, _)) = best {
+            self.set(Some(id), true);
+        }
+    }
+
+    /// The distance from one point to another along the direction, and
+    /// across it
+    fn split(direction: FocusDirection, from: Point, to: Point) -> (f32, f32) {
+        let d = to - from;
+        match direction {
+            FocusDirection::Up => (-d.y, d.x),
+            FocusDirection::Down => (d.y, d.x),
+            FocusDirection::Left => (-d.x, d.y),
+            FocusDirection::Right => (d.x, d.y),
+        }
+    }
+
+    /// The focusable widgets of the last complete frame
+    fn widgets(&self) -> Vec<(ID, Rect)> {
+        if self.previous.is_empty() {
+            self.current.clone()
+        } else {
+            self.previous.clone()
+        }
+    }
+
+    pub(crate) fn next_frame(&mut self) {
+        std::mem::swap(&mut self.current, &mut self.previous);
+        self.current.clear();
+    }
+}
@@ -1,38 +1,82 @@
+use std::collections::HashSet;
+use std::time::{Duration, Instant};
+
+use super::axis::Axes;
 use crate::skia;
-use skia::{Matrix, Point, Size};
+use crate::utils::BottomRight;
+use skia::{scalar, Matrix, Point, Size, Vector};
 use skulpin_renderer::LogicalSize;
 use skulpin_renderer_sdl2::sdl2::{
+    controller::Button,
     event::{Event as Sdl2Event, WindowEvent},
-    keyboard::Keycode,
+    keyboard::{Keycode, Mod},
     mouse::MouseButton,
 };
 
+/// An input event, as passed to [`Game::input`](super::Game::input) and the
+/// widgets.
+///
+/// The last field of every event is when it happened, on the same clock as
+/// [`State::elapsed`](super::State::elapsed). For events from the window this
+/// is SDL's timestamp, so it is more precise than the time the event is
+/// handled at, which is only on the next update. This is what to judge the
+/// timing of beat input or the speed of a gesture by.
 #[derive(Debug, PartialEq, Clone, Copy)]
 pub enum InputEvent {
-    KeyDown(Keycode),
-    KeyUp(Keycode),
-    MouseMove(Point),
-    MouseDown(MouseButton, Point),
-    MouseUp(MouseButton, Point),
-    MouseScroll(i32),
+    KeyDown(Keycode, Duration),
+    KeyUp(Keycode, Duration),
+    MouseMove(Point, Duration),
+    MouseDown(MouseButton, Point, Duration),
+    MouseUp(MouseButton, Point, Duration),
+    MouseScroll(i32, Duration),
+    /// A character typed, with the keyboard layout and shift already
+    /// applied. It comes after the `KeyDown` of the key that typed it.
+    Char(char, Duration),
+    /// A controller button pressed, on the controller with the given
+    /// instance ID
+    ControllerButtonDown(u32, Button, Duration),
+    ControllerButtonUp(u32, Button, Duration),
 }
 
 impl InputEvent {
     pub fn position(&self) -> Option<Point> {
         Some(match self {
-            Self::MouseMove(p) => *p,
-            Self::MouseDown(_, p) => *p,
-            Self::MouseUp(_, p) => *p,
+            Self::MouseMove(p, _) => *p,
+            Self::MouseDown(_, p, _) => *p,
+            Self::MouseUp(_, p, _) => *p,
             _ => return None,
         })
     }
 
+    /// The mouse button pressed or released, if this is a button event
+    pub fn button(&self) -> Option<MouseButton> {
+        match self {
+            Self::MouseDown(b, ..) | Self::MouseUp(b, ..) => Some(*b),
+            _ => None,
+        }
+    }
+
+    /// When the event happened, see [`InputEvent`]
+    pub fn time(&self) -> Duration {
+        match self {
+            Self::KeyDown(_, t)
+            | Self::KeyUp(_, t)
+            | Self::MouseMove(_, t)
+            | Self::MouseDown(_, _, t)
+            | Self::MouseUp(_, _, t)
+            | Self::MouseScroll(_, t)
+            | Self::Char(_, t)
+            | Self::ControllerButtonDown(_, _, t)
+            | Self::ControllerButtonUp(_, _, t) => *t,
+        }
+    }
+
     pub fn reverse_map_position(&self, matrix: Matrix) -> Option<Self> {
         let m = matrix.invert()?;
         Some(match self {
-            Self::MouseMove(p) => Self::MouseMove(m.map_point(*p)),
-            Self::MouseDown(b, p) => Self::MouseDown(*b, m.map_point(*p)),
-            Self::MouseUp(b, p) => Self::MouseUp(*b, m.map_point(*p)),
+            Self::MouseMove(p, t) => Self::MouseMove(m.map_point(*p), *t),
+            Self::MouseDown(b, p, t) => Self::MouseDown(*b, m.map_point(*p), *t),
+            Self::MouseUp(b, p, t) => Self::MouseUp(*b, m.map_point(*p), *t),
             _ => *self,
         })
     }
@@ -40,9 +84,34 @@ impl InputEvent {
 
 pub struct InputState {
     pub window_size: Size,
-    pub keys: [bool; Self::KEYBOARD_BUTTON_COUNT],
+    /// Pixels per window coordinate, 2 on a display scaled to 200%
+    pub scale_factor: scalar,
+    /// The fixed size the game is drawn at, letterboxed into the window.
+    /// When `None`, the canvas always matches the window size.
+    pub design_resolution: Option<Size>,
+    /// Keys that are currently held down
+    pub keys: HashSet<Keycode>,
+    /// Modifier keys that are currently held down, or locked
+    pub modifiers: Mod,
+    /// Mouse position in canvas coordinates, matching what widgets are drawn with
     pub mouse_position: Point,
+    /// Mouse position in window coordinates, as reported by SDL
+    pub raw_mouse_position: Point,
+    /// When the last mouse motion event was received by the main thread
+    pub last_mouse_move: Option<Instant>,
+    /// Smoothed mouse velocity in canvas pixels per second
+    pub mouse_velocity: Vector,
+    /// Every position the mouse moved to since the last batch of events,
+    /// oldest first, including the moves that were coalesced
+    pub mouse_path: Vec<Point>,
+    /// When the last input event of any kind was received
+    pub last_input: Instant,
     pub mouse_buttons: [bool; Self::MOUSE_BUTTON_COUNT],
+    /// Controller buttons that are currently held down, with the instance ID
+    /// of the controller they are on
+    pub controller_buttons: HashSet<(u32, Button)>,
+    /// Controller sticks and triggers
+    pub axes: Axes,
 }
 
 pub enum EventHandleResult {
@@ -52,55 +121,96 @@ pub enum EventHandleResult {
 }
 
 impl InputState {
-    pub const KEYBOARD_BUTTON_COUNT: usize = 255;
     pub const MOUSE_BUTTON_COUNT: usize = 5;
 
-    pub fn new(window_size: LogicalSize) -> Self {
+    /// How far past the last mouse motion event the position is extrapolated
+    pub const MOUSE_EXTRAPOLATION_LIMIT: Duration = Duration::from_millis(16);
+
+    pub fn new(window_size: LogicalSize, design_resolution: Option<Size>) -> Self {
         Self {
             window_size: Size::new(window_size.width as _, window_size.height as _),
-            keys: [false; Self::KEYBOARD_BUTTON_COUNT],
+            scale_factor: 1.0,
+            design_resolution,
+            keys: HashSet::new(),
+            modifiers: Mod::empty(),
             mouse_position: Point::default(),
+            raw_mouse_position: Point::default(),
+            last_mouse_move: None,
+            mouse_velocity: Vector::default(),
+            mouse_path: Vec::new(),
+            last_input: Instant::now(),
             mouse_buttons: [false; Self::MOUSE_BUTTON_COUNT],
+            controller_buttons: HashSet::new(),
+            axes: Axes::default(),
         }
     }
 
-    pub fn handle_event(&mut self, event: &Sdl2Event) -> Option<EventHandleResult> {
+    /// Update the state from an SDL event and translate it. `time` is when
+    /// the event happened, and `timestamp` the same time as given to the
+    /// [`InputEvent`].
+    pub fn handle_event(
+        &mut self,
+        event: &Sdl2Event,
+        time: Instant,
+        timestamp: Duration,
+    ) -> Option<EventHandleResult> {
+        let result = self.translate_event(event, time, timestamp);
+        if let Some(EventHandleResult::Input(_)) = result {
+            self.last_input = time;
+        }
+        result
+    }
+
+    fn translate_event(
+        &mut self,
+        event: &Sdl2Event,
+        time: Instant,
+        timestamp: Duration,
+    ) -> Option<EventHandleResult> {
         match event {
             Sdl2Event::Quit { .. } => return Some(EventHandleResult::Exit),
             Sdl2Event::Window { win_event, .. } => match win_event {
                 WindowEvent::Close => return Some(EventHandleResult::Exit),
+                WindowEvent::FocusLost => {
+                    // Release events will not arrive while the window is out
+                    // of focus, so forget everything held to avoid stuck keys.
+                    self.keys.clear();
+                    self.modifiers = Mod::empty();
+                    self.mouse_buttons = [false; Self::MOUSE_BUTTON_COUNT];
+                    self.controller_buttons.clear();
+                }
                 WindowEvent::Resized(width, height) => {
-                    return Some(EventHandleResult::Resized(Size::new(
-                        *width as _,
-                        *height as _,
-                    )));
+                    self.window_size = Size::new(*width as _, *height as _);
+                    return Some(EventHandleResult::Resized(self.canvas_size()));
                 }
                 _ => {}
             },
             Sdl2Event::KeyDown {
-                keycode: Some(k), ..
+                keycode: Some(k),
+                keymod,
+                ..
             } => {
-                if let Some(kc) = Self::keyboard_key_to_index(*k) {
-                    self.keys[kc] = true;
-                    return Some(EventHandleResult::Input(InputEvent::KeyDown(*k)));
-                }
+                self.keys.insert(*k);
+                self.modifiers = *keymod;
+                return Some(EventHandleResult::Input(InputEvent::KeyDown(*k, timestamp)));
             }
             Sdl2Event::KeyUp {
-                keycode: Some(k), ..
+                keycode: Some(k),
+                keymod,
+                ..
             } => {
-                if let Some(kc) = Self::keyboard_key_to_index(*k) {
-                    self.keys[kc] = false;
-                    return Some(EventHandleResult::Input(InputEvent::KeyUp(*k)));
-                }
+                self.keys.remove(k);
+                self.modifiers = *keymod;
+                return Some(EventHandleResult::Input(InputEvent::KeyUp(*k, timestamp)));
             }
             Sdl2Event::MouseButtonDown {
                 mouse_btn, x, y, ..
             } => {
                 if let Some(kc) = Self::mouse_button_to_index(*mouse_btn) {
                     self.mouse_buttons[kc] = true;
-                    let p = Point::new(*x as _, *y as _);
+                    let p = self.window_to_canvas(Point::new(*x as _, *y as _));
                     return Some(EventHandleResult::Input(InputEvent::MouseDown(
-                        *mouse_btn, p,
+                        *mouse_btn, p, timestamp,
                     )));
                 }
             }
@@ -109,28 +219,168 @@ impl InputState {
             } => {
                 if let Some(kc) = Self::mouse_button_to_index(*mouse_btn) {
                     self.mouse_buttons[kc] = false;
-                    let p = Point::new(*x as _, *y as _);
-                    return Some(EventHandleResult::Input(InputEvent::MouseUp(*mouse_btn, p)));
+                    let p = self.window_to_canvas(Point::new(*x as _, *y as _));
+                    return Some(EventHandleResult::Input(InputEvent::MouseUp(
+                        *mouse_btn, p, timestamp,
+                    )));
                 }
             }
             Sdl2Event::MouseMotion { x, y, .. } => {
-                let p = Point::new(*x as _, *y as _);
+                self.raw_mouse_position = Point::new(*x as _, *y as _);
+                let p = self.window_to_canvas(self.raw_mouse_position);
+                if let Some(last) = self.last_mouse_move {
+                    let dt = time.saturating_duration_since(last).as_secs_f32();
+                    if dt > 0.0 {
+                        let velocity = (p - self.mouse_position) * (1.0 / dt);
+                        self.mouse_velocity = (self.mouse_velocity + velocity) * 0.5;
+                    }
+                }
+                self.last_mouse_move = Some(time);
                 self.mouse_position = p;
-                return Some(EventHandleResult::Input(InputEvent::MouseMove(p)));
+                self.mouse_path.push(p);
+                return Some(EventHandleResult::Input(InputEvent::MouseMove(
+                    p, timestamp,
+                )));
             }
             Sdl2Event::MouseWheel { y, .. } => {
-                return Some(EventHandleResult::Input(InputEvent::MouseScroll(*y)));
+                return Some(EventHandleResult::Input(InputEvent::MouseScroll(
+                    *y, timestamp,
+                )));
+            }
+            // Input methods can commit several characters at once, which
+            // doesn't fit a single event, so only plain typing is passed on
+            Sdl2Event::TextInput { text, .. } => {
+                let mut chars = text.chars();
+                if let (Some(c), None) = (chars.next(), chars.next()) {
+                    return Some(EventHandleResult::Input(InputEvent::Char(c, timestamp)));
+                }
+            }
+            Sdl2Event::ControllerButtonDown { which, button, .. } => {
+                self.controller_buttons.insert((*which, *button));
+                return Some(EventHandleResult::Input(InputEvent::ControllerButtonDown(
+                    *which, *button, timestamp,
+                )));
+            }
+            Sdl2Event::ControllerButtonUp { which, button, .. } => {
+                self.controller_buttons.remove(&(*which, *button));
+                return Some(EventHandleResult::Input(InputEvent::ControllerButtonUp(
+                    *which, *button, timestamp,
+                )));
+            }
+            // Axes are read through the state rather than sent as events, as
+            // they move continuously
+            Sdl2Event::ControllerAxisMotion {
+                which, axis, value, ..
+            } => {
+                self.axes.set_raw(*which, *axis, *value);
+                self.last_input = time;
+            }
+            Sdl2Event::ControllerDeviceRemoved { which, .. } => {
+                self.controller_buttons.retain(|(id, _)| id != which);
+                self.axes.remove(*which);
             }
             _ => {}
         };
         None
     }
 
+    /// Size of the canvas the game draws on, in logical pixels
+    pub fn canvas_size(&self) -> Size {
+        self.design_resolution.unwrap_or(self.window_size)
+    }
+
+    /// Matrix mapping the canvas into the window, scaling the design
+    /// resolution to fit and centering it between the black bars
+    pub fn canvas_matrix(&self) -> Matrix {
+        match self.design_resolution {
+            Some(design) if !design.is_empty() => {
+                let scale = (self.window_size.width / design.width)
+                    .min(self.window_size.height / design.height);
+                let offset =
+                    (self.window_size.bottom_right() - design.bottom_right() * scale) * 0.5;
+                Matrix::concat(&Matrix::translate(offset), &Matrix::scale((scale, scale)))
+            }
+            _ => Matrix::default(),
+        }
+    }
+
+    /// Update the held keys and mouse state from an event that did not come
+    /// from SDL, such as a replayed one
+    pub fn apply(&mut self, event: &InputEvent) {
+        self.last_input = Instant::now();
+        match event {
+            InputEvent::KeyDown(k, _) => {
+                self.keys.insert(*k);
+            }
+            InputEvent::KeyUp(k, _) => {
+                self.keys.remove(k);
+            }
+            InputEvent::MouseDown(b, ..) | InputEvent::MouseUp(b, ..) => {
+                if let Some(kc) = Self::mouse_button_to_index(*b) {
+                    self.mouse_buttons[kc] = matches!(event, InputEvent::MouseDown(..));
+                }
+            }
+            InputEvent::MouseMove(p, _) => {
+                self.mouse_position = *p;
+                self.raw_mouse_position = self.canvas_to_window(*p);
+                self.mouse_path.push(*p);
+            }
+            InputEvent::ControllerButtonDown(which, b, _) => {
+                self.controller_buttons.insert((*which, *b));
+            }
+            InputEvent::ControllerButtonUp(which, b, _) => {
+                self.controller_buttons.remove(&(*which, *b));
+            }
+            InputEvent::MouseScroll(..) | InputEvent::Char(..) => {}
+        }
+    }
+
+    /// How long it has been since the last input event
+    pub fn idle_time(&self) -> Duration {
+        self.last_input.elapsed()
+    }
+
+    /// Mouse position relative to the canvas size, where (0, 0) is the top
+    /// left corner and (1, 1) the bottom right one
+    pub fn mouse_position_normalized(&self) -> Point {
+        let size = self.canvas_size();
+        if size.is_empty() {
+            return Point::default();
+        }
+        Point::new(
+            self.mouse_position.x / size.width,
+            self.mouse_position.y / size.height,
+        )
+    }
+
+    /// Estimate where the mouse is at the given time, following its velocity
+    /// for a short while after the last motion event. This hides some of the
+    /// latency between the main thread receiving an event and the next frame.
+    pub fn extrapolated_mouse_position(&self, at: Instant) -> Point {
+        match self.last_mouse_move {
+            Some(last) => {
+                let dt = at
+                    .saturating_duration_since(last)
+                    .min(Self::MOUSE_EXTRAPOLATION_LIMIT);
+                self.mouse_position + self.mouse_velocity * dt.as_secs_f32()
+            }
+            None => self.mouse_position,
+        }
+    }
+
+    /// Map a point in window coordinates into canvas coordinates
+    pub fn window_to_canvas(&self, p: Point) -> Point {
+        self.canvas_matrix().invert().map_or(p, |m| m.map_point(p))
+    }
+
+    /// Map a point in canvas coordinates into window coordinates
+    pub fn canvas_to_window(&self, p: Point) -> Point {
+        self.canvas_matrix().map_point(p)
+    }
+
     /// Returns whether the given key is down
     pub fn is_key_down(&self, key: Keycode) -> bool {
-        Self::keyboard_key_to_index(key)
-            .map(|k| self.keys[k])
-            .unwrap_or(false)
+        self.keys.contains(&key)
     }
 
     /// Returns whether the given button is down
@@ -140,6 +390,11 @@ impl InputState {
             .unwrap_or(false)
     }
 
+    /// Returns whether the given button is down on any controller
+    pub fn is_controller_button_down(&self, button: Button) -> bool {
+        self.controller_buttons.iter().any(|(_, b)| *b == button)
+    }
+
     /// Convert the mouse button enum into a numerical index
     fn mouse_button_to_index(button: MouseButton) -> Option<usize> {
         let index = match button {
@@ -157,14 +412,4 @@ impl InputState {
             Some(index)
         }
     }
-
-    /// Convert the key code into a numerical index
-    fn keyboard_key_to_index(key: Keycode) -> Option<usize> {
-        let index = key as usize;
-        if index >= Self::KEYBOARD_BUTTON_COUNT {
-            None
-        } else {
-            Some(index)
-        }
-    }
 }
@@ -0,0 +1,217 @@
+use core::fmt::{Display, Formatter, Result as FmtResult};
+use std::collections::BTreeMap;
+use std::convert::TryFrom;
+
+use serde::{Deserialize, Serialize};
+use skulpin_renderer_sdl2::sdl2::{controller::Button, keyboard::Keycode, mouse::MouseButton};
+
+use super::input::{InputEvent, InputState};
+
+/// A physical input an action can be bound to.
+///
+/// Bindings are stored in settings by name, like `"Key:Space"`,
+/// `"Mouse:Left"` or `"Controller:a"`, so key codes don't depend on the SDL
+/// version. Controller buttons are named as in SDL's controller mappings, and
+/// count on any controller.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(into = "String", try_from = "String")]
+pub enum Binding {
+    Key(Keycode),
+    Mouse(MouseButton),
+    ControllerButton(Button),
+}
+
+impl Binding {
+    /// Whether the binding is held down
+    pub fn is_down(&self, input: &InputState) -> bool {
+        match *self {
+            Binding::Key(k) => input.is_key_down(k),
+            Binding::Mouse(b) => input.is_mouse_down(b),
+            Binding::ControllerButton(b) => input.is_controller_button_down(b),
+        }
+    }
+
+    /// Whether the event presses this binding
+    pub fn is_pressed_by(&self, event: &InputEvent) -> bool {
+        match (*self, event) {
+            (Binding::Key(k), InputEvent::KeyDown(key, _)) => k == *key,
+            (Binding::Mouse(b), InputEvent::MouseDown(button, ..)) => b == *button,
+            (Binding::ControllerButton(b), InputEvent::ControllerButtonDown(_, button, _)) => {
+                b == *button
+            }
+            _ => false,
+        }
+    }
+
+    /// Whether the event releases this binding
+    pub fn is_released_by(&self, event: &InputEvent) -> bool {
+        match (*self, event) {
+            (Binding::Key(k), InputEvent::KeyUp(key, _)) => k == *key,
+            (Binding::Mouse(b), InputEvent::MouseUp(button, ..)) => b == *button,
+            (Binding::ControllerButton(b), InputEvent::ControllerButtonUp(_, button, _)) => {
+                b == *button
+            }
+            _ => false,
+        }
+    }
+}
+
+impl From<Keycode> for Binding {
+    fn from(key: Keycode) -> Self {
+        Binding::Key(key)
+    }
+}
+
+impl From<MouseButton> for Binding {
+    fn from(button: MouseButton) -> Self {
+        Binding::Mouse(button)
+    }
+}
+
+impl From<Button> for Binding {
+    fn from(button: Button) -> Self {
+        Binding::ControllerButton(button)
+    }
+}
+
+const MOUSE_BUTTONS: [(MouseButton, &str); 5] = [
+    (MouseButton::Left, "Left"),
+    (MouseButton::Middle, "Middle"),
+    (MouseButton::Right, "Right"),
+    (MouseButton::X1, "X1"),
+    (MouseButton::X2, "X2"),
+];
+
+impl Display for Binding {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match self {
+            Binding::Key(k) => write!(f, "Key:{}", k.name()),
+            Binding::Mouse(b) => {
+                let name = MOUSE_BUTTONS
+                    .iter()
+                    .find(|(button, _)| button == b)
+                    .map_or("Unknown", |(_, name)| name);
+                write!(f, "Mouse:{}", name)
+            }
+            Binding::ControllerButton(b) => write!(f, "Controller:{}", b.string()),
+        }
+    }
+}
+
+impl From<Binding> for String {
+    fn from(binding: Binding) -> Self {
+        binding.to_string()
+    }
+}
+
+impl TryFrom<String> for Binding {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        let unknown = || format!("Unknown input binding {:?}", s);
+        if let Some(name) = s.strip_prefix("Key:") {
+            Keycode::from_name(name)
+                .map(Binding::Key)
+                .ok_or_else(unknown)
+        } else if let Some(name) = s.strip_prefix("Mouse:") {
+            MOUSE_BUTTONS
+                .iter()
+                .find(|(_, n)| *n == name)
+                .map(|(b, _)| Binding::Mouse(*b))
+                .ok_or_else(unknown)
+        } else if let Some(name) = s.strip_prefix("Controller:") {
+            Button::from_string(name)
+                .map(Binding::ControllerButton)
+                .ok_or_else(unknown)
+        } else {
+            Err(unknown())
+        }
+    }
+}
+
+/// Maps abstract actions like "Jump" or "MenuUp" to the keys, mouse buttons
+/// and controller buttons that trigger them, so game logic doesn't hardcode
+/// keys and the player can remap them.
+///
+/// An action can have any number of bindings, and counts as held while any
+/// of them is. The map can be stored with [`settings`](crate::utils::settings)
+/// to keep the player's bindings between runs, and is usually installed with
+/// [`State::set_input_map`](super::State::set_input_map).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct InputMap {
+    actions: BTreeMap<String, Vec<Binding>>,
+}
+
+impl InputMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_binding(mut self, action: &str, binding: impl Into<Binding>) -> Self {
+        self.bind(action, binding);
+        self
+    }
+
+    /// Add a binding to an action, keeping the ones it already has
+    pub fn bind(&mut self, action: &str, binding: impl Into<Binding>) {
+        let binding = binding.into();
+        let bindings = self.actions.entry(action.to_owned()).or_default();
+        if !bindings.contains(&binding) {
+            bindings.push(binding);
+        }
+    }
+
+    /// Replace all bindings of an action with a single one, like when the
+    /// player picks a new key for it
+    pub fn rebind(&mut self, action: &str, binding: impl Into<Binding>) {
+        self.actions.insert(action.to_owned(), vec![binding.into()]);
+    }
+
+    pub fn unbind(&mut self, action: &str, binding: impl Into<Binding>) {
+        let binding = binding.into();
+        if let Some(bindings) = self.actions.get_mut(action) {
+            bindings.retain(|b| *b != binding);
+        }
+    }
+
+    /// Remove every binding of an action
+    pub fn clear(&mut self, action: &str) {
+        self.actions.remove(action);
+    }
+
+    pub fn bindings(&self, action: &str) -> &[Binding] {
+        self.actions.get(action).map_or(&[], |b| b.as_slice())
+    }
+
+    /// The actions that have bindings, in alphabetical order
+    pub fn actions(&self) -> impl Iterator<Item = &str> {
+        self.actions.keys().map(|a| a.as_str())
+    }
+
+    /// Whether any binding of the action is held down
+    pub fn is_down(&self, action: &str, input: &InputState) -> bool {
+        self.bindings(action).iter().any(|b| b.is_down(input))
+    }
+
+    /// Whether the event presses a binding of the action, for reacting to
+    /// the press itself rather than polling
+    pub fn is_pressed_by(&self, action: &str, event: &InputEvent) -> bool {
+        self.bindings(action).iter().any(|b| b.is_pressed_by(event))
+    }
+
+    /// Whether the event releases a binding of the action
+    pub fn is_released_by(&self, action: &str, event: &InputEvent) -> bool {
+        self.bindings(action)
+            .iter()
+            .any(|b| b.is_released_by(event))
+    }
+
+    /// The actions the event presses a binding of
+    pub fn pressed_by<'a>(&'a self, event: &'a InputEvent) -> impl Iterator<Item = &'a str> {
+        self.actions
+            .iter()
+            .filter(move |(_, bindings)| bindings.iter().any(|b| b.is_pressed_by(event)))
+            .map(|(action, _)| action.as_str())
+    }
+}
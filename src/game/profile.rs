@@ -0,0 +1,132 @@
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::time::Duration;
+
+/// The time spent in one type of widget
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WidgetCost {
+    /// The widget's type name
+    pub widget: &'static str,
+    /// How many times widgets of this type were drawn
+    pub draws: u64,
+    /// Time spent drawing, not counting the widgets drawn inside
+    pub draw: Duration,
+    /// Time spent drawing, including the widgets drawn inside
+    pub draw_total: Duration,
+    /// Time spent sizing and laying out, not counting the widgets inside
+    pub layout: Duration,
+}
+
+impl WidgetCost {
+    fn new(widget: &'static str) -> Self {
+        Self {
+            widget,
+            draws: 0,
+            draw: Duration::ZERO,
+            draw_total: Duration::ZERO,
+            layout: Duration::ZERO,
+        }
+    }
+}
+
+/// Accumulates how long each type of widget takes to draw and lay out,
+/// while [`DebugOptions::profile_widgets`](super::DebugOptions::profile_widgets)
+/// is on.
+///
+/// Widgets are told apart by their type, so every [`Text`] adds to the same
+/// cost, and a boxed widget is counted as `Box<dyn Widget>`. Time spent in a
+/// widget is split into its own time and that of the widgets inside, which
+/// makes it easy to find the ones that are expensive by themselves.
+///
+/// [`Text`]: crate::framework::widgets::Text
+#[derive(Default)]
+pub struct Profiler {
+    costs: HashMap<&'static str, WidgetCost>,
+    /// The time spent in instrumented children, for every instrumented call
+    /// that is still running
+    stack: Vec<Duration>,
+    frames: u64,
+}
+
+impl Profiler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The costs so far, the most expensive first by the time spent drawing
+    /// and laying out together
+    pub fn costs(&self) -> Vec<WidgetCost> {
+        let mut costs: Vec<_> = self.costs.values().cloned().collect();
+        costs.sort_by(|a, b| (b.draw + b.layout).cmp(&(a.draw + a.layout)));
+        costs
+    }
+
+    /// How many frames the costs were collected over
+    pub fn frames(&self) -> u64 {
+        self.frames
+    }
+
+    pub fn reset(&mut self) {
+        self.costs.clear();
+        self.frames = 0;
+    }
+
+    /// A table of the costs, averaged per frame
+    pub fn report(&self) -> String {
+        let frames = self.frames.max(1) as u32;
+        let mut out = format!(
+            "{:>10} {:>10} {:>10} {:>8}  widget ({} frames)\n",
+            "draw", "total", "layout", "draws", self.frames
+        );
+        for c in self.costs() {
+            let _ = writeln!(
+                out,
+                "{:>10.3?} {:>10.3?} {:>10.3?} {:>8}  {}",
+                c.draw / frames,
+                c.draw_total / frames,
+                c.layout / frames,
+                c.draws / frames as u64,
+                c.widget
+            );
+        }
+        out
+    }
+
+    pub(crate) fn begin(&mut self) {
+        self.stack.push(Duration::ZERO);
+    }
+
+    pub(crate) fn end_draw(&mut self, widget: &'static str, elapsed: Duration) {
+        let own = self.end(elapsed);
+        let cost = self
+            .costs
+            .entry(widget)
+            .or_insert_with(|| WidgetCost::new(widget));
+        cost.draws += 1;
+        cost.draw += own;
+        cost.draw_total += elapsed;
+    }
+
+    pub(crate) fn end_layout(&mut self, widget: &'static str, elapsed: Duration) {
+        let own = self.end(elapsed);
+        let cost = self
+            .costs
+            .entry(widget)
+            .or_insert_with(|| WidgetCost::new(widget));
+        cost.layout += own;
+    }
+
+    /// Finish the innermost call, returning its own time
+    fn end(&mut self, elapsed: Duration) -> Duration {
+        let children = self.stack.pop().unwrap_or_default();
+        if let Some(parent) = self.stack.last_mut() {
+            *parent += elapsed;
+        }
+        elapsed.checked_sub(children).unwrap_or_default()
+    }
+
+    pub(crate) fn next_frame(&mut self) {
+        self.stack.clear();
+        self.frames += 1;
+    }
+}
@@ -0,0 +1,36 @@
+use std::any::Any;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, Weak};
+
+use super::ID;
+
+/// Weak references to values by [`ID`], so they can be found by game logic
+/// without keeping them alive. See
+/// [`Tagged`](crate::framework::widgets::Tagged) for registering widgets.
+#[derive(Default)]
+pub struct Registry {
+    entries: HashMap<ID, Weak<Mutex<dyn Any + Send>>>,
+}
+
+impl Registry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn register<T: Any + Send>(&mut self, id: ID, value: &Arc<Mutex<T>>) {
+        // Dropped values leave their entries behind, so clean those up here
+        // rather than keeping track of drops.
+        self.entries.retain(|_, x| x.strong_count() > 0);
+        let value: Arc<Mutex<dyn Any + Send>> = value.clone();
+        self.entries.insert(id, Arc::downgrade(&value));
+    }
+
+    pub fn unregister(&mut self, id: ID) {
+        self.entries.remove(&id);
+    }
+
+    /// Get a strong reference to a value that is still alive
+    pub fn get(&self, id: ID) -> Option<Arc<Mutex<dyn Any + Send>>> {
+        self.entries.get(&id).and_then(Weak::upgrade)
+    }
+}
@@ -0,0 +1,189 @@
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{
+    BufRead, BufReader, BufWriter, Error as IoError, ErrorKind, Result as IoResult, Write,
+};
+use std::path::Path;
+use std::time::Duration;
+
+use skulpin_renderer_sdl2::sdl2::{
+    controller::{Axis, Button},
+    keyboard::Keycode,
+    mouse::MouseButton,
+};
+
+use super::InputEvent;
+use crate::skia::Point;
+
+/// Writes input events to a file as the game receives them.
+///
+/// Every line holds one event, prefixed with the number of microseconds
+/// since the game started, e.g. `1520300 MouseDown Left 120 48.5`. Replayed
+/// events get that time as their own. Controller axes, which aren't events,
+/// are recorded as they move, e.g. `1520300 ControllerAxis 0 leftx -1200`.
+pub struct Recorder {
+    writer: BufWriter<File>,
+}
+
+impl Recorder {
+    pub fn create<P: AsRef<Path>>(path: P) -> IoResult<Self> {
+        Ok(Self {
+            writer: BufWriter::new(File::create(path)?),
+        })
+    }
+
+    pub fn record(&mut self, time: Duration, event: &InputEvent) -> IoResult<()> {
+        writeln!(self.writer, "{} {}", time.as_micros(), format_event(event))
+    }
+
+    /// Record a controller axis moving to its raw value, on the controller
+    /// with the given instance ID
+    pub fn record_axis(
+        &mut self,
+        time: Duration,
+        controller: u32,
+        axis: Axis,
+        value: i16,
+    ) -> IoResult<()> {
+        writeln!(
+            self.writer,
+            "{} ControllerAxis {} {} {}",
+            time.as_micros(),
+            controller,
+            axis.string(),
+            value
+        )
+    }
+}
+
+/// What a [`Replay`] plays back
+pub enum Replayed {
+    Input(InputEvent),
+    /// A controller axis moving, see [`Recorder::record_axis`]
+    ControllerAxis(u32, Axis, i16),
+}
+
+/// Input events read back from a file written by a [`Recorder`].
+pub struct Replay {
+    events: VecDeque<(Duration, Replayed)>,
+}
+
+impl Replay {
+    pub fn open<P: AsRef<Path>>(path: P) -> IoResult<Self> {
+        let reader = BufReader::new(File::open(path)?);
+        let mut events = VecDeque::new();
+        for (i, line) in reader.lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            match parse_line(&line) {
+                Some(event) => events.push_back(event),
+                None => {
+                    return Err(IoError::new(
+                        ErrorKind::InvalidData,
+                        format!("Malformed input event on line {}", i + 1),
+                    ))
+                }
+            }
+        }
+        Ok(Self { events })
+    }
+
+    /// Take the next event if it is due at the given time since the game started
+    pub fn pop_due(&mut self, now: Duration) -> Option<Replayed> {
+        if self.events.front()?.0 <= now {
+            self.events.pop_front().map(|(_, e)| e)
+        } else {
+            None
+        }
+    }
+}
+
+fn format_event(event: &InputEvent) -> String {
+    match event {
+        InputEvent::KeyDown(k, _) => format!("KeyDown {}", *k as i32),
+        InputEvent::KeyUp(k, _) => format!("KeyUp {}", *k as i32),
+        InputEvent::MouseMove(p, _) => format!("MouseMove {} {}", p.x, p.y),
+        InputEvent::MouseDown(b, p, _) => {
+            format!("MouseDown {} {} {}", format_button(*b), p.x, p.y)
+        }
+        InputEvent::MouseUp(b, p, _) => {
+            format!("MouseUp {} {} {}", format_button(*b), p.x, p.y)
+        }
+        InputEvent::MouseScroll(y, _) => format!("MouseScroll {}", y),
+        InputEvent::Char(c, _) => format!("Char {}", *c as u32),
+        InputEvent::ControllerButtonDown(which, b, _) => {
+            format!("ControllerButtonDown {} {}", which, b.string())
+        }
+        InputEvent::ControllerButtonUp(which, b, _) => {
+            format!("ControllerButtonUp {} {}", which, b.string())
+        }
+    }
+}
+
+fn parse_line(line: &str) -> Option<(Duration, Replayed)> {
+    let mut parts = line.split_whitespace();
+    let time = Duration::from_micros(parts.next()?.parse().ok()?);
+    let mut next = || parts.next();
+    let event = match next()? {
+        "ControllerAxis" => {
+            let axis = Replayed::ControllerAxis(
+                next()?.parse().ok()?,
+                Axis::from_string(next()?)?,
+                next()?.parse().ok()?,
+            );
+            return Some((time, axis));
+        }
+        "KeyDown" => InputEvent::KeyDown(Keycode::from_i32(next()?.parse().ok()?)?, time),
+        "KeyUp" => InputEvent::KeyUp(Keycode::from_i32(next()?.parse().ok()?)?, time),
+        "MouseMove" => InputEvent::MouseMove(parse_point(next()?, next()?)?, time),
+        "MouseDown" => {
+            InputEvent::MouseDown(parse_button(next()?)?, parse_point(next()?, next()?)?, time)
+        }
+        "MouseUp" => {
+            InputEvent::MouseUp(parse_button(next()?)?, parse_point(next()?, next()?)?, time)
+        }
+        "MouseScroll" => InputEvent::MouseScroll(next()?.parse().ok()?, time),
+        "Char" => InputEvent::Char(std::char::from_u32(next()?.parse().ok()?)?, time),
+        "ControllerButtonDown" => InputEvent::ControllerButtonDown(
+            next()?.parse().ok()?,
+            Button::from_string(next()?)?,
+            time,
+        ),
+        "ControllerButtonUp" => InputEvent::ControllerButtonUp(
+            next()?.parse().ok()?,
+            Button::from_string(next()?)?,
+            time,
+        ),
+        _ => return None,
+    };
+    Some((time, Replayed::Input(event)))
+}
+
+fn parse_point(x: &str, y: &str) -> Option<Point> {
+    Some(Point::new(x.parse().ok()?, y.parse().ok()?))
+}
+
+fn format_button(button: MouseButton) -> &'static str {
+    match button {
+        MouseButton::Left => "Left",
+        MouseButton::Right => "Right",
+        MouseButton::Middle => "Middle",
+        MouseButton::X1 => "X1",
+        MouseButton::X2 => "X2",
+        _ => "Unknown",
+    }
+}
+
+fn parse_button(s: &str) -> Option<MouseButton> {
+    Some(match s {
+        "Left" => MouseButton::Left,
+        "Right" => MouseButton::Right,
+        "Middle" => MouseButton::Middle,
+        "X1" => MouseButton::X1,
+        "X2" => MouseButton::X2,
+        "Unknown" => MouseButton::Unknown,
+        _ => return None,
+    })
+}
@@ -1,42 +1,106 @@
 use core::fmt::{Display, Formatter, Result as FmtResult};
+use std::collections::VecDeque;
 use std::error::Error as StdError;
+use std::ffi::CString;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::mpsc::{sync_channel, SyncSender, TryRecvError, TrySendError};
-use std::thread::{sleep, spawn};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread::{sleep, Builder as ThreadBuilder};
 use std::time::{Duration, Instant};
 use std::{cell::RefCell, sync::mpsc::Receiver};
 
-use skulpin_renderer::{ash, LogicalSize, RendererBuilder};
+use skulpin_renderer::{
+    ash, CoordinateSystem, CoordinateSystemHelper, LogicalSize, PresentMode, Renderer,
+    RendererBuilder,
+};
 
 use ash::vk::Result as VkResult;
 
-use crate::skia::{Color, Matrix, Picture, PictureRecorder, Point, Rect, Size};
+use crate::framework::accessibility::{AccessNode, AccessRecorder};
+use crate::framework::music::{AudioMixer, MusicCreateError};
+use crate::framework::sound_fx::SoundFxCreateError;
+use crate::framework::theme::{ColorScheme, Theme, ThemeFollower};
+use crate::framework::widgets::Insets;
+use crate::skia::{
+    canvas::SaveLayerRec, gpu::SurfaceOrigin, scalar, Budgeted, Canvas, Color, ImageFilter, Matrix,
+    Paint, Picture, PictureRecorder, Point, Rect, Size, Surface, Vector,
+};
 
-use super::input::{EventHandleResult, InputState};
-use super::time::TimeState;
-use super::Game;
-use super::{default_font_set::DefaultFontSet, FontSet};
+use super::axis::{AxisSettings, Stick};
+use super::bounds::Bounds;
+use super::focus::Focus;
+use super::input::{EventHandleResult, InputEvent, InputState};
+use super::input_map::InputMap;
+use super::profile::{Profiler, WidgetCost};
+use super::registry::Registry;
+use super::replay::{Recorder, Replay, Replayed};
+use super::task::{TaskHandle, TaskPool};
+use super::time::{FrameStats, TimeState};
+use super::{
+    default_font_set::DefaultFontSet, DebugOptions, FontRendering, FontSet, HitTest, PointerTrail,
+};
+use super::{DrawSnapshot, Game};
 
-use sdl2::event::Event as Sdl2Event;
+use sdl2::{
+    controller::{Axis, GameController},
+    event::{Event as Sdl2Event, WindowEvent},
+    keyboard::{Keycode, Mod},
+    mouse::{Cursor, MouseButton, SystemCursor},
+    video::WindowBuildError,
+};
 use skulpin_renderer_sdl2::{sdl2, Sdl2Window};
 
 enum Event {
-    Sdl2Event(Sdl2Event),
+    /// An SDL event, along with when it happened by SDL's timestamp
+    Sdl2Event(Sdl2Event, Instant),
+    /// The window's pixels per window coordinate changed, like after moving
+    /// it to a display with another scale
+    ScaleFactor(scalar),
+    /// The renderer was built again for [`State::set_vsync`], with vsync on
+    /// or off as given, which is the old setting if that failed
+    Vsync(bool),
     Crash(Error),
 }
 
+/// Requests from the game thread to the main thread, which owns the window
+/// and renderer
 enum FeedbackEvent {
     Exit,
+    SetCoordinateSystem(CoordinateSystem),
+    SetVsync(bool),
+    SetWindowTitle(CString),
+    /// Show the system cursor with this shape, or hide it for the software
+    /// cursor with `None`
+    SetCursor(Option<SystemCursor>),
+    SetClipboardText(String),
 }
 
 #[derive(Debug)]
 pub enum Error {
     RendererError(VkResult),
+    /// SDL or one of its subsystems failed to start
+    SdlInit(String),
+    WindowCreation(WindowBuildError),
+    GStreamerInit(Box<dyn StdError + Send + Sync>),
+    RendererCreation(Box<dyn StdError + Send + Sync>),
+    /// The game thread couldn't be started
+    ThreadSpawn(std::io::Error),
+    Music(MusicCreateError),
+    SoundFx(SoundFxCreateError),
 }
 
 impl Display for Error {
     fn fmt(&self, f: &mut Formatter) -> FmtResult {
         match self {
             Error::RendererError(e) => e.fmt(f),
+            Error::SdlInit(e) => write!(f, "Failed to initialize SDL2: {}", e),
+            Error::WindowCreation(e) => write!(f, "Failed to create game window: {}", e),
+            Error::GStreamerInit(e) => write!(f, "Failed to initialize GStreamer: {}", e),
+            Error::RendererCreation(e) => write!(f, "Failed to create renderer: {}", e),
+            Error::ThreadSpawn(e) => write!(f, "Failed to spawn game thread: {}", e),
+            Error::Music(e) => write!(f, "Failed to create music player: {}", e),
+            Error::SoundFx(e) => write!(f, "Failed to create sound effect: {}", e),
         }
     }
 }
@@ -45,17 +109,36 @@ impl StdError for Error {
     fn source(&self) -> Option<&(dyn StdError + 'static)> {
         match self {
             Error::RendererError(e) => Some(e),
+            Error::SdlInit(_) => None,
+            Error::WindowCreation(e) => Some(e),
+            Error::GStreamerInit(e) => Some(e.as_ref()),
+            Error::RendererCreation(e) => Some(e.as_ref()),
+            Error::ThreadSpawn(e) => Some(e),
+            Error::Music(e) => Some(e),
+            Error::SoundFx(e) => Some(e),
         }
     }
 }
 
+impl From<MusicCreateError> for Error {
+    fn from(e: MusicCreateError) -> Self {
+        Error::Music(e)
+    }
+}
+
+impl From<SoundFxCreateError> for Error {
+    fn from(e: SoundFxCreateError) -> Self {
+        Error::SoundFx(e)
+    }
+}
+
 impl From<VkResult> for Error {
     fn from(result: VkResult) -> Self {
         Error::RendererError(result)
     }
 }
 
-#[derive(Debug, PartialEq, Clone, Copy)]
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
 pub struct ID(u64);
 
 impl ID {
@@ -70,9 +153,50 @@ impl ID {
 
 pub struct State {
     pub input_state: InputState,
+    pub input_map: InputMap,
     pub time_state: TimeState,
     pub time_state_draw: TimeState,
     pub font_set: Box<dyn FontSet>,
+    pub font_rendering: FontRendering,
+    pub cull_offscreen: bool,
+    /// Set while a culled widget's subtree is drawn onto a canvas without
+    /// pixels, so that the widgets in it aren't culled again
+    pub(crate) drawing_culled: bool,
+    /// Set while drawing once a widget without a cull margin was drawn, to
+    /// pass that on to the widgets around it
+    pub(crate) drew_unbounded: bool,
+    /// Maps what an offscreen canvas draws to where it ends up in the frame,
+    /// while a [`Layer`](crate::framework::widgets::Layer) draws onto one
+    pub(crate) offscreen_matrix: Matrix,
+    pub debug: DebugOptions,
+    pub task_pool: TaskPool,
+    pub registry: Registry,
+    pub bounds: Bounds,
+    pub focus: Focus,
+    pub(crate) accessibility: AccessRecorder,
+    pub profiler: Profiler,
+    pub hit_test: HitTest,
+    pub pointer_trail: PointerTrail,
+    coordinate_system: CoordinateSystem,
+    vsync: bool,
+    theme: Theme,
+    safe_area: Insets,
+    color_scheme: Option<ColorScheme>,
+    cursor: Option<SystemCursor>,
+    hover_cursor: Option<SystemCursor>,
+    shown_cursor: Option<SystemCursor>,
+    fixed_update_alpha: f32,
+    redraw_requested: bool,
+    /// When a redraw was asked for with `request_redraw_in`, since the start
+    redraw_at: Option<Duration>,
+    exit_requested: bool,
+    /// An error to stop the game with, from `State::crash`
+    crash: Option<Error>,
+    feedback: VecDeque<FeedbackEvent>,
+    /// Events sent with `State::send_input`, handled before the next update
+    synthetic_input: VecDeque<InputEvent>,
+    frame_dump: Option<PathBuf>,
+    frame_filter: Option<ImageFilter>,
     id_keeper: u64,
 }
 
@@ -96,6 +220,51 @@ impl State {
         Self::STATE.with(|x| f(x.borrow_mut().as_mut().expect(Self::PANIC_MESSAGE)))
     }
 
+    /// Set up the state of the current thread
+    pub(crate) fn init(mut input_state: InputState, config: &Config) {
+        input_state.axes.settings = config.axis_settings;
+        let state = State {
+            input_state,
+            input_map: config.input_map.clone(),
+            time_state: TimeState::new(),
+            time_state_draw: TimeState::new(),
+            font_set: Box::new(DefaultFontSet::new()),
+            font_rendering: config.font_rendering,
+            cull_offscreen: config.cull_offscreen,
+            drawing_culled: false,
+            drew_unbounded: false,
+            offscreen_matrix: Matrix::default(),
+            debug: DebugOptions::default(),
+            task_pool: TaskPool::new(config.task_threads),
+            registry: Registry::new(),
+            bounds: Bounds::new(),
+            focus: Focus::new(),
+            accessibility: AccessRecorder::new(),
+            profiler: Profiler::new(),
+            hit_test: HitTest::new(),
+            pointer_trail: PointerTrail::new(),
+            coordinate_system: config.renderer.coordinate_system,
+            vsync: config.renderer.vsync,
+            theme: config.theme.clone(),
+            safe_area: config.safe_area,
+            color_scheme: None,
+            cursor: None,
+            hover_cursor: None,
+            shown_cursor: None,
+            fixed_update_alpha: 0.0,
+            redraw_requested: false,
+            redraw_at: None,
+            exit_requested: false,
+            crash: None,
+            feedback: VecDeque::new(),
+            synthetic_input: VecDeque::new(),
+            frame_dump: None,
+            frame_filter: None,
+            id_keeper: 0,
+        };
+        Self::STATE.with(|x| *x.borrow_mut() = Some(state));
+    }
+
     pub fn last_update_time() -> Duration {
         Self::STATE.with(|x| {
             x.borrow()
@@ -106,6 +275,13 @@ impl State {
         })
     }
 
+    /// Time taken by the previous update, for advancing simulations
+    /// independently of the update rate. Same as
+    /// [`last_update_time`](Self::last_update_time).
+    pub fn delta() -> Duration {
+        Self::last_update_time()
+    }
+
     pub fn elapsed() -> Duration {
         Self::STATE.with(|x| {
             x.borrow()
@@ -116,6 +292,13 @@ impl State {
         })
     }
 
+    /// How far along the next [`Game::fixed_update`] step is, from 0 to 1.
+    /// Drawing the state between the last two steps by this much keeps
+    /// motion smooth at any frame rate. Always 0 without a fixed update rate.
+    pub fn fixed_update_alpha() -> f32 {
+        Self::with(|x| x.fixed_update_alpha)
+    }
+
     pub fn last_update_time_draw() -> Duration {
         Self::STATE.with(|x| {
             x.borrow()
@@ -126,6 +309,17 @@ impl State {
         })
     }
 
+    /// How evenly the last frames were drawn. A low standard deviation
+    /// compared to the mean means smooth motion.
+    pub fn frame_stats() -> FrameStats {
+        Self::with(|x| x.time_state_draw.stats())
+    }
+
+    /// What the widgets cost so far, see [`Profiler`]
+    pub fn widget_costs() -> Vec<WidgetCost> {
+        Self::with(|x| x.profiler.costs())
+    }
+
     pub fn mouse_position() -> Point {
         Self::STATE.with(|x| {
             x.borrow()
@@ -135,6 +329,747 @@ impl State {
                 .mouse_position
         })
     }
+
+    pub fn debug() -> DebugOptions {
+        Self::with(|x| x.debug)
+    }
+
+    pub fn set_debug(debug: DebugOptions) {
+        Self::with_mut(|x| x.debug = debug);
+    }
+
+    /// Note a mouse event for [`DebugOptions::pointer_trail`]
+    fn trace_pointer(&mut self, event: &InputEvent) {
+        if let Some(options) = self.debug.pointer_trail {
+            let now = self.time_state.elapsed();
+            self.pointer_trail.record(event, now, &options);
+        }
+    }
+
+    /// Returns whether the given key is currently held down
+    pub fn is_key_down(key: Keycode) -> bool {
+        Self::with(|x| x.input_state.is_key_down(key))
+    }
+
+    /// A controller axis, with the dead zone and smoothing of the
+    /// [`AxisSettings`] applied. With several controllers connected, this is
+    /// whichever moved the axis last.
+    pub fn axis(axis: Axis) -> scalar {
+        Self::with(|x| x.input_state.axes.axis(axis))
+    }
+
+    /// A controller stick's position, with the radial dead zone applied
+    pub fn stick(stick: Stick) -> Vector {
+        Self::with(|x| x.input_state.axes.stick(stick))
+    }
+
+    /// How many pixels there are per window coordinate, 2 on a display
+    /// scaled to 200%. It follows the window when it's moved to another
+    /// display.
+    ///
+    /// With the default logical coordinate system, drawing and mouse
+    /// positions are in window coordinates and already scaled, so this is
+    /// only needed for things like picking image resolutions or snapping to
+    /// whole pixels.
+    pub fn scale_factor() -> scalar {
+        Self::with(|x| x.input_state.scale_factor)
+    }
+
+    /// Modifier keys that are currently held down
+    pub fn modifiers() -> Mod {
+        Self::with(|x| x.input_state.modifiers)
+    }
+
+    /// Returns whether any input bound to the action in the [`InputMap`] is
+    /// currently held down
+    pub fn action_pressed(action: &str) -> bool {
+        Self::with(|x| x.input_map.is_down(action, &x.input_state))
+    }
+
+    /// Whether the event presses an input bound to the action, for use in
+    /// [`Game::input`] and the widgets' input handling
+    pub fn action_triggered(action: &str, event: &InputEvent) -> bool {
+        Self::with(|x| x.input_map.is_pressed_by(action, event))
+    }
+
+    /// Look at the current bindings without cloning them
+    pub fn with_input_map<F, R>(f: F) -> R
+    where
+        F: FnOnce(&InputMap) -> R,
+    {
+        Self::with(|x| f(&x.input_map))
+    }
+
+    /// Change the bindings at runtime, like from a controls menu
+    pub fn set_input_map(map: InputMap) {
+        Self::with_mut(|x| x.input_map = map);
+    }
+
+    /// Returns whether the given mouse button is currently held down
+    pub fn is_mouse_button_down(button: MouseButton) -> bool {
+        Self::with(|x| x.input_state.is_mouse_down(button))
+    }
+
+    /// Mouse position extrapolated to the current instant from its recent velocity
+    pub fn extrapolated_mouse_position() -> Point {
+        Self::with(|x| x.input_state.extrapolated_mouse_position(Instant::now()))
+    }
+
+    /// Every position the mouse moved to in the last batch of events, oldest
+    /// first. When mouse motion is coalesced, widgets only see the last
+    /// move of a batch, so those that need the whole path, like drawing
+    /// tools, read it from here.
+    pub fn mouse_path() -> Vec<Point> {
+        Self::with(|x| x.input_state.mouse_path.clone())
+    }
+
+    /// Mouse position in the 0..1 range relative to the canvas size
+    pub fn mouse_position_normalized() -> Point {
+        Self::with(|x| x.input_state.mouse_position_normalized())
+    }
+
+    /// Mouse position in window coordinates, ignoring any canvas scaling
+    pub fn raw_mouse_position() -> Point {
+        Self::with(|x| x.input_state.raw_mouse_position)
+    }
+
+    /// Matrix mapping canvas coordinates into window coordinates
+    pub fn canvas_matrix() -> Matrix {
+        Self::with(|x| x.input_state.canvas_matrix())
+    }
+
+    /// The coordinate system the main thread renders the canvas with
+    pub fn coordinate_system() -> CoordinateSystem {
+        Self::with(|x| x.coordinate_system)
+    }
+
+    /// Change the coordinate system the canvas is rendered with, overriding
+    /// the one set with [`Builder::coordinate_system`](super::Builder::coordinate_system).
+    /// Takes effect from the next rendered frame.
+    pub fn set_coordinate_system(coordinate_system: CoordinateSystem) {
+        Self::with_mut(|x| {
+            x.coordinate_system = coordinate_system;
+            x.feedback
+                .push_back(FeedbackEvent::SetCoordinateSystem(coordinate_system));
+        });
+    }
+
+    /// Whether presenting frames waits for the display's vertical blank
+    pub fn vsync() -> bool {
+        Self::with(|x| x.vsync)
+    }
+
+    /// Turn vsync on or off. This rebuilds the renderer on the main thread,
+    /// which takes a moment, so only do this when the setting changes.
+    /// [`vsync`](Self::vsync) reports the new setting once the renderer has
+    /// been rebuilt with it, and keeps the old one if that fails.
+    pub fn set_vsync(vsync: bool) {
+        Self::with_mut(|x| {
+            if x.vsync != vsync {
+                x.feedback.push_back(FeedbackEvent::SetVsync(vsync));
+            }
+        });
+    }
+
+    /// Draw the next frame as soon as possible, even when the frame rate is
+    /// lowered while idle
+    pub fn request_redraw() {
+        Self::with_mut(|x| x.redraw_requested = true);
+    }
+
+    /// Draw a frame once the given time has passed, like
+    /// [`request_redraw`](Self::request_redraw) but later. This is for
+    /// things that change with time without any input, like a clock that
+    /// only needs a frame every second while the game is otherwise idle.
+    /// When asked several times, the earliest one wins.
+    pub fn request_redraw_in(delay: Duration) {
+        Self::with_mut(|x| {
+            let at = x.time_state.elapsed() + delay;
+            x.redraw_at = Some(x.redraw_at.map_or(at, |t| t.min(at)));
+        });
+    }
+
+    /// Write the next frame's recorded picture to a file in Skia's `.skp`
+    /// format, to look at how it was drawn call by call in the Skia
+    /// debugger. The frame is written as it is sent to the renderer, with
+    /// the letterboxing and everything else the game drew. The file is
+    /// written on a thread of its own, and failing to write it is logged.
+    pub fn dump_frame(path: impl Into<PathBuf>) {
+        Self::with_mut(|x| {
+            x.frame_dump = Some(path.into());
+            x.redraw_requested = true;
+        });
+    }
+
+    /// The filter the [`Framework`](crate::framework::Framework) draws
+    /// frames through, see [`Game::post_process`]
+    pub fn frame_filter() -> Option<ImageFilter> {
+        Self::with(|x| x.frame_filter.clone())
+    }
+
+    /// Draw every frame through a filter, or stop with `None`
+    pub fn set_frame_filter(filter: Option<ImageFilter>) {
+        Self::with_mut(|x| {
+            x.frame_filter = filter;
+            x.redraw_requested = true;
+        });
+    }
+
+    /// Close the app at the end of this update, calling [`Game::close`] but
+    /// skipping [`Game::on_close_requested`]. Meant for confirming a close
+    /// that was vetoed earlier, or for quit buttons.
+    pub fn exit() {
+        Self::with_mut(|x| x.exit_requested = true);
+    }
+
+    /// Stop the app at the end of this update with an error that it can't
+    /// go on without, like a missing GStreamer plugin. It's passed to
+    /// [`Game::crash`] the same way renderer failures are. Only the first
+    /// error is kept.
+    pub fn crash(error: impl Into<Error>) {
+        let error = error.into();
+        Self::with_mut(|x| {
+            x.crash.get_or_insert(error);
+        });
+    }
+
+    /// Look at the current theme without cloning it
+    pub fn with_theme<F, R>(f: F) -> R
+    where
+        F: FnOnce(&Theme) -> R,
+    {
+        Self::with(|x| f(&x.theme))
+    }
+
+    pub fn theme() -> Theme {
+        Self::with_theme(Theme::clone)
+    }
+
+    /// Replace the theme, redrawing with it right away
+    pub fn set_theme(theme: Theme) {
+        Self::with_mut(|x| {
+            x.theme = theme;
+            x.redraw_requested = true;
+        });
+    }
+
+    /// How fonts are rasterized when resolved, see [`Font::resolve`](crate::framework::widgets::Font::resolve)
+    pub fn font_rendering() -> FontRendering {
+        Self::with(|x| x.font_rendering)
+    }
+
+    /// Change how fonts are rasterized. Widgets keep the fonts they already
+    /// resolved, so this is best done before building them.
+    pub fn set_font_rendering(rendering: FontRendering) {
+        Self::with_mut(|x| x.font_rendering = rendering);
+    }
+
+    /// How far in from each edge of the window the user interface should
+    /// stay, for displays that hide their edges behind overscan or a notch
+    pub fn safe_area() -> Insets {
+        Self::with(|x| x.safe_area)
+    }
+
+    /// Change the safe area, laying out again within it
+    pub fn set_safe_area(insets: Insets) {
+        Self::with_mut(|x| {
+            x.safe_area = insets;
+            x.redraw_requested = true;
+        });
+    }
+
+    /// The operating system's color scheme preference, if it is being
+    /// followed with [`Builder::follow_color_scheme`](super::Builder::follow_color_scheme)
+    /// and could be detected
+    pub fn color_scheme() -> Option<ColorScheme> {
+        Self::with(|x| x.color_scheme)
+    }
+
+    pub(crate) fn set_color_scheme(scheme: Option<ColorScheme>, theme: Theme) {
+        Self::with_mut(|x| x.color_scheme = scheme);
+        Self::set_theme(theme);
+    }
+
+    /// Change the window title. Changes made in quick succession are
+    /// coalesced, so it's fine to call this every frame.
+    pub fn set_window_title(title: &str) {
+        // SDL takes a C string, which can't contain nul bytes.
+        let title = CString::new(title.replace('\0', "")).unwrap();
+        Self::with_mut(|x| {
+            x.feedback
+                .retain(|e| !matches!(e, FeedbackEvent::SetWindowTitle(_)));
+            x.feedback.push_back(FeedbackEvent::SetWindowTitle(title));
+        });
+    }
+
+    /// Handle an input event as if the user made it, at the start of the
+    /// next update. This is how on-screen controls like the
+    /// [`VirtualKeyboard`](crate::framework::widgets::VirtualKeyboard) type
+    /// into whatever has focus. These events aren't recorded, as whatever
+    /// sent them is replayed itself.
+    pub fn send_input(event: InputEvent) {
+        Self::with_mut(|x| x.synthetic_input.push_back(event));
+    }
+
+    /// Put text on the system clipboard
+    pub fn set_clipboard_text(text: impl Into<String>) {
+        let text = text.into();
+        Self::with_mut(|x| x.feedback.push_back(FeedbackEvent::SetClipboardText(text)));
+    }
+
+    /// The system cursor shape being shown, if the software cursor is
+    /// replaced by one. A hover cursor wins over the one set with
+    /// [`set_cursor`](Self::set_cursor).
+    pub fn cursor() -> Option<SystemCursor> {
+        Self::with(|x| x.hover_cursor.or(x.cursor))
+    }
+
+    /// Show the operating system's cursor with the given shape instead of
+    /// the software cursor, or go back to it with `None`
+    pub fn set_cursor(cursor: Option<SystemCursor>) {
+        Self::with_mut(|x| x.cursor = cursor);
+    }
+
+    /// Show a cursor shape while the mouse is over something, like an I-beam
+    /// over text or resize arrows over a splitter. It is reset every time
+    /// the mouse moves, so call this on each [`InputEvent::MouseMove`] that
+    /// is over the widget.
+    pub fn set_hover_cursor(cursor: SystemCursor) {
+        Self::with_mut(|x| x.hover_cursor = Some(cursor));
+    }
+
+    /// Tell the main thread when the cursor to show has changed
+    fn sync_cursor() {
+        Self::with_mut(|x| {
+            let cursor = x.hover_cursor.or(x.cursor);
+            if cursor != x.shown_cursor {
+                x.shown_cursor = cursor;
+                x.feedback.push_back(FeedbackEvent::SetCursor(cursor));
+            }
+        });
+    }
+
+    /// The widget with keyboard focus, see [`Focus`]
+    pub fn focused() -> Option<ID> {
+        Self::with(|x| x.focus.focused())
+    }
+
+    /// Give a widget keyboard focus, without showing a focus ring
+    pub fn set_focus(id: Option<ID>) {
+        Self::with_mut(|x| x.focus.set(id, false));
+    }
+
+    /// The annotated widgets drawn in the last frame, see
+    /// [`accessibility`](crate::framework::accessibility)
+    pub fn accessibility_tree() -> Vec<AccessNode> {
+        Self::with(|x| x.accessibility.tree().to_vec())
+    }
+
+    /// Where the widget with the given ID was last drawn, in canvas
+    /// coordinates. See [`Bounds`] for when this is up to date.
+    pub fn widget_bounds(id: ID) -> Option<Rect> {
+        Self::with(|x| x.bounds.get(id))
+    }
+
+    /// The canvas's matrix, extended to where it draws in the frame. This is
+    /// what widget bounds are in, and differs from
+    /// [`Canvas::total_matrix`] for offscreen canvases like a
+    /// [`Layer`](crate::framework::widgets::Layer)'s.
+    pub fn window_matrix(canvas: &Canvas) -> Matrix {
+        Self::with(|x| Matrix::concat(&x.offscreen_matrix, &canvas.total_matrix()))
+    }
+
+    /// Call a closure with the value registered under the given ID, if it is
+    /// still alive, is of the given type, and isn't in use.
+    ///
+    /// Values are usually widgets wrapped in
+    /// [`Tagged`](crate::framework::widgets::Tagged). A widget is in use
+    /// while the framework is calling into it, so looking it up from within
+    /// its own subtree returns `None`.
+    pub fn with_widget<T, F, R>(id: ID, f: F) -> Option<R>
+    where
+        T: 'static,
+        F: FnOnce(&mut T) -> R,
+    {
+        // The registry borrow has to end before calling the closure, which
+        // may well use the state itself.
+        let entry = Self::with(|x| x.registry.get(id))?;
+        let mut value = entry.try_lock().ok()?;
+        value.downcast_mut::<T>().map(f)
+    }
+
+    /// Run a closure on the background task pool, returning a handle to poll
+    /// for its result from the update loop. Like the rest of the state, the
+    /// pool belongs to the game thread, so this panics when called from a
+    /// task or any other thread.
+    pub fn spawn_task<F, T>(f: F) -> TaskHandle<T>
+    where
+        F: 'static + Send + FnOnce() -> T,
+        T: 'static + Send,
+    {
+        Self::with(|x| x.task_pool.spawn(f))
+    }
+}
+
+/// Cleans up when the game thread panics: the sounds are stopped, and the
+/// main thread is told to exit rather than wait for frames that never come.
+struct PanicGuard {
+    feedback_tx: SyncSender<FeedbackEvent>,
+}
+
+impl Drop for PanicGuard {
+    fn drop(&mut self) {
+        if std::thread::panicking() {
+            AudioMixer::stop_all();
+            // If the queue is full, the main thread still exits once it has
+            // drained it and finds the channel closed.
+            let _ = self.feedback_tx.try_send(FeedbackEvent::Exit);
+        }
+    }
+}
+
+/// What the game thread does when the next frame is due but the main thread
+/// hasn't presented the last one yet, see
+/// [`Builder::frame_policy`](super::Builder::frame_policy)
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum FramePolicy {
+    /// Draw the new frame anyway, and throw it away if the main thread is
+    /// still busy. The game thread never waits, so input stays responsive,
+    /// but a slow renderer shows as skipped frames.
+    DropFrame,
+    /// Wait up to the given time for the last frame to be presented before
+    /// drawing the new one, which is dropped if the main thread is still
+    /// busy then. Fewer frames are skipped, at the cost of the game thread
+    /// falling behind input while it waits.
+    BlockWithTimeout(Duration),
+}
+
+/// What each frame starts out as, see
+/// [`Builder::clear_mode`](super::Builder::clear_mode)
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum ClearMode {
+    /// Fill the frame with a color, a dark grey by default
+    Color(Color),
+    /// Clear the frame to transparent. The window is always presented as
+    /// opaque, so this doesn't show what is behind it, and what the screen
+    /// shows where the game draws nothing is up to the driver, usually
+    /// black. It only makes a difference where the frame's alpha is kept,
+    /// like in frame dumps.
+    Transparent,
+    /// Leave the frame as it is. What is there depends on the driver, so
+    /// the game has to cover the whole frame itself.
+    None,
+}
+
+impl ClearMode {
+    fn clear(&self, canvas: &mut Canvas, letterbox: bool) {
+        let color = match self {
+            // The bars around a letterboxed game are always black
+            Self::Color(_) if letterbox => Color::BLACK,
+            Self::Color(color) => *color,
+            Self::Transparent => Color::TRANSPARENT,
+            Self::None => return,
+        };
+        canvas.clear(color);
+    }
+}
+
+/// Where the window opens, see [`Builder::window_position`](super::Builder::window_position)
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum WindowPosition {
+    /// Wherever the window manager puts it
+    Default,
+    /// In the middle of the display
+    Centered,
+    /// With its top left corner here, relative to the display
+    At(i32, i32),
+}
+
+/// Options the game thread runs with, collected by the [`Builder`](super::Builder)
+#[derive(Clone)]
+pub struct Config {
+    pub design_resolution: Option<Size>,
+    pub max_frame_lag: Duration,
+    pub frame_policy: FramePolicy,
+    pub record_input: Option<PathBuf>,
+    pub replay_input: Option<PathBuf>,
+    /// The action bindings to start with
+    pub input_map: InputMap,
+    pub axis_settings: AxisSettings,
+    /// Frame rate to draw at instead of the display's refresh rate
+    pub frame_rate: Option<f64>,
+    /// Rate to call [`Game::fixed_update`] at, per second
+    pub fixed_update_rate: Option<f64>,
+    /// Frame rate to drop to, and how long without input before doing so
+    pub idle_frame_rate: Option<(f64, Duration)>,
+    /// Only pass on the last of several mouse moves in a row
+    pub coalesce_mouse_motion: bool,
+    /// Number of worker threads in the background task pool
+    pub task_threads: usize,
+    pub renderer: RendererConfig,
+    /// Samples per pixel, see [`Builder::msaa`](super::Builder::msaa)
+    pub msaa: usize,
+    pub theme: Theme,
+    /// How fonts are rasterized unless a widget says otherwise
+    pub font_rendering: FontRendering,
+    /// Light and dark themes to switch between with the OS color scheme
+    pub follow_color_scheme: Option<(Theme, Theme)>,
+    /// A key that requests closing the app like the window's close button
+    pub exit_key: Option<Keycode>,
+    /// Space to keep the user interface out of at the window's edges
+    pub safe_area: Insets,
+    /// Where the window opens, relative to the display
+    pub window_position: WindowPosition,
+    /// The index of the display to open the window on
+    pub monitor: Option<usize>,
+    /// Draw common paints and effects once at startup to compile their
+    /// pipelines ahead of time
+    pub warm_up: bool,
+    /// What frames are cleared to before drawing
+    pub clear: ClearMode,
+    /// Skip drawing widgets that are entirely out of view
+    pub cull_offscreen: bool,
+    /// Raise the priority of the game and draw threads
+    pub high_priority_threads: bool,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            design_resolution: None,
+            max_frame_lag: Duration::MILLISECOND * 8,
+            frame_policy: FramePolicy::DropFrame,
+            record_input: None,
+            replay_input: None,
+            input_map: InputMap::new(),
+            axis_settings: AxisSettings::default(),
+            frame_rate: None,
+            fixed_update_rate: None,
+            idle_frame_rate: None,
+            coalesce_mouse_motion: true,
+            task_threads: 4,
+            renderer: RendererConfig::default(),
+            msaa: 1,
+            theme: Theme::default(),
+            font_rendering: FontRendering::default(),
+            follow_color_scheme: None,
+            exit_key: None,
+            safe_area: Insets::uniform(0.0),
+            window_position: WindowPosition::Default,
+            monitor: None,
+            warm_up: false,
+            clear: ClearMode::Color(Runner::BACKGROUND),
+            cull_offscreen: true,
+            high_priority_threads: false,
+        }
+    }
+}
+
+/// Options the renderer is built with, kept around to rebuild it when they
+/// change at runtime
+#[derive(Clone)]
+pub struct RendererConfig {
+    pub app_name: Option<CString>,
+    pub coordinate_system: CoordinateSystem,
+    pub vsync: bool,
+}
+
+impl RendererConfig {
+    fn builder(&self) -> RendererBuilder {
+        let mut builder = RendererBuilder::new()
+            .use_vulkan_debug_layer(false)
+            .coordinate_system(self.coordinate_system)
+            .present_mode_priority(if self.vsync {
+                vec![PresentMode::Fifo]
+            } else {
+                vec![PresentMode::Immediate]
+            });
+        if let Some(app_name) = &self.app_name {
+            builder = builder.app_name(app_name.clone());
+        }
+        builder
+    }
+}
+
+impl Default for RendererConfig {
+    fn default() -> Self {
+        Self {
+            app_name: None,
+            coordinate_system: CoordinateSystem::Logical,
+            vsync: false,
+        }
+    }
+}
+
+/// What is needed to start recording a frame
+struct Frame {
+    window_size: Size,
+    /// The canvas matrix and the design resolution's rectangle, when
+    /// letterboxing
+    letterbox: Option<(Matrix, Rect)>,
+    /// Where to write the picture to, see [`State::dump_frame`]
+    dump: Option<PathBuf>,
+    clear: ClearMode,
+    /// Applied to the whole frame, see [`Game::post_process`]
+    filter: Option<ImageFilter>,
+}
+
+impl Frame {
+    /// Record a frame with `rec`. Recorders are reused from frame to frame,
+    /// which Skia allows once the last recording is finished. This saves
+    /// setting up the recording canvas every time, though the recorded
+    /// commands are still allocated anew, as they go with each picture.
+    fn record(&self, rec: &mut PictureRecorder, draw: impl FnOnce(&mut Canvas)) -> Picture {
+        let canvas = rec.begin_recording(Rect::from_size(self.window_size), None);
+        if let Some((matrix, design_rect)) = self.letterbox {
+            self.clear.clear(canvas, true);
+            canvas.concat(&matrix);
+            canvas.clip_rect(design_rect, None, None);
+            self.clear.clear(canvas, false);
+        }
+        draw(canvas);
+        let pic = rec
+            .finish_recording_as_picture(None)
+            .expect("Failed to finish recording picture while rendering");
+        if let Some(path) = self.dump.clone() {
+            // Serializing and writing a busy frame takes a while, so it's
+            // done on a thread of its own rather than the one drawing frames
+            let dump = pic.clone();
+            let spawned = ThreadBuilder::new()
+                .name("tachibana-frame-dump".to_owned())
+                .spawn(move || {
+                    if let Err(e) = std::fs::write(&path, dump.serialize().as_bytes()) {
+                        eprintln!("Failed to write frame to {}: {}", path.display(), e);
+                    }
+                });
+            if let Err(e) = spawned {
+                eprintln!("Failed to start writing frame: {}", e);
+            }
+        }
+        pic
+    }
+
+    /// Draw the game's frame through the post processing filter, if there
+    /// is one. The overlay is drawn outside of this, so it isn't filtered.
+    fn filtered(&self, canvas: &mut Canvas, draw: impl FnOnce(&mut Canvas)) {
+        match &self.filter {
+            Some(filter) => {
+                let mut paint = Paint::default();
+                paint.set_image_filter(filter.clone());
+                canvas.save_layer(&SaveLayerRec::default().paint(&paint));
+                draw(canvas);
+                canvas.restore();
+            }
+            None => draw(canvas),
+        }
+    }
+
+    /// Record drawing to be played back inside of [`record`](Self::record),
+    /// where the letterboxing is already applied
+    fn record_overlay(&self, rec: &mut PictureRecorder, draw: impl FnOnce(&mut Canvas)) -> Picture {
+        let bounds = match self.letterbox {
+            Some((_, design_rect)) => design_rect,
+            None => Rect::from_size(self.window_size),
+        };
+        draw(rec.begin_recording(bounds, None));
+        rec.finish_recording_as_picture(None)
+            .expect("Failed to finish recording overlay picture")
+    }
+}
+
+/// Whether a picture is on its way to the main thread. It is set by the
+/// game thread when it sends one and cleared by the main thread once it has
+/// been presented, which wakes up a game thread waiting for that.
+struct FramePending {
+    pending: Mutex<bool>,
+    presented: Condvar,
+}
+
+impl FramePending {
+    fn new() -> Self {
+        Self {
+            pending: Mutex::new(false),
+            presented: Condvar::new(),
+        }
+    }
+
+    fn is_set(&self) -> bool {
+        *self.pending.lock().unwrap()
+    }
+
+    fn set(&self) {
+        *self.pending.lock().unwrap() = true;
+    }
+
+    fn clear(&self) {
+        *self.pending.lock().unwrap() = false;
+        self.presented.notify_all();
+    }
+
+    /// Wait up to `timeout` for the pending picture to be presented,
+    /// returning whether it was
+    fn wait(&self, timeout: Duration) -> bool {
+        let pending = self.pending.lock().unwrap();
+        let (pending, _) = self
+            .presented
+            .wait_timeout_while(pending, timeout, |pending| *pending)
+            .unwrap();
+        !*pending
+    }
+}
+
+/// An offscreen surface with several samples per pixel for
+/// [`Builder::msaa`](super::Builder::msaa). The renderer only draws to the
+/// swapchain's images, which have one sample, so frames are drawn here and
+/// then resolved onto them.
+struct MsaaSurface {
+    samples: usize,
+    surface: Option<Surface>,
+}
+
+impl MsaaSurface {
+    fn new(samples: usize) -> Self {
+        Self {
+            samples,
+            surface: None,
+        }
+    }
+
+    /// The canvas to draw the frame to instead of `target`, made again when
+    /// the window's size changes, or `None` if the GPU can't make one
+    fn canvas(&mut self, target: &mut Canvas) -> Option<&mut Canvas> {
+        let info = target.image_info();
+        let stale = self.surface.as_ref().map_or(true, |s| {
+            s.width() != info.width() || s.height() != info.height()
+        });
+        if stale {
+            let mut context = target.recording_context()?;
+            self.surface = Surface::new_render_target(
+                &mut context,
+                Budgeted::YES,
+                &info,
+                self.samples,
+                SurfaceOrigin::TopLeft,
+                None,
+                false,
+            );
+        }
+        self.surface.as_mut().map(|s| s.canvas())
+    }
+
+    /// Draw the frame drawn to [`canvas`](Self::canvas) onto `target`
+    fn resolve(&mut self, target: &mut Canvas) {
+        if let Some(surface) = &mut self.surface {
+            target.reset_matrix();
+            surface.draw(target, (0.0, 0.0), None);
+        }
+    }
+
+    /// Forget the surface, which belongs to the renderer's GPU context, for
+    /// when the renderer is built again
+    fn reset(&mut self) {
+        self.surface = None;
+    }
 }
 
 pub struct Runner;
@@ -142,91 +1077,314 @@ pub struct Runner;
 impl Runner {
     pub const PIC_QUEUE_LENGTH: usize = 1;
     pub const EVENT_QUEUE_SIZE: usize = 8;
-    pub const FEEDBACK_QUEUE_SIZE: usize = 1;
+    pub const FEEDBACK_QUEUE_SIZE: usize = 8;
 
     pub const BACKGROUND: Color = Color::from_argb(255, 10, 10, 10);
+    /// The most [`Game::fixed_update`] steps run to catch up in one go
+    pub const MAX_FIXED_STEPS: u32 = 8;
+    /// The time between frames when the display's refresh rate is unknown
+    pub const DEFAULT_FRAME_TIME: Duration = Duration::from_millis(8); // 120 fps
 
+    /// Start the game and run it until it exits. Anything that fails while
+    /// starting up is returned, and failures once running are passed to
+    /// [`Game::crash`].
     pub fn run<F, T>(
         game: F,
         inner_size: LogicalSize,
         window_title: &str,
-        renderer_builder: RendererBuilder,
-    ) where
+        config: Config,
+    ) -> Result<(), Error>
+    where
         F: 'static + Send + FnOnce() -> T,
         T: Game,
     {
-        let sdl_context = sdl2::init().expect("Failed to initialize SDL2");
-        let video_subsystem = sdl_context
-            .video()
-            .expect("Failed to create SDL2 video subsystem");
+        let sdl_context = sdl2::init().map_err(Error::SdlInit)?;
+        let video_subsystem = sdl_context.video().map_err(Error::SdlInit)?;
 
-        let sdl_window = video_subsystem
-            .window(window_title, inner_size.width, inner_size.height)
-            .resizable()
-            .build()
-            .expect("Failed to create game window");
+        let mut window_builder =
+            video_subsystem.window(window_title, inner_size.width, inner_size.height);
+        window_builder.resizable();
+        // Without this, displays that scale windows get a blurry upscaled
+        // picture instead of one drawn at their resolution
+        window_builder.allow_highdpi();
+        if let Some((x, y)) = Self::window_position(&video_subsystem, &config, inner_size) {
+            window_builder.position(x, y);
+        }
+        let sdl_window = window_builder.build().map_err(Error::WindowCreation)?;
 
         let window = Sdl2Window::new(&sdl_window);
         sdl_context.mouse().show_cursor(false);
+        let mut event_pump = sdl_context.event_pump().map_err(Error::SdlInit)?;
+        // SDL timestamps events in milliseconds since it was initialized, so
+        // this is what they are counted from
+        let sdl_ticks = sdl_context.timer().map_err(Error::SdlInit)?.ticks();
+        let sdl_epoch = Instant::now()
+            .checked_sub(Duration::from_millis(sdl_ticks.into()))
+            .unwrap_or_else(Instant::now);
+        gstreamer::init().map_err(|e| Error::GStreamerInit(Box::new(e)))?;
 
         let (pic_tx, pic_rx) = sync_channel(Self::PIC_QUEUE_LENGTH);
         let (event_tx, event_rx) = sync_channel(Self::EVENT_QUEUE_SIZE);
         let (feedback_tx, feedback_rx) = sync_channel(Self::FEEDBACK_QUEUE_SIZE);
+        // Set while a picture is on its way to the main thread and cleared
+        // once it has been presented, so the game thread draws the next
+        // frame right after a present instead of on a clock of its own.
+        let frame_pending = Arc::new(FramePending::new());
+        let game_frame_pending = frame_pending.clone();
+        // The time between frames in nanoseconds, following the refresh rate
+        // of the display the window is on
+        let mut display = sdl_window.display_index().ok();
+        let mut scale_factor = Self::window_scale_factor(&sdl_window);
+        let frame_time = Arc::new(AtomicU64::new(
+            Self::display_frame_time(&sdl_window).as_nanos() as u64,
+        ));
+        let game_frame_time = frame_time.clone();
 
-        spawn(move || {
-            gstreamer::init().expect("Failed to initialize GStreamer");
-
-            let input_state = InputState::new(inner_size);
-            let time_state = TimeState::new();
-            let time_state_draw = TimeState::new();
-            State::STATE.with(|x| {
-                *x.borrow_mut() = Some(State {
-                    input_state,
-                    time_state,
-                    time_state_draw,
-                    font_set: Box::new(DefaultFontSet::new()),
-                    id_keeper: 0,
-                });
-            });
+        let mut renderer_config = config.renderer.clone();
+        let warm_up = config.warm_up;
+        let clear = config.clear;
+        let mut msaa = Some(config.msaa)
+            .filter(|samples| *samples > 1)
+            .map(MsaaSurface::new);
+        // Built before the game thread is started, so that a failure doesn't
+        // leave it running with nobody to present its frames
+        let mut renderer = renderer_config
+            .builder()
+            .build(&window)
+            .map_err(|e| Error::RendererCreation(e.to_string().into()))?;
+
+        let game_thread = ThreadBuilder::new().name("tachibana-game".to_owned());
+        let spawned = game_thread.spawn(move || {
+            if config.high_priority_threads {
+                Self::raise_priority();
+            }
+            let _guard = PanicGuard {
+                feedback_tx: feedback_tx.clone(),
+            };
+
+            State::init(
+                InputState::new(inner_size, config.design_resolution),
+                &config,
+            );
+
+            let mut theme_follower = config
+                .follow_color_scheme
+                .clone()
+                .map(|(light, dark)| ThemeFollower::new(light, dark));
+            if let Some(follower) = &mut theme_follower {
+                let (scheme, theme) = follower.initial();
+                State::set_color_scheme(scheme, theme);
+            }
 
             let mut game = game();
-            game.set_size(
-                State::STATE.with(|x| x.borrow().as_ref().unwrap().input_state.window_size),
+            game.set_size(State::with(|x| x.input_state.canvas_size()));
+            Self::game_thread(
+                game,
+                config,
+                theme_follower,
+                event_rx,
+                pic_tx,
+                feedback_tx,
+                game_frame_pending,
+                game_frame_time,
             );
-            Self::game_thread(game, event_rx, pic_tx, feedback_tx);
+            State::with_mut(|x| x.task_pool.shutdown());
         });
+        spawned.map_err(Error::ThreadSpawn)?;
 
-        let mut renderer = renderer_builder
-            .build(&window)
-            .expect("Failed to create renderer");
+        if warm_up {
+            let result = renderer.draw(&window, |canvas, _| {
+                super::warm_up::warm_up(canvas);
+                canvas.clear(Self::BACKGROUND);
+            });
+            if let Err(e) = result {
+                eprintln!("Failed to warm up the renderer: {}", e);
+            }
+        }
 
-        let mut event_pump = sdl_context
-            .event_pump()
-            .expect("Failed to create SDL2 event pump");
+        // Controllers only send events while they are open, so every one
+        // that is plugged in is opened and kept here
+        let controller_subsystem = sdl_context
+            .game_controller()
+            .map_err(|e| eprintln!("Failed to initialize controller support: {}", e))
+            .ok();
+        let mut controllers: Vec<GameController> = Vec::new();
 
+        let mut coordinate_system = None;
+        // SDL cursors have to outlive their use, so the ones that were used
+        // are kept around here
+        let mut cursors: Vec<(SystemCursor, Cursor)> = Vec::new();
+
+        // The game thread starts out assuming no scaling
+        if scale_factor != 1.0 {
+            let _ = event_tx.send(Event::ScaleFactor(scale_factor));
+        }
         'events: loop {
             match feedback_rx.try_recv() {
                 Ok(event) => match event {
                     FeedbackEvent::Exit => {
                         break 'events;
                     }
+                    FeedbackEvent::SetCoordinateSystem(c) => {
+                        renderer_config.coordinate_system = c;
+                        coordinate_system = Some(c);
+                    }
+                    FeedbackEvent::SetWindowTitle(title) => {
+                        // SAFETY: The window pointer is valid for as long as
+                        // `sdl_window` lives, and SDL copies the title.
+                        // `Window::set_title` requires a mutable borrow,
+                        // which isn't possible while the renderer's window
+                        // wrapper holds on to it.
+                        unsafe {
+                            sdl2::sys::SDL_SetWindowTitle(sdl_window.raw(), title.as_ptr());
+                        }
+                    }
+                    FeedbackEvent::SetCursor(None) => {
+                        sdl_context.mouse().show_cursor(false);
+                    }
+                    FeedbackEvent::SetCursor(Some(shape)) => {
+                        if !cursors.iter().any(|(s, _)| *s == shape) {
+                            match Cursor::from_system(shape) {
+                                Ok(cursor) => cursors.push((shape, cursor)),
+                                Err(e) => eprintln!("Failed to create {:?} cursor: {}", shape, e),
+                            }
+                        }
+                        if let Some((_, cursor)) = cursors.iter().find(|(s, _)| *s == shape) {
+                            cursor.set();
+                            sdl_context.mouse().show_cursor(true);
+                        }
+                    }
+                    FeedbackEvent::SetClipboardText(text) => {
+                        if let Err(e) = video_subsystem.clipboard().set_clipboard_text(&text) {
+                            eprintln!("Failed to copy text to the clipboard: {}", e);
+                        }
+                    }
+                    FeedbackEvent::SetVsync(vsync) => {
+                        let previous = renderer_config.vsync;
+                        renderer_config.vsync = vsync;
+                        // The window can only have one surface at a time, so
+                        // the old renderer has to go before the new one is
+                        // built.
+                        drop(renderer);
+                        if let Some(msaa) = &mut msaa {
+                            msaa.reset();
+                        }
+                        renderer = match renderer_config.builder().build(&window) {
+                            Ok(r) => r,
+                            Err(e) => {
+                                eprintln!("Failed to rebuild renderer with vsync {}: {}", vsync, e);
+                                renderer_config.vsync = previous;
+                                match renderer_config.builder().build(&window) {
+                                    Ok(r) => r,
+                                    Err(e) => {
+                                        let e = Error::RendererCreation(e.to_string().into());
+                                        let _ = event_tx.send(Event::Crash(e));
+                                        break 'events;
+                                    }
+                                }
+                            }
+                        };
+                        if event_tx.send(Event::Vsync(renderer_config.vsync)).is_err() {
+                            break 'events;
+                        }
+                    }
                 },
                 Err(e) => match e {
                     TryRecvError::Empty => {
                         for event in event_pump.poll_iter() {
-                            if event_tx.send(Event::Sdl2Event(event)).is_err() {
+                            if let Sdl2Event::Window {
+                                win_event: WindowEvent::Moved(..),
+                                ..
+                            } = event
+                            {
+                                let moved_to = sdl_window.display_index().ok();
+                                if moved_to != display {
+                                    display = moved_to;
+                                    let t = Self::display_frame_time(&sdl_window);
+                                    frame_time.store(t.as_nanos() as u64, Ordering::Relaxed);
+                                }
+                            }
+                            // Moving between displays with different scales
+                            // changes the drawable size but not the window
+                            // size, so it's checked on any window event
+                            if let Sdl2Event::Window { .. } = event {
+                                let scale = Self::window_scale_factor(&sdl_window);
+                                if scale != scale_factor {
+                                    scale_factor = scale;
+                                    if event_tx.send(Event::ScaleFactor(scale)).is_err() {
+                                        break 'events;
+                                    }
+                                }
+                            }
+                            match &event {
+                                Sdl2Event::ControllerDeviceAdded { which, .. } => {
+                                    if let Some(subsystem) = &controller_subsystem {
+                                        match subsystem.open(*which) {
+                                            Ok(c) => controllers.push(c),
+                                            Err(e) => {
+                                                eprintln!("Failed to open controller: {}", e)
+                                            }
+                                        }
+                                    }
+                                }
+                                Sdl2Event::ControllerDeviceRemoved { which, .. } => {
+                                    controllers.retain(|c| c.instance_id() != *which);
+                                }
+                                _ => {}
+                            }
+                            let time = Self::event_instant(&event, sdl_epoch);
+                            if event_tx.send(Event::Sdl2Event(event, time)).is_err() {
                                 break 'events;
                             }
                         }
                         match pic_rx.try_recv() {
                             Ok(pic) => {
-                                if let Err(e) = renderer.draw(&window, |canvas, _| {
-                                    canvas.clear(Self::BACKGROUND);
-                                    canvas.draw_picture(pic, Some(&Matrix::default()), None);
-                                }) {
-                                    let _ = event_tx.send(Event::Crash(e.into()));
-                                    break 'events;
+                                let mut draw = |renderer: &mut Renderer| {
+                                    renderer.draw(&window, |canvas, helper| {
+                                        let draw_frame = |canvas: &mut Canvas| {
+                                            if let Some(c) = coordinate_system {
+                                                Self::apply_coordinate_system(canvas, &helper, c);
+                                            }
+                                            clear.clear(canvas, false);
+                                            canvas.draw_picture(
+                                                &pic,
+                                                Some(&Matrix::default()),
+                                                None,
+                                            );
+                                        };
+                                        if let Some(msaa) = &mut msaa {
+                                            if let Some(offscreen) = msaa.canvas(canvas) {
+                                                draw_frame(offscreen);
+                                                msaa.resolve(canvas);
+                                                return;
+                                            }
+                                        }
+                                        draw_frame(canvas);
+                                    })
+                                };
+                                // A minimized window has nothing to draw
+                                // to, so its frames are skipped until it is
+                                // shown again
+                                let (width, height) = sdl_window.vulkan_drawable_size();
+                                let result = if width == 0 || height == 0 {
+                                    Ok(())
+                                } else {
+                                    draw(&mut renderer)
+                                };
+                                match result {
+                                    Ok(()) => {}
+                                    // The renderer builds the swapchain again
+                                    // when it's out of date, but that can
+                                    // fail too, like while the window is
+                                    // being resized. The frame is skipped
+                                    // and the next one tries again.
+                                    Err(e) if Self::is_recoverable(e) => {}
+                                    Err(e) => {
+                                        let _ = event_tx.send(Event::Crash(e.into()));
+                                        break 'events;
+                                    }
                                 }
+                                frame_pending.clear();
                             }
                             Err(e) => match e {
                                 TryRecvError::Empty => sleep(Duration::MILLISECOND),
@@ -238,55 +1396,402 @@ impl Runner {
                 },
             }
         }
+        Ok(())
+    }
+
+    /// Like [`run`](Self::run), but panics if the game can't be started
+    pub fn run_or_panic<F, T>(game: F, inner_size: LogicalSize, window_title: &str, config: Config)
+    where
+        F: 'static + Send + FnOnce() -> T,
+        T: Game,
+    {
+        if let Err(e) = Self::run(game, inner_size, window_title, config) {
+            panic!("{}", e);
+        }
+    }
+
+    /// Whether a renderer error only means the swapchain didn't match the
+    /// window for this frame, rather than that the renderer is broken
+    fn is_recoverable(error: VkResult) -> bool {
+        matches!(
+            error,
+            VkResult::ERROR_OUT_OF_DATE_KHR | VkResult::SUBOPTIMAL_KHR
+        )
+    }
+
+    /// Pixels per window coordinate, which is the scale the renderer's
+    /// logical coordinates use
+    fn window_scale_factor(window: &sdl2::video::Window) -> scalar {
+        let (width, _) = window.size();
+        let (drawable_width, _) = window.vulkan_drawable_size();
+        if width == 0 || drawable_width == 0 {
+            1.0
+        } else {
+            drawable_width as scalar / width as scalar
+        }
+    }
+
+    /// The time between frames that matches the refresh rate of the display
+    /// the window is on. It is cut a little short, since waiting for the
+    /// last frame to be presented already keeps the pace, and a frame that
+    /// is ready just too late would miss a refresh.
+    fn display_frame_time(window: &sdl2::video::Window) -> Duration {
+        match window.display_mode() {
+            Ok(mode) if mode.refresh_rate > 0 => {
+                Duration::from_secs_f64(0.9 / mode.refresh_rate as f64)
+            }
+            _ => Self::DEFAULT_FRAME_TIME,
+        }
+    }
+
+    /// Where to put the window on the desktop, or `None` to leave it to the
+    /// window manager. Positions are kept within the display, so that the
+    /// window can't open off screen.
+    fn window_position(
+        video: &sdl2::VideoSubsystem,
+        config: &Config,
+        size: LogicalSize,
+    ) -> Option<(i32, i32)> {
+        let displays = video.num_video_displays().unwrap_or(1).max(1);
+        let index = match config.monitor {
+            Some(m) if m as i32 >= displays => {
+                eprintln!("There is no display {}, using the primary one", m);
+                0
+            }
+            Some(m) => m as i32,
+            None => 0,
+        };
+        let bounds = video.display_bounds(index).ok()?;
+        let (width, height) = (size.width as i32, size.height as i32);
+        let (x, y) = match (config.window_position, config.monitor) {
+            (WindowPosition::Default, None) => return None,
+            (WindowPosition::Default, Some(_)) | (WindowPosition::Centered, _) => (
+                (bounds.width() as i32 - width) / 2,
+                (bounds.height() as i32 - height) / 2,
+            ),
+            (WindowPosition::At(x, y), _) => (x, y),
+        };
+        let max_x = (bounds.width() as i32 - width).max(0);
+        let max_y = (bounds.height() as i32 - height).max(0);
+        Some((
+            bounds.x() + x.max(0).min(max_x),
+            bounds.y() + y.max(0).min(max_y),
+        ))
+    }
+
+    /// Redo what the renderer does with its configured coordinate system
+    /// before handing over the canvas, with another coordinate system
+    fn apply_coordinate_system(
+        canvas: &mut Canvas,
+        helper: &CoordinateSystemHelper,
+        coordinate_system: CoordinateSystem,
+    ) {
+        let result = match coordinate_system {
+            CoordinateSystem::None => {
+                canvas.reset_matrix();
+                Ok(())
+            }
+            CoordinateSystem::Physical => {
+                helper.use_physical_coordinates(canvas);
+                Ok(())
+            }
+            CoordinateSystem::Logical => {
+                helper.use_logical_coordinates(canvas);
+                Ok(())
+            }
+            CoordinateSystem::VisibleRange(range, scale_to_fit) => {
+                helper.use_visible_range(canvas, range, scale_to_fit)
+            }
+            CoordinateSystem::FixedWidth(center, x_half_extents) => {
+                helper.use_fixed_width(canvas, center, x_half_extents)
+            }
+        };
+        if result.is_err() {
+            eprintln!("Failed to apply coordinate system");
+        }
+    }
+
+    /// Pass queued requests on to the main thread. Whatever doesn't fit into
+    /// the channel is kept for the next frame, since blocking here could
+    /// deadlock with the main thread blocking on sending events.
+    /// Returns whether the main thread is gone.
+    fn flush_feedback(feedback_tx: &SyncSender<FeedbackEvent>) -> bool {
+        State::with_mut(|x| {
+            while let Some(event) = x.feedback.pop_front() {
+                match feedback_tx.try_send(event) {
+                    Ok(()) => {}
+                    Err(TrySendError::Full(event)) => {
+                        x.feedback.push_front(event);
+                        return false;
+                    }
+                    Err(TrySendError::Disconnected(_)) => return true,
+                }
+            }
+            false
+        })
     }
 
     fn game_thread(
         mut game: impl Game,
+        config: Config,
+        mut theme_follower: Option<ThemeFollower>,
         event_rx: Receiver<Event>,
         pic_tx: SyncSender<Picture>,
         feedback_tx: SyncSender<FeedbackEvent>,
+        frame_pending: Arc<FramePending>,
+        display_frame_time: Arc<AtomicU64>,
     ) {
         let target_update_time = Duration::MILLISECOND; // 1000 fps
-        let target_frame_time = Duration::MILLISECOND * 8; // 120 fps
+        let idle_frame_time = config
+            .idle_frame_rate
+            .map(|(fps, after)| (Duration::from_secs_f64(1.0 / fps), after));
         let mut last_frame = Instant::now();
+        let mut draw_worker = None;
+        let mut picture_recorder = PictureRecorder::new();
+        let fixed_step = config
+            .fixed_update_rate
+            .map(|rate| Duration::from_secs_f64(1.0 / rate));
+        // How far the fixed updates have simulated, on the clock of
+        // `State::elapsed`
+        let mut fixed_time = State::elapsed();
+
+        let mut recorder = config.record_input.as_ref().and_then(|path| {
+            Recorder::create(path)
+                .map_err(|e| eprintln!("Failed to create input recording: {}", e))
+                .ok()
+        });
+        let mut replay = config.replay_input.as_ref().and_then(|path| {
+            Replay::open(path)
+                .map_err(|e| eprintln!("Failed to open input replay: {}", e))
+                .ok()
+        });
 
         loop {
+            if let Some(follower) = &mut theme_follower {
+                follower.poll();
+            }
+            if let Some(step) = fixed_step {
+                let now = State::elapsed();
+                // After a long stall, drop the time rather than running a
+                // burst of steps that would only stall again
+                if now.checked_sub(fixed_time).unwrap_or(Duration::ZERO)
+                    > step * Self::MAX_FIXED_STEPS
+                {
+                    fixed_time = now - step * Self::MAX_FIXED_STEPS;
+                }
+                while fixed_time + step <= now {
+                    game.fixed_update(step);
+                    fixed_time += step;
+                }
+                let alpha = (now - fixed_time).as_secs_f32() / step.as_secs_f32();
+                State::with_mut(|x| x.fixed_update_alpha = alpha);
+            }
+            State::with_mut(|x| {
+                let dt = x.time_state.last_update_time();
+                x.input_state.axes.smooth(dt);
+            });
             game.update();
             let mut is_redraw = false;
-            // TODO: is this loop the cause of bad VSync?
-            loop {
-                match event_rx.try_recv() {
-                    Ok(event) => {
-                        if Self::handle_event(&mut game, event, &feedback_tx) {
-                            return;
+            State::with_mut(|x| x.input_state.mouse_path.clear());
+            if let Some(replay) = &mut replay {
+                let now = State::elapsed();
+                while let Some(event) = replay.pop_due(now) {
+                    let event = match event {
+                        Replayed::Input(event) => event,
+                        Replayed::ControllerAxis(controller, axis, value) => {
+                            State::with_mut(|x| {
+                                x.input_state.axes.set_raw(controller, axis, value);
+                                x.input_state.last_input = Instant::now();
+                            });
+                            continue;
+                        }
+                    };
+                    State::with_mut(|x| {
+                        x.input_state.apply(&event);
+                        x.trace_pointer(&event);
+                        if let InputEvent::MouseMove(..) = event {
+                            x.hover_cursor = None;
                         }
+                    });
+                    game.input(event);
+                }
+            }
+            // Only what was queued before this started, as handling an event
+            // can queue more
+            let synthetic = State::with_mut(|x| std::mem::take(&mut x.synthetic_input));
+            for event in synthetic {
+                State::with_mut(|x| {
+                    x.input_state.apply(&event);
+                    x.trace_pointer(&event);
+                });
+                game.input(event);
+            }
+            // Draining the events is bounded by the size of the event
+            // queue, so it can't hold up a frame for long.
+            let mut pending = Vec::new();
+            let disconnected = loop {
+                match event_rx.try_recv() {
+                    Ok(event) => pending.push(event),
+                    Err(TryRecvError::Empty) => break false,
+                    Err(TryRecvError::Disconnected) => break true,
+                }
+            };
+            let mut pending = pending.into_iter().peekable();
+            while let Some(event) = pending.next() {
+                // A move followed straight away by another one only updates
+                // the mouse state, so widgets don't redo their hover
+                // handling for positions that are already stale. Anything
+                // else in between, like a click, ends the run.
+                let coalesce = config.coalesce_mouse_motion
+                    && replay.is_none()
+                    && Self::is_mouse_motion(&event)
+                    && pending.peek().map_or(false, Self::is_mouse_motion);
+                if coalesce {
+                    if let Event::Sdl2Event(event, time) = &event {
+                        State::with_mut(|x| {
+                            let timestamp = time.saturating_duration_since(x.time_state.start());
+                            let r = x.input_state.handle_event(event, *time, timestamp);
+                            if let Some(EventHandleResult::Input(event)) = r {
+                                x.trace_pointer(&event);
+                                // Replays don't coalesce, so the whole path
+                                // comes back move by move
+                                if let Some(r) = &mut recorder {
+                                    if let Err(e) = r.record(event.time(), &event) {
+                                        eprintln!("Failed to record input event: {}", e);
+                                    }
+                                }
+                            }
+                        });
                     }
-                    Err(e) => match e {
-                        TryRecvError::Empty => break,
-                        TryRecvError::Disconnected => return,
-                    },
+                    continue;
+                }
+                if Self::handle_event(
+                    &mut game,
+                    event,
+                    &feedback_tx,
+                    &mut recorder,
+                    replay.is_some(),
+                    config.exit_key,
+                ) {
+                    return;
                 }
             }
+            if disconnected {
+                return;
+            }
+            game.late_update();
+            State::sync_cursor();
+            if let Some(e) = State::with_mut(|x| x.crash.take()) {
+                Self::handle_event(
+                    &mut game,
+                    Event::Crash(e),
+                    &feedback_tx,
+                    &mut recorder,
+                    replay.is_some(),
+                    config.exit_key,
+                );
+                return;
+            }
+            if State::with_mut(|x| std::mem::take(&mut x.exit_requested)) {
+                Self::exit(&mut game, &feedback_tx);
+                return;
+            }
+            if Self::flush_feedback(&feedback_tx) {
+                return;
+            }
+            let target_frame_time = match idle_frame_time {
+                Some((frame_time, after)) if State::with(|x| x.input_state.idle_time()) > after => {
+                    frame_time
+                }
+                _ => match config.frame_rate {
+                    Some(fps) => Duration::from_secs_f64(1.0 / fps),
+                    None => Duration::from_nanos(display_frame_time.load(Ordering::Relaxed)),
+                },
+            };
             let frame_time = last_frame.elapsed();
-            if frame_time > target_frame_time {
-                last_frame = Instant::now() - (frame_time - target_frame_time);
+            // Frames drawn while the last one hasn't been presented yet would
+            // be dropped, and with vsync that made frames reach the screen
+            // at uneven intervals. Waiting for the present instead lines the
+            // frames up with the display's refresh. Once the next frame is
+            // due, the frame policy decides whether to keep waiting.
+            let ready = !frame_pending.is_set()
+                || frame_time > target_frame_time
+                    && match config.frame_policy {
+                        FramePolicy::DropFrame => true,
+                        FramePolicy::BlockWithTimeout(timeout) => {
+                            frame_pending.wait(timeout);
+                            true
+                        }
+                    };
+            State::with_mut(|x| {
+                if x.redraw_at.map_or(false, |t| t <= x.time_state.elapsed()) {
+                    x.redraw_at = None;
+                    x.redraw_requested = true;
+                }
+            });
+            let redraw_requested =
+                ready && State::with_mut(|x| std::mem::take(&mut x.redraw_requested));
+            if ready && (frame_time > target_frame_time || redraw_requested) {
+                // Carry over how late this frame is to keep the pace, but not
+                // so much that a long stall results in a burst of frames.
+                let lag = frame_time
+                    .checked_sub(target_frame_time)
+                    .unwrap_or(Duration::ZERO)
+                    .min(config.max_frame_lag);
+                last_frame = Instant::now() - lag;
                 is_redraw = true;
-                let mut rec = PictureRecorder::new();
-                let bounds = Rect::from_size(State::with(|x| {
-                    let w = x.input_state.window_size;
-                    (w.width, w.height)
-                }));
-                let canvas = rec.begin_recording(bounds, None);
-                game.draw(canvas);
-                if let Err(why) = pic_tx.try_send(
-                    rec.finish_recording_as_picture(None)
-                        .expect("Failed to finish recording picture while rendering"),
-                ) {
-                    match why {
-                        // Skip any unsent frames, just in case the renderer
-                        // fails to catch up, and to prevent lockups.
-                        TrySendError::Full(_) => {}
-                        TrySendError::Disconnected(_) => {
+                State::with_mut(|x| {
+                    x.bounds.next_frame();
+                    x.focus.next_frame();
+                    x.accessibility.next_frame();
+                    if x.debug.profile_widgets {
+                        x.profiler.next_frame();
+                    }
+                    if x.debug.hit_test && x.hit_test.next_frame() {
+                        x.redraw_requested = true;
+                    }
+                });
+                game.pre_draw();
+                let filter = game.post_process();
+                let frame = State::with_mut(|x| {
+                    let i = &x.input_state;
+                    let letterbox = i
+                        .design_resolution
+                        .map(|d| (i.canvas_matrix(), Rect::from_size(d)));
+                    Frame {
+                        window_size: i.window_size,
+                        letterbox,
+                        dump: x.frame_dump.take(),
+                        clear: config.clear,
+                        filter,
+                    }
+                });
+                // Set before sending, since the main thread may present the
+                // picture before this thread gets to run again.
+                frame_pending.set();
+                match game.snapshot() {
+                    Some(snapshot) => {
+                        let overlay = frame.record_overlay(&mut picture_recorder, |canvas| {
+                            game.draw_overlay(canvas)
+                        });
+                        let worker = draw_worker.get_or_insert_with(|| {
+                            Self::spawn_draw_worker(pic_tx.clone(), config.high_priority_threads)
+                        });
+                        // The worker usually gets a new snapshot only once
+                        // the picture of the last one was presented, but
+                        // when the frame policy gave up waiting it may still
+                        // be drawing, and the snapshot is dropped instead.
+                        match worker.try_send((snapshot, overlay, frame)) {
+                            Ok(()) | Err(TrySendError::Full(_)) => {}
+                            Err(TrySendError::Disconnected(_)) => return,
+                        }
+                    }
+                    None => {
+                        let pic = frame.record(&mut picture_recorder, |canvas| {
+                            frame.filtered(canvas, |canvas| game.draw(canvas));
+                            game.draw_overlay(canvas);
+                        });
+                        if !Self::send_picture(&pic_tx, pic) {
                             panic!("Failed to send canvas to draw thread (disconnected channel)")
                         }
                     }
@@ -305,29 +1810,143 @@ impl Runner {
         }
     }
 
+    /// Send a frame to the main thread, returning `false` if it is gone.
+    /// Any waiting for the main thread was already done according to the
+    /// frame policy, so a frame it still has no room for is dropped.
+    fn send_picture(pic_tx: &SyncSender<Picture>, pic: Picture) -> bool {
+        match pic_tx.try_send(pic) {
+            Ok(()) | Err(TrySendError::Full(_)) => true,
+            Err(TrySendError::Disconnected(_)) => false,
+        }
+    }
+
+    /// Start the thread that draws [`DrawSnapshot`]s, see [`Game::snapshot`]
+    fn spawn_draw_worker(
+        pic_tx: SyncSender<Picture>,
+        high_priority: bool,
+    ) -> SyncSender<(Box<dyn DrawSnapshot>, Picture, Frame)> {
+        let (tx, rx) = sync_channel::<(Box<dyn DrawSnapshot>, Picture, Frame)>(1);
+        let worker = ThreadBuilder::new().name("tachibana-draw".to_owned());
+        let spawned = worker.spawn(move || {
+            if high_priority {
+                Self::raise_priority();
+            }
+            let mut recorder = PictureRecorder::new();
+            for (snapshot, overlay, frame) in rx {
+                let pic = frame.record(&mut recorder, |canvas| {
+                    frame.filtered(canvas, |canvas| snapshot.draw(canvas));
+                    canvas.draw_picture(&overlay, None, None);
+                });
+                // Fails when the main thread is gone, and then the game
+                // thread stops sending snapshots too.
+                Self::send_picture(&pic_tx, pic);
+            }
+        });
+        spawned.expect("Failed to spawn draw thread");
+        tx
+    }
+
+    /// Ask the OS to schedule the current thread ahead of others. This may
+    /// take privileges the process doesn't have, in which case it carries
+    /// on at the normal priority.
+    fn raise_priority() {
+        use sdl2::sys::{SDL_GetError, SDL_SetThreadPriority, SDL_ThreadPriority};
+        // SAFETY: SDL_SetThreadPriority only affects the calling thread, and
+        // SDL_GetError returns a valid C string owned by SDL.
+        unsafe {
+            if SDL_SetThreadPriority(SDL_ThreadPriority::SDL_THREAD_PRIORITY_HIGH) != 0 {
+                let error = std::ffi::CStr::from_ptr(SDL_GetError());
+                eprintln!(
+                    "Failed to raise thread priority: {}",
+                    error.to_string_lossy()
+                );
+            }
+        }
+    }
+
+    /// When an SDL event happened, by its timestamp counted from when SDL
+    /// was initialized. It can't be later than now.
+    fn event_instant(event: &Sdl2Event, sdl_epoch: Instant) -> Instant {
+        let at = sdl_epoch + Duration::from_millis(event.get_timestamp().into());
+        at.min(Instant::now())
+    }
+
+    fn is_mouse_motion(event: &Event) -> bool {
+        matches!(event, Event::Sdl2Event(Sdl2Event::MouseMotion { .. }, _))
+    }
+
     fn handle_event(
         game: &mut impl Game,
         event: Event,
         feedback_tx: &SyncSender<FeedbackEvent>,
+        recorder: &mut Option<Recorder>,
+        replaying: bool,
+        exit_key: Option<Keycode>,
     ) -> bool {
         match event {
-            Event::Sdl2Event(event) => {
-                if let Some(r) = State::with_mut(|x| x.input_state.handle_event(&event)) {
+            Event::Sdl2Event(event, time) => {
+                // While replaying, only window events are taken from SDL, so
+                // that the real mouse and keyboard cannot interfere.
+                if replaying && !matches!(event, Sdl2Event::Quit { .. } | Sdl2Event::Window { .. })
+                {
+                    return false;
+                }
+                let old_size = State::with(|x| x.input_state.canvas_size());
+                let timestamp =
+                    State::with(|x| time.saturating_duration_since(x.time_state.start()));
+                let r = State::with_mut(|x| x.input_state.handle_event(&event, time, timestamp));
+                // Axes don't make events, so they are recorded on their own
+                if let (
+                    Some(r),
+                    Sdl2Event::ControllerAxisMotion {
+                        which, axis, value, ..
+                    },
+                ) = (recorder.as_mut(), &event)
+                {
+                    if let Err(e) = r.record_axis(timestamp, *which, *axis, *value) {
+                        eprintln!("Failed to record input event: {}", e);
+                    }
+                }
+                if let Some(r) = r {
                     match r {
-                        EventHandleResult::Input(event) => game.input(event),
-                        EventHandleResult::Resized(size) => {
-                            game.set_size(Size::new(size.width, size.height))
+                        EventHandleResult::Input(event) => {
+                            if let Some(r) = recorder {
+                                if let Err(e) = r.record(event.time(), &event) {
+                                    eprintln!("Failed to record input event: {}", e);
+                                }
+                            }
+                            State::with_mut(|x| {
+                                x.trace_pointer(&event);
+                                if let InputEvent::MouseMove(..) = event {
+                                    x.hover_cursor = None;
+                                }
+                            });
+                            let exit = matches!(event, InputEvent::KeyDown(key, _) if Some(key) == exit_key);
+                            // The game gets the first chance at the exit key,
+                            // like a menu that closes on escape
+                            if !game.input(event) && exit {
+                                return Self::request_close(game, feedback_tx);
+                            }
                         }
-                        EventHandleResult::Exit => {
-                            game.close();
-                            feedback_tx
-                                .send(FeedbackEvent::Exit)
-                                .expect("Failed to send feedback event to draw thread");
-                            return true;
+                        EventHandleResult::Resized(size) => {
+                            game.set_size(size);
+                            game.on_resize(old_size, size);
                         }
+                        EventHandleResult::Exit => return Self::request_close(game, feedback_tx),
                     }
                 }
             }
+            Event::Vsync(vsync) => State::with_mut(|x| x.vsync = vsync),
+            Event::ScaleFactor(scale) => {
+                let size = State::with_mut(|x| {
+                    x.input_state.scale_factor = scale;
+                    x.redraw_requested = true;
+                    x.input_state.canvas_size()
+                });
+                // The logical size stays the same, but laying out again lets
+                // widgets that care about pixels pick up the new scale
+                game.set_size(size);
+            }
             Event::Crash(e) => {
                 game.crash(e);
                 feedback_tx
@@ -339,4 +1958,21 @@ impl Runner {
 
         false
     }
+
+    /// Close the app unless the game vetoes it. Returns whether it closed.
+    fn request_close(game: &mut impl Game, feedback_tx: &SyncSender<FeedbackEvent>) -> bool {
+        if game.on_close_requested() {
+            Self::exit(game, feedback_tx);
+            true
+        } else {
+            false
+        }
+    }
+
+    fn exit(game: &mut impl Game, feedback_tx: &SyncSender<FeedbackEvent>) {
+        game.close();
+        feedback_tx
+            .send(FeedbackEvent::Exit)
+            .expect("Failed to send feedback event to draw thread");
+    }
 }
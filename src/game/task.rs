@@ -0,0 +1,141 @@
+use std::any::Any;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::mpsc::{channel, Receiver, Sender, TryRecvError};
+use std::sync::{Arc, Mutex};
+use std::thread::{Builder as ThreadBuilder, JoinHandle};
+
+type Job = Box<dyn FnOnce() + Send>;
+
+/// A fixed set of worker threads running tasks off the game thread. Spawn
+/// tasks through [`State::spawn_task`](super::State::spawn_task).
+pub struct TaskPool {
+    sender: Option<Sender<Job>>,
+    workers: Vec<JoinHandle<()>>,
+}
+
+impl TaskPool {
+    pub fn new(threads: usize) -> Self {
+        let (sender, receiver) = channel::<Job>();
+        let receiver = Arc::new(Mutex::new(receiver));
+        let workers = (0..threads.max(1))
+            .map(|i| {
+                let receiver = receiver.clone();
+                ThreadBuilder::new()
+                    .name(format!("tachibana-task-{}", i))
+                    .spawn(move || loop {
+                        // The lock is only held while waiting for a job, not
+                        // while running it.
+                        let job = receiver.lock().unwrap().recv();
+                        match job {
+                            Ok(job) => job(),
+                            Err(_) => break,
+                        }
+                    })
+                    .expect("Failed to spawn task worker thread")
+            })
+            .collect();
+        Self {
+            sender: Some(sender),
+            workers,
+        }
+    }
+
+    pub fn spawn<F, T>(&self, f: F) -> TaskHandle<T>
+    where
+        F: 'static + Send + FnOnce() -> T,
+        T: 'static + Send,
+    {
+        let (tx, rx) = channel();
+        let job = Box::new(move || {
+            // A panicking task is reported through its handle rather than
+            // taking the worker down with it.
+            let _ = tx.send(catch_unwind(AssertUnwindSafe(f)));
+        });
+        if let Some(sender) = &self.sender {
+            let _ = sender.send(job);
+        }
+        TaskHandle {
+            receiver: rx,
+            result: TaskResult::Running,
+        }
+    }
+
+    /// Stop accepting tasks and wait for the workers to finish the ones
+    /// already queued.
+    pub fn shutdown(&mut self) {
+        self.sender = None;
+        for worker in self.workers.drain(..) {
+            let _ = worker.join();
+        }
+    }
+}
+
+impl Drop for TaskPool {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+
+/// The pending result of a task spawned with
+/// [`State::spawn_task`](super::State::spawn_task), meant to be polled from
+/// the update loop.
+pub struct TaskHandle<T> {
+    receiver: Receiver<Result<T, Box<dyn Any + Send>>>,
+    result: TaskResult<T>,
+}
+
+enum TaskResult<T> {
+    Running,
+    Finished(Result<T, TaskError>),
+    /// The result was taken by [`TaskHandle::poll`]. The channel is closed
+    /// by then, which must not be mistaken for the task being cancelled.
+    Taken,
+}
+
+impl<T> TaskHandle<T> {
+    fn receive(&mut self) {
+        if let TaskResult::Running = self.result {
+            self.result = match self.receiver.try_recv() {
+                Ok(Ok(value)) => TaskResult::Finished(Ok(value)),
+                Ok(Err(_)) => TaskResult::Finished(Err(TaskError::Panicked)),
+                Err(TryRecvError::Empty) => TaskResult::Running,
+                Err(TryRecvError::Disconnected) => TaskResult::Finished(Err(TaskError::Cancelled)),
+            };
+        }
+    }
+
+    /// Whether the task has finished, successfully or not, including when
+    /// its result was already taken
+    pub fn is_finished(&mut self) -> bool {
+        self.receive();
+        !matches!(self.result, TaskResult::Running)
+    }
+
+    /// Whether the result was taken by [`poll`](Self::poll)
+    pub fn is_taken(&self) -> bool {
+        matches!(self.result, TaskResult::Taken)
+    }
+
+    /// Take the result if the task has finished. Returns `None` while it is
+    /// still running, and after the result has been taken, which
+    /// [`is_taken`](Self::is_taken) tells apart.
+    pub fn poll(&mut self) -> Option<Result<T, TaskError>> {
+        self.receive();
+        match std::mem::replace(&mut self.result, TaskResult::Taken) {
+            TaskResult::Finished(result) => Some(result),
+            TaskResult::Running => {
+                self.result = TaskResult::Running;
+                None
+            }
+            TaskResult::Taken => None,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum TaskError {
+    /// The task panicked
+    Panicked,
+    /// The task was dropped before running, because the pool shut down
+    Cancelled,
+}
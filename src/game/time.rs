@@ -1,13 +1,29 @@
+use std::collections::VecDeque;
 use std::time::{Duration, Instant};
 
+/// How the times between recent updates are spread, to tell smooth frame
+/// pacing from jittery pacing with the same average frame rate
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub struct FrameStats {
+    pub mean: Duration,
+    pub std_dev: Duration,
+    pub max: Duration,
+    /// How many updates the statistics cover
+    pub samples: usize,
+}
+
 pub struct TimeState {
     start: Instant,
     last_update: Instant,
     elapsed: Duration,
     last_update_time: Duration,
+    history: VecDeque<Duration>,
 }
 
 impl TimeState {
+    /// How many of the last update times are kept for [`stats`](Self::stats)
+    pub const HISTORY: usize = 120;
+
     pub fn new() -> Self {
         let now = Instant::now();
         Self {
@@ -15,6 +31,7 @@ impl TimeState {
             last_update: now,
             elapsed: Duration::ZERO,
             last_update_time: Duration::ZERO,
+            history: VecDeque::with_capacity(Self::HISTORY),
         }
     }
 
@@ -23,6 +40,10 @@ impl TimeState {
         self.last_update_time = now.duration_since(self.last_update);
         self.last_update = now;
         self.elapsed = now.duration_since(self.start);
+        if self.history.len() == Self::HISTORY {
+            self.history.pop_front();
+        }
+        self.history.push_back(self.last_update_time);
     }
 
     pub fn start(&self) -> Instant {
@@ -40,6 +61,22 @@ impl TimeState {
     pub fn elapsed(&self) -> Duration {
         self.elapsed
     }
+
+    pub fn stats(&self) -> FrameStats {
+        let samples = self.history.len();
+        if samples == 0 {
+            return FrameStats::default();
+        }
+        let times = self.history.iter().map(Duration::as_secs_f64);
+        let mean = times.clone().sum::<f64>() / samples as f64;
+        let variance = times.map(|t| (t - mean).powi(2)).sum::<f64>() / samples as f64;
+        FrameStats {
+            mean: Duration::from_secs_f64(mean),
+            std_dev: Duration::from_secs_f64(variance.sqrt()),
+            max: self.history.iter().copied().max().unwrap_or_default(),
+            samples,
+        }
+    }
 }
 
 impl Default for TimeState {
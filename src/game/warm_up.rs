@@ -0,0 +1,94 @@
+use crate::skia::{
+    gradient_shader, image_filters, paint::Style, BlendMode, Canvas, Color, Font, ImageInfo, Paint,
+    Path, RRect, Rect, TileMode,
+};
+
+/// Draw a variety of paints, shapes and effects once, so the GPU pipelines
+/// they need are compiled before the first real frame instead of stalling it.
+///
+/// Drawing happens on an offscreen surface made to match the canvas where
+/// possible, and on a clipped corner of the canvas otherwise, which the
+/// caller should clear afterwards.
+pub(crate) fn warm_up(canvas: &mut Canvas) {
+    let info = ImageInfo::new_n32_premul((128, 128), None);
+    match canvas.new_surface(&info, None) {
+        Some(mut surface) => {
+            let offscreen = surface.canvas();
+            draw(offscreen);
+            offscreen.flush();
+        }
+        None => {
+            canvas.save();
+            canvas.clip_rect(Rect::from_wh(128.0, 128.0), None, None);
+            draw(canvas);
+            canvas.restore();
+            canvas.flush();
+        }
+    }
+}
+
+fn draw(canvas: &mut Canvas) {
+    let rect = Rect::from_xywh(8.0, 8.0, 48.0, 32.0);
+    let rrect = RRect::new_rect_xy(rect, 6.0, 6.0);
+    let mut path = Path::new();
+    path.move_to((10.0, 60.0));
+    path.cubic_to((30.0, 40.0), (60.0, 90.0), (90.0, 60.0));
+    path.close();
+
+    for &anti_alias in &[false, true] {
+        for &(style, width) in &[
+            (Style::Fill, 0.0),
+            (Style::Stroke, 0.0),
+            (Style::Stroke, 3.0),
+        ] {
+            let mut paint = Paint::default();
+            paint.set_anti_alias(anti_alias);
+            paint.set_style(style);
+            paint.set_stroke_width(width);
+            paint.set_color(Color::WHITE);
+            canvas.draw_rect(rect, &paint);
+            canvas.draw_rrect(rrect, &paint);
+            canvas.draw_circle((64.0, 64.0), 20.0, &paint);
+            canvas.draw_path(&path, &paint);
+            canvas.draw_line((0.0, 0.0), (100.0, 40.0), &paint);
+        }
+    }
+
+    let mut text = Paint::default();
+    text.set_anti_alias(true);
+    canvas.draw_str("Tachibana", (4.0, 100.0), &Font::default(), &text);
+
+    let colors = [Color::RED, Color::BLUE];
+    let mut gradient = Paint::default();
+    gradient.set_shader(gradient_shader::linear(
+        ((0.0, 0.0), (128.0, 0.0)),
+        &colors[..],
+        None,
+        TileMode::Clamp,
+        None,
+        None,
+    ));
+    canvas.draw_rect(Rect::from_wh(128.0, 16.0), &gradient);
+    gradient.set_shader(gradient_shader::radial(
+        (64.0, 64.0),
+        32.0,
+        &colors[..],
+        None,
+        TileMode::Clamp,
+        None,
+        None,
+    ));
+    canvas.draw_circle((64.0, 64.0), 32.0, &gradient);
+
+    let mut blend = Paint::default();
+    blend.set_blend_mode(BlendMode::Multiply);
+    canvas.draw_rect(rect, &blend);
+
+    // Layers with and without filters, as used by layers and backdrops
+    canvas.save_layer_alpha(rect, 128);
+    canvas.draw_rect(rect, &text);
+    canvas.restore();
+    let mut filtered = Paint::default();
+    filtered.set_image_filter(image_filters::blur((4.0, 4.0), None, None, None));
+    canvas.draw_rect(rect, &filtered);
+}
@@ -8,8 +8,10 @@ pub mod utils;
 pub use skulpin_renderer::skia_safe as skia;
 
 pub mod prelude {
+    pub use crate::forward_widget;
     pub use crate::framework::{
         music::Music,
+        sound_fx::SoundFx,
         widgets::{LayoutDimension, LayoutSize, Widget, Wrap, WrapState, Wrappable},
     };
     pub use crate::game;
@@ -1,3 +1,5 @@
+pub mod settings;
+
 use crate::skia::{
     scalar, Color4f, ColorSpace, IRect, ISize, Paint, PaintStyle, Point, Rect, Size,
 };
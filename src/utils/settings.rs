@@ -0,0 +1,107 @@
+//! Saving and loading user settings between runs.
+//!
+//! Settings are stored as TOML in the platform's config directory, under a
+//! folder named after the app, for example
+//! `~/.config/<app>/settings.toml` on Linux.
+
+use core::fmt::{Display, Formatter, Result as FmtResult};
+use std::error::Error as StdError;
+use std::fs;
+use std::io::{Error as IoError, ErrorKind};
+use std::path::PathBuf;
+
+use serde::{de::DeserializeOwned, Serialize};
+
+const FILE_NAME: &str = "settings.toml";
+
+#[derive(Debug)]
+pub enum SettingsError {
+    /// The platform has no config directory
+    NoConfigDir,
+    IoError(IoError),
+    ParseError(toml::de::Error),
+    SerializeError(toml::ser::Error),
+}
+
+impl Display for SettingsError {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match self {
+            SettingsError::NoConfigDir => write!(f, "No config directory found"),
+            SettingsError::IoError(e) => e.fmt(f),
+            SettingsError::ParseError(e) => e.fmt(f),
+            SettingsError::SerializeError(e) => e.fmt(f),
+        }
+    }
+}
+
+impl StdError for SettingsError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match self {
+            SettingsError::NoConfigDir => None,
+            SettingsError::IoError(e) => Some(e),
+            SettingsError::ParseError(e) => Some(e),
+            SettingsError::SerializeError(e) => Some(e),
+        }
+    }
+}
+
+impl From<IoError> for SettingsError {
+    fn from(err: IoError) -> Self {
+        Self::IoError(err)
+    }
+}
+
+impl From<toml::de::Error> for SettingsError {
+    fn from(err: toml::de::Error) -> Self {
+        Self::ParseError(err)
+    }
+}
+
+impl From<toml::ser::Error> for SettingsError {
+    fn from(err: toml::ser::Error) -> Self {
+        Self::SerializeError(err)
+    }
+}
+
+/// Where the settings for the given app are stored
+pub fn path(app_name: &str) -> Result<PathBuf, SettingsError> {
+    let mut path = dirs::config_dir().ok_or(SettingsError::NoConfigDir)?;
+    path.push(app_name);
+    path.push(FILE_NAME);
+    Ok(path)
+}
+
+/// Load the saved settings, or `None` if none were saved yet
+pub fn try_load<T: DeserializeOwned>(app_name: &str) -> Result<Option<T>, SettingsError> {
+    match fs::read_to_string(path(app_name)?) {
+        Ok(s) => Ok(Some(toml::from_str(&s)?)),
+        Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Load the saved settings, falling back to the defaults if none were saved
+/// yet or they can't be read. Errors are logged rather than returned, since
+/// there is rarely anything better to do on startup than using the defaults.
+pub fn load<T: DeserializeOwned + Default>(app_name: &str) -> T {
+    try_load(app_name)
+        .unwrap_or_else(|e| {
+            eprintln!("Failed to load settings, using the defaults: {}", e);
+            None
+        })
+        .unwrap_or_default()
+}
+
+/// Save the settings, creating the config directory if needed. The file is
+/// written next to the old one and then moved over it, so a crash midway
+/// doesn't lose the previous settings.
+pub fn save<T: Serialize>(app_name: &str, settings: &T) -> Result<(), SettingsError> {
+    let path = path(app_name)?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let temp = path.with_extension("toml.tmp");
+    fs::write(&temp, toml::to_string_pretty(settings)?)?;
+    fs::rename(&temp, &path)?;
+    Ok(())
+}